@@ -0,0 +1,333 @@
+use crate::prime_pi;
+use crate::sieve::sieve_primes;
+
+const EULER_GAMMA: f64 = 0.577_215_664_901_532_9;
+
+/// Chebyshev's θ(x), the sum of `ln p` over all primes `p <= x`.
+pub fn chebyshev_theta(x: u64) -> f64 {
+    sieve_primes(x).iter().map(|&p| (p as f64).ln()).sum()
+}
+
+/// Chebyshev's ψ(x), the sum of `ln p` over all prime powers `p^k <= x`.
+pub fn chebyshev_psi(x: u64) -> f64 {
+    sieve_primes(x)
+        .iter()
+        .map(|&p| {
+            let mut k = 0u32;
+            let mut q = p;
+
+            loop {
+                k += 1;
+
+                match q.checked_mul(p) {
+                    Some(next) if next <= x => q = next,
+                    _ => break,
+                }
+            }
+
+            k as f64 * (p as f64).ln()
+        })
+        .sum()
+}
+
+/// The sum of `1 / p` over all primes `p <= x`.
+pub fn sum_reciprocal_primes(x: u64) -> f64 {
+    // Summing the smallest terms first keeps the rounding error down.
+    sieve_primes(x).iter().rev().map(|&p| 1.0 / p as f64).sum()
+}
+
+/// The sum of `ln p / p` over all primes `p <= x`.
+pub fn sum_log_primes_over_primes(x: u64) -> f64 {
+    sieve_primes(x)
+        .iter()
+        .rev()
+        .map(|&p| (p as f64).ln() / p as f64)
+        .sum()
+}
+
+/// The logarithmic integral li(x), for `x >= 0`. NaN for negative or NaN `x`.
+pub fn log_integral(x: f64) -> f64 {
+    if x.is_nan() || x < 0.0 {
+        return f64::NAN;
+    }
+
+    if x == 0.0 {
+        return 0.0;
+    }
+
+    if x == 1.0 {
+        return f64::NEG_INFINITY;
+    }
+
+    // li(x) = Ei(ln x) = γ + ln|t| + Σ t^n / (n n!).
+    let t = x.ln();
+    let mut sum = 0.0;
+    let mut term = 1.0;
+    let mut n = 1.0;
+
+    loop {
+        term *= t / n;
+        let delta = term / n;
+        sum += delta;
+
+        if delta.abs() <= f64::EPSILON * sum.abs() {
+            break;
+        }
+
+        n += 1.0;
+    }
+
+    EULER_GAMMA + t.abs().ln() + sum
+}
+
+fn zeta(s: u32) -> f64 {
+    // Euler–Maclaurin summation with a fixed cutoff, accurate to f64 precision for s >= 2.
+    const N: f64 = 100.0;
+
+    let s_f = s as f64;
+    let mut sum = 0.0;
+
+    for n in (1..N as u32).rev() {
+        sum += (n as f64).powi(-(s as i32));
+    }
+
+    sum + N.powf(1.0 - s_f) / (s_f - 1.0) + N.powf(-s_f) / 2.0 + s_f * N.powf(-s_f - 1.0) / 12.0
+        - s_f * (s_f + 1.0) * (s_f + 2.0) * N.powf(-s_f - 3.0) / 720.0
+}
+
+/// Riemann's prime-counting function R(x), evaluated with the Gram series, for `x >= 0`. NaN
+/// for negative or NaN `x`.
+pub fn riemann_r(x: f64) -> f64 {
+    if x.is_nan() || x < 0.0 {
+        return f64::NAN;
+    }
+
+    if x == 0.0 {
+        return 0.0;
+    }
+
+    let t = x.ln();
+    let mut sum = 1.0;
+    let mut power = 1.0;
+    let mut k = 1;
+
+    loop {
+        power *= t / k as f64;
+        let delta = power / (k as f64 * zeta(k + 1));
+        sum += delta;
+
+        if delta.abs() <= f64::EPSILON * sum.abs() {
+            break;
+        }
+
+        k += 1;
+    }
+
+    sum
+}
+
+/// An approximation of π(x) by Riemann's R function.
+pub fn prime_pi_approx(x: u64) -> f64 {
+    if x < 2 {
+        return 0.0;
+    }
+
+    riemann_r(x as f64)
+}
+
+/// Rigorous bounds `(lower, upper)` with `lower <= π(x) <= upper`.
+pub fn prime_pi_bounds(x: u64) -> (u64, u64) {
+    // Dusart (2010): both bounds hold for x >= 355991. Below that, the exact count is cheap.
+    if x < 355_991 {
+        let pi = prime_pi(x);
+        return (pi, pi);
+    }
+
+    let x = x as f64;
+    let ln_x = x.ln();
+    let base = x / ln_x;
+
+    let lower = base * (1.0 + 1.0 / ln_x + 2.0 / (ln_x * ln_x));
+    let upper = base * (1.0 + 1.0 / ln_x + 2.51 / (ln_x * ln_x));
+
+    (lower.floor() as u64, upper.ceil() as u64)
+}
+
+const SMALL_PRIMES: [u64; 5] = [2, 3, 5, 7, 11];
+
+/// An estimate of `nth_prime(k)`, found by inverting Riemann's R function.
+pub fn nth_prime_estimate(k: usize) -> u64 {
+    if k < SMALL_PRIMES.len() {
+        return SMALL_PRIMES[k];
+    }
+
+    let n = (k + 1) as f64;
+    let ln_n = n.ln();
+    let ln_ln_n = ln_n.ln();
+
+    // Start from Cipolla's asymptotic expansion and refine with Newton's method, using
+    // R'(x) ~ 1 / ln x.
+    let mut x = n * (ln_n + ln_ln_n - 1.0 + (ln_ln_n - 2.0) / ln_n);
+
+    for _ in 0..4 {
+        x -= (riemann_r(x) - n) * x.ln();
+    }
+
+    x.round() as u64
+}
+
+/// Rigorous bounds `(lower, upper)` with `lower <= nth_prime(k) <= upper`.
+pub fn nth_prime_bounds(k: usize) -> (u64, u64) {
+    if k < SMALL_PRIMES.len() {
+        return (SMALL_PRIMES[k], SMALL_PRIMES[k]);
+    }
+
+    let n = (k + 1) as f64;
+    let ln_n = n.ln();
+    let ln_ln_n = ln_n.ln();
+
+    // Dusart (1999) for the lower bound and for the upper bound below n = 688383,
+    // Dusart (2010) above it.
+    let lower = n * (ln_n + ln_ln_n - 1.0 + (ln_ln_n - 2.1) / ln_n);
+    let upper = if n >= 688_383.0 {
+        n * (ln_n + ln_ln_n - 1.0 + (ln_ln_n - 2.0) / ln_n)
+    } else {
+        n * (ln_n + ln_ln_n)
+    };
+
+    (lower.floor() as u64, upper.ceil() as u64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn close(a: f64, b: f64) -> bool {
+        (a - b).abs() <= 1e-9 * b.abs().max(1.0)
+    }
+
+    #[test]
+    fn chebyshev_01() {
+        assert_eq!(chebyshev_theta(1), 0.0);
+        assert_eq!(chebyshev_psi(1), 0.0);
+
+        assert!(close(chebyshev_theta(10), 210f64.ln()));
+        assert!(close(chebyshev_psi(10), 2520f64.ln()));
+    }
+
+    #[test]
+    fn chebyshev_02() {
+        // ψ(x) ~ x and θ(x) <= ψ(x).
+        let theta = chebyshev_theta(1_000_000);
+        let psi = chebyshev_psi(1_000_000);
+
+        assert!(theta < psi);
+        assert!((theta - 998_484.175_025_634_2).abs() < 1e-3);
+        assert!((psi - 999_586.597_495_633).abs() < 1e-3);
+        assert!((psi / 1e6 - 1.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn mertens_01() {
+        assert_eq!(sum_reciprocal_primes(1), 0.0);
+        assert!(close(
+            sum_reciprocal_primes(5),
+            1.0 / 2.0 + 1.0 / 3.0 + 1.0 / 5.0
+        ));
+        assert!(close(
+            sum_log_primes_over_primes(3),
+            2f64.ln() / 2.0 + 3f64.ln() / 3.0
+        ));
+    }
+
+    #[test]
+    fn mertens_02() {
+        // Mertens' theorems: Σ 1/p - ln ln x -> M and Σ ln p / p - ln x -> -E.
+        const MEISSEL_MERTENS: f64 = 0.261_497_212_847_642_8;
+        const E: f64 = 1.332_582_275_733_220_8;
+
+        let x = 1_000_000u64;
+        let ln_x = (x as f64).ln();
+
+        assert!((sum_reciprocal_primes(x) - ln_x.ln() - MEISSEL_MERTENS).abs() < 1e-3);
+        assert!((sum_log_primes_over_primes(x) - ln_x + E).abs() < 1e-2);
+    }
+
+    #[test]
+    fn prime_pi_approx_01() {
+        assert!(close(log_integral(2.0), 1.045_163_780_117_49));
+        assert!(close(log_integral(1e6), 78_627.549_159_462_2));
+        assert!(close(log_integral(1e12), 37_607_950_280.804_9));
+
+        assert!(close(riemann_r(1e6), 78_527.399_429_127_7));
+        assert!(close(
+            prime_pi_approx(1_000_000_000_000),
+            37_607_910_542.225_9
+        ));
+        assert_eq!(prime_pi_approx(1), 0.0);
+
+        for x in [f64::NAN, -1.0, f64::NEG_INFINITY] {
+            assert!(log_integral(x).is_nan() && riemann_r(x).is_nan());
+        }
+
+        assert_eq!((log_integral(0.0), riemann_r(0.0)), (0.0, 0.0));
+    }
+
+    #[test]
+    fn prime_pi_bounds_01() {
+        for x in [
+            0,
+            1,
+            2,
+            100,
+            355_990,
+            355_991,
+            1_000_000,
+            123_456_789,
+            10_000_000_000,
+        ] {
+            let (lower, upper) = prime_pi_bounds(x);
+            let pi = prime_pi(x);
+
+            assert!(lower <= pi && pi <= upper);
+        }
+
+        let (lower, upper) = prime_pi_bounds(u64::MAX);
+
+        assert!(lower < upper);
+    }
+
+    #[test]
+    fn nth_prime_estimate_01() {
+        use crate::nth_prime;
+
+        for k in 0..2000 {
+            let (lower, upper) = nth_prime_bounds(k);
+            let p = nth_prime(k);
+
+            assert!(lower <= p && p <= upper);
+        }
+
+        assert_eq!(nth_prime_estimate(0), 2);
+
+        let estimate = nth_prime_estimate(999_999) as f64;
+
+        assert!((estimate / 15_485_863.0 - 1.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn nth_prime_bounds_01() {
+        // p_(10^9) = 22801763489 and p_(10^12) = 29996224275833.
+        let (lower, upper) = nth_prime_bounds(999_999_999);
+
+        assert!(lower <= 22_801_763_489 && 22_801_763_489 <= upper);
+
+        let (lower, upper) = nth_prime_bounds(999_999_999_999);
+
+        assert!(lower <= 29_996_224_275_833 && 29_996_224_275_833 <= upper);
+
+        let estimate = nth_prime_estimate(999_999_999_999) as f64;
+
+        assert!((estimate / 29_996_224_275_833.0 - 1.0).abs() < 1e-6);
+    }
+}
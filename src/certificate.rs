@@ -0,0 +1,545 @@
+use std::error::Error;
+use std::fmt;
+
+use num_bigint::BigUint;
+use num_integer::Integer;
+use num_traits::{One, Zero};
+
+#[cfg(feature = "zeroize")]
+use crate::factor::wipe_biguint;
+use crate::is_prime;
+
+// The version byte that starts every encoding.
+const ENCODING_VERSION: u8 = 1;
+
+const SMALL_TAG: u8 = 0;
+const POCKLINGTON_TAG: u8 = 1;
+// Reserved for elliptic curve (ECPP) nodes, which version 1 has no layout for yet.
+const ECPP_TAG: u8 = 2;
+
+// Deeper nesting than this is rejected when decoding rather than recursed into. Maurer's method
+// nests about log2(bits / 64) levels deep, so this is never reached by a real certificate.
+const MAX_DEPTH: usize = 64;
+
+/// A certificate that a number is prime, which [`verify`](PrimeCertificate::verify) checks
+/// without trusting whoever produced it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PrimeCertificate {
+    /// A prime below 2^64, which the deterministic Miller–Rabin test settles.
+    Small(u64),
+    /// Pocklington's criterion: `n − 1 = F R` where `F`, the part of `n − 1` made of the
+    /// certified primes `q` in `factors`, exceeds `sqrt(n)`, and the witness `a` has
+    /// `a^(n − 1) ≡ 1 (mod n)` and `gcd(a^((n − 1) / q) − 1, n) = 1` for every `q`.
+    /// Listing every prime factor of `n − 1` makes it a Pratt certificate.
+    Pocklington {
+        n: BigUint,
+        witness: BigUint,
+        factors: Vec<PrimeCertificate>,
+    },
+}
+
+impl PrimeCertificate {
+    /// The number certified prime.
+    pub fn n(&self) -> BigUint {
+        match self {
+            PrimeCertificate::Small(p) => BigUint::from(*p),
+            PrimeCertificate::Pocklington { n, .. } => n.clone(),
+        }
+    }
+
+    /// Whether the certificate, and every certificate nested in it, is valid.
+    pub fn verify(&self) -> bool {
+        match self {
+            PrimeCertificate::Small(p) => is_prime(*p),
+            PrimeCertificate::Pocklington {
+                n,
+                witness,
+                factors,
+            } => verify_pocklington(n, witness, factors),
+        }
+    }
+}
+
+impl PrimeCertificate {
+    /// The portable binary encoding: a version byte, 1, and then the certificate, each node a
+    /// tag byte followed by its fields. A [`Small`](PrimeCertificate::Small) node is tag 0 and
+    /// the prime as 8 little-endian bytes. A
+    /// [`Pocklington`](PrimeCertificate::Pocklington) node is tag 1, `n` and the witness each
+    /// as an LEB128 byte count and little-endian bytes, and the LEB128 count of factors
+    /// followed by their nodes. Tag 2 is reserved for ECPP nodes, which this version cannot
+    /// express, and decodes to [`CertificateError::UnsupportedNode`]. The JSON mapping is the
+    /// `serde` form.
+    pub fn encode(&self) -> Vec<u8> {
+        let mut bytes = vec![ENCODING_VERSION];
+
+        self.encode_into(&mut bytes);
+        bytes
+    }
+
+    fn encode_into(&self, bytes: &mut Vec<u8>) {
+        match self {
+            PrimeCertificate::Small(p) => {
+                bytes.push(SMALL_TAG);
+                bytes.extend(p.to_le_bytes());
+            }
+            PrimeCertificate::Pocklington {
+                n,
+                witness,
+                factors,
+            } => {
+                bytes.push(POCKLINGTON_TAG);
+
+                for value in [n, witness] {
+                    let digits = value.to_bytes_le();
+
+                    encode_leb128(digits.len() as u64, bytes);
+                    bytes.extend(digits);
+                }
+
+                encode_leb128(factors.len() as u64, bytes);
+
+                for factor in factors {
+                    factor.encode_into(bytes);
+                }
+            }
+        }
+    }
+
+    /// Decodes the form of [`encode`](PrimeCertificate::encode) without verifying it.
+    pub fn decode(bytes: &[u8]) -> Result<PrimeCertificate, CertificateError> {
+        let (&version, mut rest) = bytes.split_first().ok_or(CertificateError::Malformed)?;
+
+        if version != ENCODING_VERSION {
+            return Err(CertificateError::UnsupportedVersion(version));
+        }
+
+        let certificate = decode_node(&mut rest, 0)?;
+
+        if !rest.is_empty() {
+            return Err(CertificateError::Malformed);
+        }
+
+        Ok(certificate)
+    }
+}
+
+/// Decodes and verifies an encoded certificate, as produced by [`PrimeCertificate::encode`]
+/// anywhere, returning the number it proves prime.
+pub fn verify_encoded(bytes: &[u8]) -> Result<BigUint, CertificateError> {
+    let certificate = PrimeCertificate::decode(bytes)?;
+
+    if !certificate.verify() {
+        return Err(CertificateError::Invalid);
+    }
+
+    Ok(certificate.n())
+}
+
+/// The error returned when decoding or verifying an encoded [`PrimeCertificate`] fails.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum CertificateError {
+    /// The bytes are truncated, have trailing data, an unknown tag, or nest too deeply.
+    Malformed,
+    /// The encoding has a version this crate cannot read.
+    UnsupportedVersion(u8),
+    /// The encoding has a node with a reserved tag this crate cannot read, such as ECPP's.
+    UnsupportedNode(u8),
+    /// The certificate decodes but does not prove its number prime.
+    Invalid,
+}
+
+impl fmt::Display for CertificateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CertificateError::Malformed => write!(f, "malformed certificate encoding"),
+            CertificateError::UnsupportedVersion(v) => {
+                write!(f, "unsupported certificate encoding version {v}")
+            }
+            CertificateError::UnsupportedNode(tag) => {
+                write!(f, "unsupported certificate node tag {tag}")
+            }
+            CertificateError::Invalid => write!(f, "certificate does not verify"),
+        }
+    }
+}
+
+impl Error for CertificateError {}
+
+fn decode_node(bytes: &mut &[u8], depth: usize) -> Result<PrimeCertificate, CertificateError> {
+    if depth > MAX_DEPTH {
+        return Err(CertificateError::Malformed);
+    }
+
+    match take(bytes, 1)?[0] {
+        SMALL_TAG => {
+            let p = u64::from_le_bytes(take(bytes, 8)?.try_into().unwrap());
+
+            Ok(PrimeCertificate::Small(p))
+        }
+        POCKLINGTON_TAG => {
+            let mut value = || {
+                let len = decode_leb128(bytes)?;
+                let len = usize::try_from(len).map_err(|_| CertificateError::Malformed)?;
+
+                Ok(BigUint::from_bytes_le(take(bytes, len)?))
+            };
+
+            let n = value()?;
+            let witness = value()?;
+            let count = decode_leb128(bytes)?;
+
+            // Every node takes at least two bytes, which bounds the allocation.
+            if count > bytes.len() as u64 / 2 {
+                return Err(CertificateError::Malformed);
+            }
+
+            let factors = (0..count)
+                .map(|_| decode_node(bytes, depth + 1))
+                .collect::<Result<_, _>>()?;
+
+            Ok(PrimeCertificate::Pocklington {
+                n,
+                witness,
+                factors,
+            })
+        }
+        ECPP_TAG => Err(CertificateError::UnsupportedNode(ECPP_TAG)),
+        _ => Err(CertificateError::Malformed),
+    }
+}
+
+// Splits the first len bytes off.
+fn take<'a>(bytes: &mut &'a [u8], len: usize) -> Result<&'a [u8], CertificateError> {
+    if bytes.len() < len {
+        return Err(CertificateError::Malformed);
+    }
+
+    let (head, tail) = bytes.split_at(len);
+
+    *bytes = tail;
+    Ok(head)
+}
+
+fn encode_leb128(mut value: u64, bytes: &mut Vec<u8>) {
+    while value >= 0x80 {
+        bytes.push(value as u8 | 0x80);
+        value >>= 7;
+    }
+
+    bytes.push(value as u8);
+}
+
+fn decode_leb128(bytes: &mut &[u8]) -> Result<u64, CertificateError> {
+    let mut value = 0u64;
+
+    for shift in (0..64).step_by(7) {
+        let byte = take(bytes, 1)?[0];
+
+        value |= ((byte & 0x7f) as u64)
+            .checked_shl(shift)
+            .filter(|&bits| bits >> shift == (byte & 0x7f) as u64)
+            .ok_or(CertificateError::Malformed)?;
+
+        if byte & 0x80 == 0 {
+            return Ok(value);
+        }
+    }
+
+    Err(CertificateError::Malformed)
+}
+
+fn verify_pocklington(n: &BigUint, a: &BigUint, factors: &[PrimeCertificate]) -> bool {
+    if *n < BigUint::from(3u32) || n.is_even() {
+        return false;
+    }
+
+    let one = BigUint::one();
+    let n_minus_1 = n - &one;
+
+    if !a.modpow(&n_minus_1, n).is_one() {
+        return false;
+    }
+
+    let mut f = BigUint::one();
+    let mut seen = Vec::with_capacity(factors.len());
+
+    for certificate in factors {
+        let q = certificate.n();
+
+        if seen.contains(&q) || !n_minus_1.is_multiple_of(&q) || !certificate.verify() {
+            return false;
+        }
+
+        let test = a.modpow(&(&n_minus_1 / &q), n);
+
+        if test.is_zero() || !(test - &one).gcd(n).is_one() {
+            return false;
+        }
+
+        // All of q's multiplicity in n − 1 counts towards F.
+        let mut rest = &n_minus_1 / &q;
+
+        f *= &q;
+
+        while rest.is_multiple_of(&q) {
+            rest /= &q;
+            f *= &q;
+        }
+
+        seen.push(q);
+    }
+
+    &f * &f > *n
+}
+
+// Wipes every number in the certificate, leaving a certificate for 0 that no longer verifies.
+#[cfg(feature = "zeroize")]
+impl zeroize::Zeroize for PrimeCertificate {
+    fn zeroize(&mut self) {
+        match self {
+            PrimeCertificate::Small(p) => p.zeroize(),
+            PrimeCertificate::Pocklington {
+                n,
+                witness,
+                factors,
+            } => {
+                wipe_biguint(n);
+                wipe_biguint(witness);
+                factors.iter_mut().for_each(zeroize::Zeroize::zeroize);
+            }
+        }
+    }
+}
+
+// Serialized as the prime itself below 2^64, and otherwise as an object with `n` and `witness`
+// as decimal strings and the nested `factors`. Deserializing verifies the whole certificate.
+#[cfg(feature = "serde")]
+impl serde::Serialize for PrimeCertificate {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeStruct;
+
+        match self {
+            PrimeCertificate::Small(p) => serializer.serialize_u64(*p),
+            PrimeCertificate::Pocklington {
+                n,
+                witness,
+                factors,
+            } => {
+                let mut state = serializer.serialize_struct("PrimeCertificate", 3)?;
+
+                state.serialize_field("n", &n.to_string())?;
+                state.serialize_field("witness", &witness.to_string())?;
+                state.serialize_field("factors", factors)?;
+                state.end()
+            }
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for PrimeCertificate {
+    fn deserialize<D: serde::Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<PrimeCertificate, D::Error> {
+        let certificate = deserializer.deserialize_any(CertificateVisitor)?;
+
+        if !certificate.verify() {
+            return Err(serde::de::Error::custom(format!(
+                "the certificate for {} does not verify",
+                certificate.n()
+            )));
+        }
+
+        Ok(certificate)
+    }
+}
+
+#[cfg(feature = "serde")]
+struct CertificateVisitor;
+
+#[cfg(feature = "serde")]
+impl<'de> serde::de::Visitor<'de> for CertificateVisitor {
+    type Value = PrimeCertificate;
+
+    fn expecting(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "a prime below 2^64 or a Pocklington certificate")
+    }
+
+    fn visit_u64<E: serde::de::Error>(self, p: u64) -> Result<PrimeCertificate, E> {
+        Ok(PrimeCertificate::Small(p))
+    }
+
+    fn visit_map<A: serde::de::MapAccess<'de>>(
+        self,
+        mut map: A,
+    ) -> Result<PrimeCertificate, A::Error> {
+        use serde::de::Error;
+
+        let parse = |s: String| {
+            s.parse::<BigUint>()
+                .map_err(|_| A::Error::custom(format!("{s} is not a decimal integer")))
+        };
+
+        let (mut n, mut witness, mut factors) = (None, None, None);
+
+        while let Some(key) = map.next_key::<String>()? {
+            match key.as_str() {
+                "n" => n = Some(parse(map.next_value()?)?),
+                "witness" => witness = Some(parse(map.next_value()?)?),
+                "factors" => factors = Some(map.next_value()?),
+                _ => return Err(A::Error::unknown_field(&key, &["n", "witness", "factors"])),
+            }
+        }
+
+        Ok(PrimeCertificate::Pocklington {
+            n: n.ok_or_else(|| A::Error::missing_field("n"))?,
+            witness: witness.ok_or_else(|| A::Error::missing_field("witness"))?,
+            factors: factors.ok_or_else(|| A::Error::missing_field("factors"))?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 2^127 − 1 from 2^127 − 2 = 2 · 3^3 · 7^2 · 19 · 43 · 73 · 127 · 337 · 5419 · 92737 ·
+    // 649657 · 77158673929, where the primes from 127 up already exceed its square root.
+    fn mersenne_127() -> PrimeCertificate {
+        PrimeCertificate::Pocklington {
+            n: (BigUint::one() << 127) - 1u32,
+            witness: BigUint::from(3u32),
+            factors: [127, 337, 5419, 92_737, 649_657, 77_158_673_929]
+                .into_iter()
+                .map(PrimeCertificate::Small)
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn prime_certificate_01() {
+        assert!(mersenne_127().verify());
+        assert!(PrimeCertificate::Small(1_000_000_007).verify());
+        assert!(!PrimeCertificate::Small(1_000_000_007 * 3).verify());
+
+        let PrimeCertificate::Pocklington {
+            n,
+            witness,
+            factors,
+        } = mersenne_127()
+        else {
+            unreachable!()
+        };
+
+        // Too small a factored part, a wrong witness, a non-divisor, a repeated factor, or a
+        // composite n.
+        let broken = [
+            (n.clone(), witness.clone(), factors[..3].to_vec()),
+            (n.clone(), BigUint::one(), factors.clone()),
+            (
+                n.clone(),
+                witness.clone(),
+                [&factors[..], &[PrimeCertificate::Small(131)]].concat(),
+            ),
+            (
+                n.clone(),
+                witness.clone(),
+                [&factors[..], &[PrimeCertificate::Small(127)]].concat(),
+            ),
+            (&n + 2u32, witness.clone(), factors.clone()),
+        ];
+
+        for (n, witness, factors) in broken {
+            let certificate = PrimeCertificate::Pocklington {
+                n,
+                witness,
+                factors,
+            };
+
+            assert!(!certificate.verify());
+        }
+    }
+
+    #[test]
+    fn encode_01() {
+        let nested = PrimeCertificate::Pocklington {
+            n: (BigUint::one() << 127) - 1u32,
+            witness: BigUint::from(3u32),
+            factors: vec![PrimeCertificate::Small(2), mersenne_127()],
+        };
+
+        for certificate in [PrimeCertificate::Small(7), mersenne_127(), nested] {
+            let bytes = certificate.encode();
+
+            assert_eq!(PrimeCertificate::decode(&bytes), Ok(certificate.clone()));
+
+            // Every truncation fails to decode, as does trailing data.
+            for len in 0..bytes.len() {
+                assert_eq!(
+                    PrimeCertificate::decode(&bytes[..len]),
+                    Err(CertificateError::Malformed)
+                );
+            }
+
+            assert_eq!(
+                PrimeCertificate::decode(&[&bytes[..], &[0]].concat()),
+                Err(CertificateError::Malformed)
+            );
+        }
+
+        assert_eq!(
+            PrimeCertificate::Small(7).encode(),
+            [1, 0, 7, 0, 0, 0, 0, 0, 0, 0]
+        );
+        assert_eq!(
+            verify_encoded(&mersenne_127().encode()),
+            Ok((BigUint::one() << 127) - 1u32)
+        );
+        assert_eq!(
+            verify_encoded(&PrimeCertificate::Small(9).encode()),
+            Err(CertificateError::Invalid)
+        );
+        assert_eq!(
+            verify_encoded(&[2, 0]),
+            Err(CertificateError::UnsupportedVersion(2))
+        );
+        assert_eq!(verify_encoded(&[1, 5]), Err(CertificateError::Malformed));
+
+        // A Pocklington node for 7 whose only factor is a reserved ECPP node.
+        assert_eq!(
+            verify_encoded(&[1, 1, 1, 7, 1, 3, 1, 2, 0]),
+            Err(CertificateError::UnsupportedNode(2))
+        );
+
+        // Overlong LEB128 lengths, and nesting past the depth limit.
+        let overlong = [&[1, 1][..], &[0xff; 10], &[1]].concat();
+        let deep = [
+            &[1][..],
+            &[1, 0, 0, 1].repeat(100),
+            &[0, 2, 0, 0, 0, 0, 0, 0, 0],
+        ]
+        .concat();
+
+        assert_eq!(verify_encoded(&overlong), Err(CertificateError::Malformed));
+        assert_eq!(verify_encoded(&deep), Err(CertificateError::Malformed));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn prime_certificate_serde_01() {
+        let certificate = mersenne_127();
+        let json = serde_json::to_string(&certificate).unwrap();
+
+        assert!(json.starts_with(r#"{"n":"170141183460469231731687303715884105727","witness":"3""#));
+        assert_eq!(
+            serde_json::from_str::<PrimeCertificate>(&json).unwrap(),
+            certificate
+        );
+
+        // The witness 1 no longer proves anything.
+        let forged = json.replace(r#""witness":"3""#, r#""witness":"1""#);
+
+        assert!(serde_json::from_str::<PrimeCertificate>(&forged).is_err());
+        assert!(serde_json::from_str::<PrimeCertificate>("15").is_err());
+    }
+}
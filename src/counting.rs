@@ -0,0 +1,375 @@
+use std::iter::FusedIterator;
+use std::ops::{ControlFlow, Mul, Sub};
+
+use crate::sieve::{for_each_odd_prime, sieve_primes};
+use crate::{is_prime, nth_prime, Primes};
+
+/// Lucy_Hedgehog's method for summing a completely multiplicative weight `w(p)` over the primes
+/// `p <= n` in `O(n^(3/4))` time, where `prefix(v)` is the sum of `w(k)` for `2 <= k <= v`.
+fn lucy_hedgehog<T>(n: u64, prefix: impl Fn(u64) -> T, w: impl Fn(u64) -> T) -> T
+where
+    T: Copy + PartialEq + Sub<Output = T> + Mul<Output = T>,
+{
+    let r = n.isqrt() as usize;
+
+    // small[v] holds S(v) for v <= r, large[i] holds S(n / i) for i <= r.
+    let mut small: Vec<T> = (0..=r as u64).map(&prefix).collect();
+    let mut large: Vec<T> = (0..=r as u64)
+        .map(|i| prefix(n.checked_div(i).unwrap_or(0)))
+        .collect();
+
+    for p in 2..=r {
+        if small[p] == small[p - 1] {
+            continue;
+        }
+
+        let sp = small[p - 1];
+        let wp = w(p as u64);
+        let p2 = p * p;
+        let limit = r.min((n / p2 as u64) as usize);
+
+        for i in 1..=limit {
+            let d = i * p;
+            let v = if d <= r {
+                large[d]
+            } else {
+                small[(n / d as u64) as usize]
+            };
+
+            large[i] = large[i] - wp * (v - sp);
+        }
+
+        for v in (p2..=r).rev() {
+            small[v] = small[v] - wp * (small[v / p] - sp);
+        }
+    }
+
+    large[1]
+}
+
+/// The sum of all primes `<= n`, computed in sub-linear time.
+pub fn sum_primes(n: u64) -> u128 {
+    if n < 2 {
+        return 0;
+    }
+
+    lucy_hedgehog(
+        n,
+        |v| {
+            let v = v as u128;
+            (v * (v + 1) / 2).saturating_sub(1)
+        },
+        |p| p as u128,
+    )
+}
+
+/// The number of primes `<= x`, computed in sub-linear time.
+pub fn prime_pi(x: u64) -> u64 {
+    if x < 2 {
+        return 0;
+    }
+
+    lucy_hedgehog(x, |v| v.saturating_sub(1), |_| 1)
+}
+
+/// The index `k` such that `nth_prime(k) == p`, or `None` if `p` is not prime.
+pub fn prime_index(p: u64) -> Option<usize> {
+    if !is_prime(p) {
+        return None;
+    }
+
+    Some((prime_pi(p) - 1) as usize)
+}
+
+/// The number of twin prime pairs `(p, p + 2)` with `p + 2 <= x`: 35 below 1000, and 27412679
+/// below 10^10. Counted over a segmented sieve, in `O(sqrt(x))` memory.
+pub fn twin_prime_count(x: u64) -> u64 {
+    let mut count = 0;
+    let mut last = 0;
+
+    for_each_odd_prime(x, |p| {
+        if p - last == 2 {
+            count += 1;
+        }
+
+        last = p;
+        ControlFlow::<()>::Continue(())
+    });
+
+    count
+}
+
+/// The smaller prime of the `k`-th twin prime pair, counting from zero, so that
+/// `nth_twin_prime(0) == 3`. The segmented sieve is rerun over a range four times larger until
+/// the pair turns up.
+pub fn nth_twin_prime(k: usize) -> u64 {
+    let mut limit = 1 << 16;
+
+    loop {
+        let mut count = 0;
+        let mut last = 0;
+
+        let found = for_each_odd_prime(limit, |p| {
+            if p - last == 2 {
+                if count == k {
+                    return ControlFlow::Break(last);
+                }
+
+                count += 1;
+            }
+
+            last = p;
+            ControlFlow::Continue(())
+        });
+
+        if let Some(p) = found {
+            return p;
+        }
+
+        limit *= 4;
+    }
+}
+
+/// The first `count` Ramanujan primes.
+fn ramanujan_primes_list(count: usize) -> Vec<u64> {
+    if count == 0 {
+        return vec![];
+    }
+
+    // R_n < p_(3n) (Laishram, 2010), so every x with π(x) - π(x / 2) < count lies below it.
+    let limit = nth_prime(3 * count - 1);
+    let mut prime = vec![false; limit as usize + 1];
+
+    for p in sieve_primes(limit) {
+        prime[p as usize] = true;
+    }
+
+    // last[m] is the largest x <= limit with π(x) - π(x / 2) == m.
+    let mut last = vec![0; count];
+    let mut c = 0;
+
+    for x in 1..=limit as usize {
+        if prime[x] {
+            c += 1;
+        }
+
+        if x % 2 == 0 && prime[x / 2] {
+            c -= 1;
+        }
+
+        if c < count {
+            last[c] = x as u64;
+        }
+    }
+
+    let mut result = Vec::with_capacity(count);
+    let mut max = 0;
+
+    for &x in &last {
+        max = max.max(x);
+        result.push(max + 1);
+    }
+
+    result
+}
+
+pub struct RamanujanPrimes {
+    primes: Vec<u64>,
+    index: usize,
+}
+
+impl RamanujanPrimes {
+    pub fn new() -> RamanujanPrimes {
+        RamanujanPrimes {
+            primes: vec![],
+            index: 0,
+        }
+    }
+}
+
+impl Default for RamanujanPrimes {
+    fn default() -> RamanujanPrimes {
+        RamanujanPrimes::new()
+    }
+}
+
+impl Iterator for RamanujanPrimes {
+    type Item = u64;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.index >= self.primes.len() {
+            self.primes = ramanujan_primes_list((2 * self.primes.len()).max(64));
+        }
+
+        let prime = self.primes[self.index];
+        self.index += 1;
+
+        Some(prime)
+    }
+}
+
+impl FusedIterator for RamanujanPrimes {}
+
+pub fn ramanujan_primes() -> RamanujanPrimes {
+    RamanujanPrimes::new()
+}
+
+/// The `k`-th Ramanujan prime, counting from zero, so that `nth_ramanujan_prime(0) == 2`.
+pub fn nth_ramanujan_prime(k: usize) -> u64 {
+    ramanujan_primes_list(k + 1)[k]
+}
+
+/// The balanced primes in increasing order, from [`balanced_primes`].
+pub struct BalancedPrimes {
+    primes: Primes,
+    // Three consecutive primes, the middle one the candidate.
+    window: [u64; 3],
+}
+
+impl Iterator for BalancedPrimes {
+    type Item = u64;
+
+    fn next(&mut self) -> Option<u64> {
+        loop {
+            let [_, b, c] = self.window;
+
+            self.window = [b, c, self.primes.next().unwrap()];
+
+            let [a, b, c] = self.window;
+
+            if b - a == c - b {
+                return Some(b);
+            }
+        }
+    }
+}
+
+impl FusedIterator for BalancedPrimes {}
+
+/// The balanced primes, each the mean of the primes either side of it: 5, 53, 157, 173, 211,
+/// …, such as 53 between 47 and 59. A window of three consecutive primes slides along
+/// [`primes`](crate::primes), so each prime is generated once.
+pub fn balanced_primes() -> BalancedPrimes {
+    let mut primes = Primes::new();
+    let window = [0, primes.next().unwrap(), primes.next().unwrap()];
+
+    BalancedPrimes { primes, window }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::primes_upto;
+
+    #[test]
+    fn twin_prime_count_01() {
+        let counts: Vec<u64> = (1..=7).map(|n| twin_prime_count(10u64.pow(n))).collect();
+
+        assert_eq!(counts, [2, 8, 35, 205, 1224, 8169, 58980]);
+        assert_eq!(twin_prime_count(4), 0);
+        assert_eq!(twin_prime_count(5), 1);
+        assert_eq!(twin_prime_count(6), 1);
+        assert_eq!(twin_prime_count(7), 2);
+
+        // Against pairs of the primes directly.
+        let primes: Vec<u64> = sieve_primes(20_000);
+
+        for x in (0..20_000).step_by(97) {
+            let expected = primes
+                .windows(2)
+                .filter(|w| w[1] - w[0] == 2 && w[1] <= x)
+                .count();
+
+            assert_eq!(twin_prime_count(x), expected as u64, "{x}");
+        }
+    }
+
+    #[test]
+    fn nth_twin_prime_01() {
+        let first: Vec<u64> = (0..8).map(nth_twin_prime).collect();
+
+        assert_eq!(first, [3, 5, 11, 17, 29, 41, 59, 71]);
+
+        // The last pair below 10^7 is the 58980th.
+        let p = nth_twin_prime(58_979);
+
+        assert!(p + 2 < 10_000_000 && is_prime(p) && is_prime(p + 2));
+        assert_eq!(twin_prime_count(p + 2), 58_980);
+        assert!(nth_twin_prime(58_980) + 2 > 10_000_000);
+    }
+
+    #[test]
+    fn balanced_primes_01() {
+        assert!(balanced_primes()
+            .take(16)
+            .eq([5, 53, 157, 173, 211, 257, 263, 373, 563, 593, 607, 653, 733, 947, 977, 1103]));
+
+        let primes: Vec<u64> = primes_upto(50_000).collect();
+        let expected = primes
+            .windows(3)
+            .filter(|w| w[0] + w[2] == 2 * w[1])
+            .map(|w| w[1]);
+
+        assert!(balanced_primes()
+            .take_while(|&p| p < primes[primes.len() - 1])
+            .eq(expected));
+    }
+
+    #[test]
+    fn sum_primes_01() {
+        for n in 0..1000 {
+            let expected: u128 = primes_upto(n).map(|p| p as u128).sum();
+
+            assert_eq!(sum_primes(n), expected);
+        }
+    }
+
+    #[test]
+    fn sum_primes_02() {
+        assert_eq!(sum_primes(2_000_000), 142_913_828_922);
+        assert_eq!(sum_primes(1_000_000_000), 24_739_512_092_254_535);
+    }
+
+    #[test]
+    fn prime_pi_01() {
+        for x in 0..1000 {
+            assert_eq!(prime_pi(x), primes_upto(x).count() as u64);
+        }
+    }
+
+    #[test]
+    fn prime_pi_02() {
+        assert_eq!(prime_pi(1_000_000), 78_498);
+        assert_eq!(prime_pi(1_000_000_000), 50_847_534);
+        assert_eq!(prime_pi(10_000_000_000), 455_052_511);
+    }
+
+    #[test]
+    fn prime_index_01() {
+        use crate::nth_prime;
+
+        for k in 0..1000 {
+            assert_eq!(prime_index(nth_prime(k)), Some(k));
+        }
+
+        assert_eq!(prime_index(0), None);
+        assert_eq!(prime_index(1), None);
+        assert_eq!(prime_index(100), None);
+        assert_eq!(prime_index(1_000_000_007), Some(50_847_534));
+    }
+
+    #[test]
+    fn ramanujan_primes_01() {
+        let expected = [
+            2, 11, 17, 29, 41, 47, 59, 67, 71, 97, 101, 107, 127, 149, 151, 167, 179, 181, 227,
+            229, 233, 239, 241, 263, 269, 281, 307, 311, 347, 349, 367, 373, 401, 409, 419, 431,
+            433, 439, 461, 487, 491,
+        ];
+        let a: Vec<_> = ramanujan_primes().take(expected.len()).collect();
+
+        assert_eq!(a, expected);
+        assert_eq!(ramanujan_primes().nth(199), Some(nth_ramanujan_prime(199)));
+        assert_eq!(nth_ramanujan_prime(0), 2);
+        assert_eq!(nth_ramanujan_prime(9), 97);
+    }
+}
@@ -0,0 +1,488 @@
+use std::iter::FusedIterator;
+
+use crate::sieve::sieve_primes;
+use crate::{is_prime, is_prime_u128};
+
+/// The primes whose digits in some base read the same backwards, from [`palindromic_primes`].
+pub struct PalindromicPrimes {
+    base: u64,
+    // The palindromes of len digits are built from their first len / 2 digits, rounded up, in
+    // half..half_end.
+    len: u32,
+    half: u64,
+    half_end: u64,
+    done: bool,
+}
+
+impl PalindromicPrimes {
+    // Moves on to the next length that can hold a palindromic prime, or finishes if its
+    // palindromes would overflow.
+    fn next_len(&mut self) {
+        self.len += 1;
+
+        // Palindromes of even length are multiples of base + 1, so only base + 1 itself, of
+        // length 2, can be prime.
+        if self.len > 2 && self.len.is_multiple_of(2) {
+            self.len += 1;
+        }
+
+        let half_len = self.len.div_ceil(2);
+
+        match (
+            self.base.checked_pow(half_len - 1),
+            self.base.checked_pow(half_len),
+        ) {
+            (Some(start), end) => {
+                self.half = start;
+                self.half_end = end.unwrap_or(u64::MAX);
+            }
+            (None, _) => self.done = true,
+        }
+    }
+}
+
+impl Iterator for PalindromicPrimes {
+    type Item = u64;
+
+    fn next(&mut self) -> Option<u64> {
+        while !self.done {
+            if self.half >= self.half_end {
+                self.next_len();
+                continue;
+            }
+
+            let half = self.half;
+
+            self.half += 1;
+
+            // Mirror the half, dropping its last digit for odd lengths.
+            let mut n = Some(half);
+            let mut rest = if self.len % 2 == 1 {
+                half / self.base
+            } else {
+                half
+            };
+
+            while rest > 0 {
+                n = n
+                    .and_then(|n| n.checked_mul(self.base))
+                    .and_then(|n| n.checked_add(rest % self.base));
+                rest /= self.base;
+            }
+
+            match n {
+                // Palindromes of one length increase with their halves, so no later one fits.
+                None => self.done = true,
+                Some(n) if is_prime(n) => return Some(n),
+                Some(_) => {}
+            }
+        }
+
+        None
+    }
+}
+
+impl FusedIterator for PalindromicPrimes {}
+
+/// The palindromic primes in `base` in increasing order, up to the largest below 2^64: 2, 3, 5,
+/// 7, 11, 101, 131, … in base 10. Palindromes are built from their first half of digits rather
+/// than by filtering the primes, so only they are tested. Panics unless `2 <= base <= 36`.
+pub fn palindromic_primes(base: u64) -> PalindromicPrimes {
+    assert!((2..=36).contains(&base), "base must be from 2 to 36");
+
+    PalindromicPrimes {
+        base,
+        len: 0,
+        half: 0,
+        half_end: 0,
+        done: false,
+    }
+}
+
+/// The circular primes `<= limit` in base 10, those whose every rotation of digits is prime:
+/// 2, 3, 5, 7, 11, 13, 17, 31, 37, 71, …, such as 197 with 971 and 719. Rotations may exceed
+/// `limit`. Past one digit, every digit must be 1, 3, 7 or 9, or some rotation would end in an
+/// even digit, a 5 or a 0.
+pub fn circular_primes_upto(limit: u64) -> Vec<u64> {
+    sieve_primes(limit)
+        .into_iter()
+        .filter(|&p| {
+            let digits = p.ilog10();
+
+            if digits == 0 {
+                return true;
+            }
+
+            let high = 10u64.pow(digits);
+            let mut n = p;
+
+            for _ in 0..digits {
+                if !matches!(n % 10, 1 | 3 | 7 | 9) {
+                    return false;
+                }
+
+                // The last digit moved to the front.
+                n = n % 10 * high + n / 10;
+
+                if !is_prime(n) {
+                    return false;
+                }
+            }
+
+            true
+        })
+        .collect()
+}
+
+/// The digits of `n` in `base` in reverse order, read as a number in that base, or `None` if
+/// that overflows. Trailing zeros are lost, so `reverse_digits(120, 10) == Some(21)`. Panics if
+/// `base < 2`.
+pub fn reverse_digits(n: u64, base: u64) -> Option<u64> {
+    assert!(base >= 2, "base must be at least 2");
+
+    let mut n = n;
+    let mut reversed = 0u64;
+
+    while n > 0 {
+        reversed = reversed.checked_mul(base)?.checked_add(n % base)?;
+        n /= base;
+    }
+
+    Some(reversed)
+}
+
+/// The emirps in increasing order, from [`emirps`].
+pub struct Emirps {
+    next: Option<u64>,
+}
+
+impl Iterator for Emirps {
+    type Item = u64;
+
+    fn next(&mut self) -> Option<u64> {
+        loop {
+            let n = self.next?;
+            let high = 10u64.pow(n.ilog10());
+            let lead = n / high;
+
+            // Reversed, these would end in an even digit or a 5, so skip to the next lead.
+            if matches!(lead, 2 | 4 | 5 | 6 | 8) {
+                self.next = (lead + 1).checked_mul(high).and_then(|n| n.checked_add(1));
+                continue;
+            }
+
+            self.next = n.checked_add(2);
+
+            if is_emirp(n) {
+                return Some(n);
+            }
+        }
+    }
+}
+
+impl FusedIterator for Emirps {}
+
+/// The emirps, primes whose base-10 digits reversed are a different prime, in increasing order
+/// up to the largest below 2^64: 13, 17, 31, 37, 71, …. Primes whose leading digit would leave
+/// their reversal even or a multiple of 5 are skipped a whole range of leading digit at a time.
+pub fn emirps() -> Emirps {
+    Emirps { next: Some(11) }
+}
+
+/// The emirps `<= limit`, from a sieve; their reversals may exceed `limit`.
+pub fn emirps_upto(limit: u64) -> Vec<u64> {
+    sieve_primes(limit)
+        .into_iter()
+        .filter(|&p| is_emirp(p))
+        .collect()
+}
+
+// Whether the prime n reversed is a different prime.
+fn is_emirp(n: u64) -> bool {
+    is_prime(n) && reverse_digits(n, 10).is_some_and(|r| r != n && is_prime(r))
+}
+
+/// The 83 right-truncatable primes in base 10, which stay prime as their last digits are
+/// removed one by one, in increasing order: 2, 3, 5, 7, 23, 29, …, 73939133. Found by extending
+/// each such prime by a digit on the right until none remain, since every truncation of one is
+/// another.
+pub fn right_truncatable_primes() -> Vec<u64> {
+    let mut primes = vec![];
+    let mut frontier = vec![2, 3, 5, 7];
+
+    while let Some(p) = frontier.pop() {
+        primes.push(p);
+        frontier.extend(
+            [1, 3, 7, 9]
+                .map(|d| 10 * p + d)
+                .into_iter()
+                .filter(|&n| is_prime(n)),
+        );
+    }
+
+    primes.sort_unstable();
+    primes
+}
+
+/// The 4260 left-truncatable primes in base 10, with no zero digits, which stay prime as their
+/// first digits are removed one by one, in increasing order: 2, 3, 5, 7, 13, 17, …, up to the
+/// 24-digit 357686312646216567629137, hence `u128`. Found by extending each such prime by a
+/// digit on the left until none remain.
+pub fn left_truncatable_primes() -> Vec<u128> {
+    let mut primes = vec![];
+    let mut frontier = vec![(2, 10), (3, 10), (5, 10), (7, 10)];
+
+    // Each prime with the power of 10 above it.
+    while let Some((p, high)) = frontier.pop() {
+        primes.push(p);
+        frontier.extend(
+            (1..10)
+                .map(|d| (d * high + p, 10 * high))
+                .filter(|&(n, _)| is_prime_u128(n)),
+        );
+    }
+
+    primes.sort_unstable();
+    primes
+}
+
+/// The 15 two-sided primes in base 10, both left- and right-truncatable: 2, 3, 5, 7, 23, 37,
+/// 53, 73, 313, 317, 373, 797, 3137, 3797 and 739397.
+pub fn two_sided_primes() -> Vec<u64> {
+    right_truncatable_primes()
+        .into_iter()
+        .filter(|&p| {
+            // Every proper suffix, removing the leading digit in turn.
+            let mut high = 10u64.pow(p.ilog10());
+
+            while high > 1 {
+                if !is_prime(p % high) {
+                    return false;
+                }
+
+                high /= 10;
+            }
+
+            true
+        })
+        .collect()
+}
+
+// The sum of the squares of the digits of any u64, with at most 20 digits, is at most 20 · 9^2.
+const MAX_DIGIT_SQUARE_SUM: u64 = 20 * 81;
+
+/// Whether `n` is happy, reaching 1 when repeatedly replaced by the sum of the squares of its
+/// base-10 digits: 7 → 49 → 97 → 130 → 10 → 1. Unhappy numbers all fall into the cycle through
+/// 4 instead.
+pub fn is_happy(n: u64) -> bool {
+    let mut n = n;
+
+    while n != 1 && n != 4 && n != 0 {
+        n = digit_square_sum(n);
+    }
+
+    n == 1
+}
+
+/// The happy primes in increasing order, up to the largest below 2^64, from [`happy_primes`].
+pub struct HappyPrimes {
+    next: Option<u64>,
+    // Whether each possible digit square sum is happy.
+    happy: Vec<bool>,
+}
+
+impl Iterator for HappyPrimes {
+    type Item = u64;
+
+    fn next(&mut self) -> Option<u64> {
+        loop {
+            let n = self.next?;
+
+            self.next = n.checked_add(if n == 2 { 1 } else { 2 });
+
+            if self.happy[digit_square_sum(n) as usize] && is_prime(n) {
+                return Some(n);
+            }
+        }
+    }
+}
+
+impl FusedIterator for HappyPrimes {}
+
+/// The primes that are happy in the sense of [`is_happy`]: 7, 13, 19, 23, 31, 79, …. Every
+/// candidate is one digit square sum from a table of which sums are happy, so only happy
+/// numbers reach the primality test.
+pub fn happy_primes() -> HappyPrimes {
+    HappyPrimes {
+        next: Some(2),
+        happy: (0..=MAX_DIGIT_SQUARE_SUM).map(is_happy).collect(),
+    }
+}
+
+// The sum of the squares of the base-10 digits of n.
+fn digit_square_sum(n: u64) -> u64 {
+    let mut n = n;
+    let mut sum = 0;
+
+    while n > 0 {
+        sum += (n % 10) * (n % 10);
+        n /= 10;
+    }
+
+    sum
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::primes_upto;
+
+    fn digits(mut n: u64, base: u64) -> Vec<u64> {
+        let mut digits = vec![];
+
+        while n > 0 {
+            digits.push(n % base);
+            n /= base;
+        }
+
+        digits
+    }
+
+    #[test]
+    fn palindromic_primes_01() {
+        assert!(palindromic_primes(10).take(21).eq([
+            2, 3, 5, 7, 11, 101, 131, 151, 181, 191, 313, 353, 373, 383, 727, 757, 787, 797, 919,
+            929, 10301
+        ]));
+
+        // Against filtering the primes, in every base.
+        for base in 2..=36 {
+            let expected = primes_upto(200_000).filter(|&p| {
+                let d = digits(p, base);
+
+                d.iter().eq(d.iter().rev())
+            });
+
+            assert!(
+                palindromic_primes(base)
+                    .take_while(|&p| p <= 200_000)
+                    .eq(expected),
+                "{base}"
+            );
+        }
+
+        // The last 63-bit binary palindromes, after which the next length overflows.
+        let mut binary = PalindromicPrimes {
+            base: 2,
+            len: 63,
+            half: (1 << 32) - 1000,
+            half_end: 1 << 32,
+            done: false,
+        };
+        let last: Vec<u64> = binary.by_ref().collect();
+
+        assert!(!last.is_empty());
+        assert!(last
+            .iter()
+            .all(|&p| p >> 62 == 1 && digits(p, 2).iter().eq(digits(p, 2).iter().rev())));
+        assert_eq!(binary.next(), None);
+    }
+
+    #[test]
+    fn circular_primes_upto_01() {
+        assert_eq!(
+            circular_primes_upto(1_000_000),
+            [
+                2, 3, 5, 7, 11, 13, 17, 31, 37, 71, 73, 79, 97, 113, 131, 197, 199, 311, 337, 373,
+                719, 733, 919, 971, 991, 1193, 1931, 3119, 3779, 7793, 7937, 9311, 9377, 11939,
+                19391, 19937, 37199, 39119, 71993, 91193, 93719, 93911, 99371, 193939, 199933,
+                319993, 331999, 391939, 393919, 919393, 933199, 939193, 939391, 993319, 999331
+            ]
+        );
+        assert_eq!(circular_primes_upto(100).len(), 13);
+        assert!(circular_primes_upto(1).is_empty());
+    }
+
+    #[test]
+    fn reverse_digits_01() {
+        assert_eq!(reverse_digits(0, 10), Some(0));
+        assert_eq!(reverse_digits(12_345, 10), Some(54_321));
+        assert_eq!(reverse_digits(120, 10), Some(21));
+        assert_eq!(reverse_digits(0b1011, 2), Some(0b1101));
+        assert_eq!(reverse_digits(u64::MAX, 2), Some(u64::MAX));
+        assert_eq!(reverse_digits(u64::MAX, 10), None);
+        assert_eq!(reverse_digits(10_000_000_000_000_000_009, 10), None);
+    }
+
+    #[test]
+    fn emirps_01() {
+        let expected = [
+            13, 17, 31, 37, 71, 73, 79, 97, 107, 113, 149, 157, 167, 179, 199, 311, 337, 347, 359,
+            389, 701, 709, 733, 739, 743, 751, 761, 769, 907, 937, 941, 953, 967, 971, 983, 991,
+        ];
+
+        assert_eq!(emirps_upto(1000), expected);
+        assert!(emirps().take(expected.len()).eq(expected));
+        assert!(emirps()
+            .take_while(|&p| p <= 200_000)
+            .eq(emirps_upto(200_000)));
+        assert_eq!(emirps_upto(10_000).len(), 240);
+
+        // Palindromic primes are not emirps.
+        assert!(!emirps_upto(20_000).contains(&10_301));
+    }
+
+    #[test]
+    fn truncatable_primes_01() {
+        let right = right_truncatable_primes();
+        let left = left_truncatable_primes();
+
+        assert_eq!(right.len(), 83);
+        assert_eq!(right[..8], [2, 3, 5, 7, 23, 29, 31, 37]);
+        assert_eq!(right.last(), Some(&73_939_133));
+        assert_eq!(left.len(), 4260);
+        assert_eq!(left[..8], [2, 3, 5, 7, 13, 17, 23, 37]);
+        assert_eq!(left.last(), Some(&357_686_312_646_216_567_629_137));
+        assert_eq!(
+            two_sided_primes(),
+            [2, 3, 5, 7, 23, 37, 53, 73, 313, 317, 373, 797, 3137, 3797, 739_397]
+        );
+
+        // Against truncating the primes directly.
+        let primes: Vec<u64> = primes_upto(100_000).collect();
+        let is_listed = |n: u64| primes.binary_search(&n).is_ok();
+
+        for &p in &primes {
+            let digits = p.ilog10();
+            let prefixes = (1..=digits).all(|k| is_listed(p / 10u64.pow(k)));
+            let suffixes = (1..=digits).all(|k| is_listed(p % 10u64.pow(k)));
+            let no_zeros = !p.to_string().contains('0');
+
+            assert_eq!(right.binary_search(&p).is_ok(), prefixes, "{p}");
+            assert_eq!(
+                left.binary_search(&(p as u128)).is_ok(),
+                no_zeros && suffixes,
+                "{p}"
+            );
+        }
+    }
+
+    #[test]
+    fn happy_primes_01() {
+        let happy: Vec<u64> = (1..=50).filter(|&n| is_happy(n)).collect();
+
+        assert_eq!(happy, [1, 7, 10, 13, 19, 23, 28, 31, 32, 44, 49]);
+        assert!(!is_happy(0) && is_happy(1_000_000_000_000_000_000));
+        assert!(happy_primes().take(23).eq([
+            7, 13, 19, 23, 31, 79, 97, 103, 109, 139, 167, 193, 239, 263, 293, 313, 331, 367, 379,
+            383, 397, 409, 487
+        ]));
+
+        // Against testing each prime.
+        let expected = primes_upto(100_000).filter(|&p| is_happy(p));
+
+        assert!(happy_primes().take_while(|&p| p <= 100_000).eq(expected));
+        assert_eq!(digit_square_sum(u64::MAX), 515);
+        assert!(digit_square_sum(9_999_999_999_999_999_999) <= MAX_DIGIT_SQUARE_SUM);
+    }
+}
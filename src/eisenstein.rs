@@ -0,0 +1,246 @@
+use std::ops::Mul;
+
+use crate::{factorization, is_prime, is_prime_u128, sqrt_mod_prime};
+
+/// An Eisenstein integer `a + b ω` in `ℤ[ω]`, where `ω = (−1 + sqrt(−3)) / 2` is a primitive
+/// cube root of unity, so `ω^2 = −1 − ω`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct EisensteinInt {
+    pub a: i64,
+    pub b: i64,
+}
+
+impl EisensteinInt {
+    pub const fn new(a: i64, b: i64) -> EisensteinInt {
+        EisensteinInt { a, b }
+    }
+
+    /// The norm `a^2 − a b + b^2`, multiplicative and never overflowing.
+    pub fn norm(self) -> u128 {
+        let a = self.a.unsigned_abs() as u128;
+        let b = self.b.unsigned_abs() as u128;
+
+        if (self.a < 0) == (self.b < 0) {
+            a.abs_diff(b).pow(2) + a * b
+        } else {
+            a * a + a * b + b * b
+        }
+    }
+
+    /// The complex conjugate `a + b ω^2 = (a − b) − b ω`.
+    pub fn conj(self) -> EisensteinInt {
+        EisensteinInt::new(self.a - self.b, -self.b)
+    }
+
+    /// Whether this is one of the six units `±1`, `±ω` and `±ω^2`.
+    pub fn is_unit(self) -> bool {
+        self.norm() == 1
+    }
+
+    // The associate with a > b >= 0, in the sextant of arguments [0, π / 3), for nonzero self,
+    // by turning through π / 3 at a time, multiplying by the unit 1 + ω = −ω^2.
+    fn normalize(self) -> EisensteinInt {
+        let mut z = self;
+
+        while !(z.a > z.b && z.b >= 0) {
+            z = EisensteinInt::new(z.a - z.b, z.a);
+        }
+
+        z
+    }
+
+    // self / other if other divides self, for norms that fit in a u64.
+    fn div_exact(self, other: EisensteinInt) -> Option<EisensteinInt> {
+        let n = other.norm() as i128;
+        let c = other.conj();
+        let (x, y) = (self.a as i128, self.b as i128);
+        let (u, v) = (c.a as i128, c.b as i128);
+        let (a, b) = (x * u - y * v, x * v + y * u - y * v);
+
+        (a % n == 0 && b % n == 0).then(|| EisensteinInt::new((a / n) as i64, (b / n) as i64))
+    }
+}
+
+impl Mul for EisensteinInt {
+    type Output = EisensteinInt;
+
+    /// Panics if the product does not fit.
+    fn mul(self, other: EisensteinInt) -> EisensteinInt {
+        let (a, b) = (self.a as i128, self.b as i128);
+        let (c, d) = (other.a as i128, other.b as i128);
+        let part = |x: i128| i64::try_from(x).expect("Eisenstein integer overflow");
+
+        EisensteinInt::new(part(a * c - b * d), part(a * d + b * c - b * d))
+    }
+}
+
+/// Whether `a + b ω` is an Eisenstein prime: either its norm `a^2 − a b + b^2` is a rational
+/// prime, as for `1 − ω` and the factors of primes `≡ 1 (mod 3)`, or it is an associate of a
+/// rational prime `≡ 2 (mod 3)`, which stays prime in `ℤ[ω]`.
+pub fn is_eisenstein_prime(a: i64, b: i64) -> bool {
+    // The associates ±p, ±p ω and ±p ω^2 = ∓p ∓ p ω of a rational p.
+    let rational = match (a, b) {
+        (0, n) | (n, 0) => Some(n),
+        _ if a == b => Some(a),
+        _ => None,
+    };
+
+    if let Some(n) = rational {
+        let n = n.unsigned_abs();
+
+        return n % 3 == 2 && is_prime(n);
+    }
+
+    is_prime_u128(EisensteinInt::new(a, b).norm())
+}
+
+// An Eisenstein prime of norm p for a prime p ≡ 1 (mod 3), from p = x^2 + 3 y^2 by Cornacchia's
+// algorithm and sqrt(−3) = 1 + 2 ω, so x + y sqrt(−3) = (x + y) + 2 y ω.
+fn eisenstein_prime_above(p: u64) -> EisensteinInt {
+    let r = sqrt_mod_prime(p - 3, p).unwrap();
+    let (mut r0, mut r1) = (p, r.max(p - r));
+
+    while r1 as u128 * r1 as u128 > p as u128 {
+        (r0, r1) = (r1, r0 % r1);
+    }
+
+    let x = r1 as i64;
+    let y = ((p - r1 * r1) / 3).isqrt() as i64;
+
+    EisensteinInt::new(x + y, 2 * y).normalize()
+}
+
+/// The factorization of `a + b ω` into Eisenstein primes, as a unit and pairs of a prime and
+/// its exponent, with the product of the unit and every prime power equal to `a + b ω`. Each
+/// prime is the associate `c + d ω` with `c > d >= 0`, and they come in increasing order of
+/// norm. The norm is factored over the integers: 3 gives powers of `2 + ω`, an associate of
+/// `1 − ω`, primes `p ≡ 2 (mod 3)` give `p` itself, and primes `p ≡ 1 (mod 3)` split into a
+/// prime and its conjugate, each side found by trial division. Panics if `a + b ω` is zero or
+/// its norm does not fit in a `u64`.
+pub fn eisenstein_factorization(a: i64, b: i64) -> (EisensteinInt, Vec<(EisensteinInt, u32)>) {
+    let mut z = EisensteinInt::new(a, b);
+
+    assert!(z != EisensteinInt::default(), "0 has no factorization");
+
+    let norm = u64::try_from(z.norm()).expect("norm must fit in a u64");
+    let mut factors = vec![];
+
+    // Divides z by prime as often as it goes, and records the power.
+    let mut divide_out = |z: &mut EisensteinInt, prime: EisensteinInt, max: u32| {
+        let mut e = 0;
+
+        while e < max {
+            match z.div_exact(prime) {
+                Some(q) => *z = q,
+                None => break,
+            }
+
+            e += 1;
+        }
+
+        if e > 0 {
+            factors.push((prime, e));
+        }
+
+        e
+    };
+
+    for &(p, e) in factorization(norm).iter() {
+        match p % 3 {
+            0 => {
+                divide_out(&mut z, EisensteinInt::new(2, 1), e);
+            }
+            2 => {
+                divide_out(&mut z, EisensteinInt::new(p as i64, 0), e / 2);
+            }
+            _ => {
+                let prime = eisenstein_prime_above(p);
+                let k = divide_out(&mut z, prime, e);
+
+                divide_out(&mut z, prime.conj().normalize(), e - k);
+            }
+        }
+    }
+
+    factors.sort_by_key(|&(prime, _)| (prime.norm(), prime.a, prime.b));
+
+    (z, factors)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn is_eisenstein_prime_01() {
+        // Against trial division by every Eisenstein integer of smaller norm.
+        for a in -20i64..=20 {
+            for b in -20i64..=20 {
+                let z = EisensteinInt::new(a, b);
+                let n = z.norm();
+                let has_divisor = (-25i64..=25).any(|c| {
+                    (-25i64..=25).any(|d| {
+                        let w = EisensteinInt::new(c, d);
+
+                        w.norm() > 1 && w.norm() < n && z.div_exact(w).is_some()
+                    })
+                });
+
+                assert_eq!(is_eisenstein_prime(a, b), n > 1 && !has_divisor, "{a} {b}");
+            }
+        }
+
+        // 1 − ω has norm 3, 5 stays prime, and 7 = (3 + ω)(3 + 2 ω).
+        assert!(is_eisenstein_prime(1, -1) && is_eisenstein_prime(-5, -5));
+        assert!(!is_eisenstein_prime(7, 0) && is_eisenstein_prime(3, 2));
+    }
+
+    #[test]
+    fn eisenstein_factorization_01() {
+        let check = |a: i64, b: i64| {
+            let (unit, factors) = eisenstein_factorization(a, b);
+            let product = factors
+                .iter()
+                .fold(unit, |z, &(prime, e)| (0..e).fold(z, |z, _| z * prime));
+
+            assert!(unit.is_unit());
+            assert_eq!(product, EisensteinInt::new(a, b), "{a} {b}");
+
+            for &(prime, _) in &factors {
+                assert!(is_eisenstein_prime(prime.a, prime.b));
+                assert!(prime.a > prime.b && prime.b >= 0);
+            }
+
+            assert!(factors.windows(2).all(|w| w[0].0 != w[1].0));
+        };
+
+        for a in -40..=40 {
+            for b in -40..=40 {
+                if (a, b) != (0, 0) {
+                    check(a, b);
+                }
+            }
+        }
+
+        check(4_294_967_295, 0);
+        check(3_000_000_019, -1_000_000_007);
+        check(i32::MAX as i64, i32::MIN as i64);
+
+        // 3 = −ω (2 + ω)^2, and 7 splits.
+        assert_eq!(
+            eisenstein_factorization(3, 0),
+            (
+                EisensteinInt::new(0, -1),
+                vec![(EisensteinInt::new(2, 1), 2)]
+            )
+        );
+        assert_eq!(
+            eisenstein_factorization(7, 0).1,
+            [(EisensteinInt::new(3, 1), 1), (EisensteinInt::new(3, 2), 1)]
+        );
+
+        for p in (7..5000).filter(|&p| is_prime(p) && p % 3 == 1) {
+            assert_eq!(eisenstein_prime_above(p).norm(), p as u128);
+        }
+    }
+}
@@ -0,0 +1,542 @@
+use num_bigint::BigUint;
+use num_integer::Integer;
+use num_traits::{One, ToPrimitive, Zero};
+
+use super::{factorize_u128, siqs, trial_primes};
+use crate::modular::jacobi_u128;
+use crate::primality::baillie_psw;
+use crate::sieve::sieve_primes;
+
+const RHO_STEPS: u64 = 1 << 14;
+
+// (b1, b2, curves) for successive rounds of the elliptic curve method, aimed at factors of
+// about 15, 20 and 25 digits.
+const ECM_ROUNDS: [(u64, u64, usize); 3] = [
+    (2_000, 200_000, 25),
+    (11_000, 1_100_000, 90),
+    (50_000, 5_000_000, 300),
+];
+
+// Past this many digits, SIQS is slow enough that every ECM round is worth trying first.
+const ECM_DIGITS_PER_ROUND: usize = 20;
+
+const STAGE2_D: u64 = 210;
+
+const GCD_INTERVAL: usize = 64;
+
+/// Whether `n` is prime, by the Baillie–PSW test.
+pub fn is_prime_biguint(n: &BigUint) -> bool {
+    if let Some(n) = n.to_u128() {
+        return baillie_psw(n);
+    }
+
+    if trial_primes().iter().take(12).any(|&p| (n % p).is_zero()) {
+        return false;
+    }
+
+    let root = n.sqrt();
+
+    if &root * &root == *n {
+        return false;
+    }
+
+    is_strong_probable_prime_biguint(n, &BigUint::from(2u32)) && is_strong_lucas_probable_prime(n)
+}
+
+// The strong probable prime test to the given base, for odd n > 2.
+pub(crate) fn is_strong_probable_prime_biguint(n: &BigUint, base: &BigUint) -> bool {
+    let one = BigUint::one();
+    let n_minus_1 = n - &one;
+    let s = n_minus_1.trailing_zeros().unwrap();
+    let d = &n_minus_1 >> s;
+    let mut x = base.modpow(&d, n);
+
+    if x == one || x == n_minus_1 {
+        return true;
+    }
+
+    for _ in 1..s {
+        x = &x * &x % n;
+
+        if x == n_minus_1 {
+            return true;
+        }
+    }
+
+    false
+}
+
+// The strong Lucas test with Selfridge's parameters, as for u128.
+fn is_strong_lucas_probable_prime(n: &BigUint) -> bool {
+    let reduce = |x: i64| {
+        let r = BigUint::from(x.unsigned_abs()) % n;
+
+        if x < 0 && !r.is_zero() {
+            n - r
+        } else {
+            r
+        }
+    };
+    let mut d: i64 = 5;
+
+    loop {
+        match jacobi_small(d, n) {
+            -1 => break,
+            0 => return false,
+            _ => d = if d > 0 { -d - 2 } else { -d + 2 },
+        }
+    }
+
+    let sub = |a: &BigUint, b: &BigUint| if a >= b { a - b } else { n - b + a };
+    let half = |x: BigUint| {
+        if x.is_even() {
+            x >> 1
+        } else {
+            (x + n) >> 1
+        }
+    };
+
+    let d_mod = reduce(d);
+    let q = reduce((1 - d) / 4);
+    let n_plus_1 = n + 1u32;
+    let s = n_plus_1.trailing_zeros().unwrap();
+    let k = &n_plus_1 >> s;
+
+    let mut u = BigUint::zero();
+    let mut v = BigUint::from(2u32);
+    let mut q_k = BigUint::one();
+
+    for bit in (0..k.bits()).rev() {
+        u = &u * &v % n;
+        v = sub(&(&v * &v % n), &(&q_k * 2u32 % n));
+        q_k = &q_k * &q_k % n;
+
+        if k.bit(bit) {
+            let next_u = half((&u + &v) % n);
+
+            v = half((&d_mod * &u + &v) % n);
+            u = next_u;
+            q_k = &q_k * &q % n;
+        }
+    }
+
+    if u.is_zero() || v.is_zero() {
+        return true;
+    }
+
+    for _ in 1..s {
+        v = sub(&(&v * &v % n), &(&q_k * 2u32 % n));
+        q_k = &q_k * &q_k % n;
+
+        if v.is_zero() {
+            return true;
+        }
+    }
+
+    false
+}
+
+// The Jacobi symbol (D | n) for small D and odd n, reduced by reciprocity to a symbol of two
+// u128 values.
+fn jacobi_small(d: i64, n: &BigUint) -> i32 {
+    let a = d.unsigned_abs() as u128;
+    let n_mod_a = (n % a).to_u128().unwrap();
+    let n_mod_8 = (n % 8u32).to_u32().unwrap();
+
+    // (−1 | n) = −1 exactly when n ≡ 3 (mod 4).
+    let mut result = if d < 0 && n_mod_8 % 4 == 3 { -1 } else { 1 };
+
+    let twos = a.trailing_zeros();
+    let odd = a >> twos;
+
+    if twos % 2 == 1 && matches!(n_mod_8, 3 | 5) {
+        result = -result;
+    }
+
+    if odd == 1 {
+        return result;
+    }
+
+    // (odd | n) = (n | odd), up to the sign from reciprocity.
+    if odd % 4 == 3 && n_mod_8 % 4 == 3 {
+        result = -result;
+    }
+
+    result * jacobi_u128(n_mod_a % odd, odd)
+}
+
+// Overwrites the digits of n with zeros in place, leaving it 0. num-bigint 0.4 has no Zeroize,
+// so the zeros go through assign_from_slice, which refills the same buffer when the value keeps
+// its length, and black_box keeps the stores from being dropped as dead before the buffer is
+// freed. Spare capacity left over from larger earlier values is not reached.
+#[cfg(feature = "zeroize")]
+pub(crate) fn wipe_biguint(n: &mut BigUint) {
+    let zeros = vec![0u32; n.iter_u32_digits().len()];
+
+    n.assign_from_slice(&zeros);
+    std::hint::black_box(&*n);
+}
+
+/// The prime factorization of `n` as `(prime, exponent)` pairs in increasing order of prime.
+///
+/// Cofactors that fit in a `u128` go through [`factorize_u128`]. Wider ones are split by a short
+/// run of Pollard's rho, rounds of the elliptic curve method sized to the cofactor, and finally
+/// the self-initializing quadratic sieve, so that the time is governed by the second largest
+/// prime factor once it is out of reach of ECM. Panics if `n` is 0.
+pub fn factorize_biguint(n: &BigUint) -> Vec<(BigUint, u32)> {
+    assert!(!n.is_zero(), "0 has no factorization");
+
+    let mut primes = vec![];
+    let mut k = n.clone();
+
+    for &p in trial_primes() {
+        if k.to_u128().is_some() {
+            break;
+        }
+
+        while (&k % p).is_zero() {
+            primes.push(BigUint::from(p));
+            k /= p;
+        }
+    }
+
+    if !k.is_one() {
+        split(k, &mut primes);
+    }
+
+    primes.sort_unstable();
+
+    let mut factors: Vec<(BigUint, u32)> = vec![];
+
+    for p in primes {
+        match factors.last_mut() {
+            Some((q, e)) if *q == p => *e += 1,
+            _ => factors.push((p, 1)),
+        }
+    }
+
+    factors
+}
+
+fn split(n: BigUint, primes: &mut Vec<BigUint>) {
+    if let Some(small) = n.to_u128() {
+        for (p, e) in factorize_u128(small) {
+            primes.extend(std::iter::repeat_n(BigUint::from(p), e as usize));
+        }
+
+        return;
+    }
+
+    if is_prime_biguint(&n) {
+        primes.push(n);
+        return;
+    }
+
+    let d = find_factor(&n);
+    let rest = &n / &d;
+
+    split(d, primes);
+    split(rest, primes);
+}
+
+fn find_factor(n: &BigUint) -> BigUint {
+    if let Some(d) = pollard_rho(n, RHO_STEPS) {
+        return d;
+    }
+
+    for e in 2..=n.bits() as u32 / 10 {
+        let root = n.nth_root(e);
+
+        if root.pow(e) == *n {
+            return root;
+        }
+    }
+
+    let digits = n.to_string().len();
+    let rounds = (digits / ECM_DIGITS_PER_ROUND).clamp(1, ECM_ROUNDS.len());
+    let mut sigma = 6;
+
+    for &(b1, b2, curves) in &ECM_ROUNDS[..rounds] {
+        let primes = sieve_primes(b2);
+
+        for _ in 0..curves {
+            if let Some(d) = ecm_curve(n, sigma, b1, &primes) {
+                return d;
+            }
+
+            sigma += 1;
+        }
+    }
+
+    if let Some(d) = siqs(n) {
+        return d;
+    }
+
+    // Only reached for inputs SIQS cannot handle; keep drawing curves from the largest round.
+    let (b1, b2, _) = ECM_ROUNDS[ECM_ROUNDS.len() - 1];
+    let primes = sieve_primes(b2);
+
+    loop {
+        if let Some(d) = ecm_curve(n, sigma, b1, &primes) {
+            return d;
+        }
+
+        sigma += 1;
+    }
+}
+
+fn factor_from(g: BigUint, n: &BigUint) -> Option<BigUint> {
+    Some(g).filter(|g| !g.is_one() && g != n)
+}
+
+// Pollard–Brent with the map x -> x^2 + 1, giving up after `steps` steps.
+fn pollard_rho(n: &BigUint, steps: u64) -> Option<BigUint> {
+    let f = |x: &BigUint| (x * x + 1u32) % n;
+    let diff = |a: &BigUint, b: &BigUint| if a >= b { a - b } else { b - a };
+
+    let mut y = BigUint::from(2u32);
+    let mut r = 1;
+    let mut q = BigUint::one();
+    let mut spent = 0;
+
+    while spent < steps {
+        let x = y.clone();
+
+        for _ in 0..r {
+            y = f(&y);
+        }
+
+        for _ in 0..r {
+            y = f(&y);
+            q = q * diff(&x, &y) % n;
+        }
+
+        spent += 2 * r;
+
+        let g = q.gcd(n);
+
+        if !g.is_one() {
+            // A batch that collapses to n is abandoned rather than retraced.
+            return factor_from(g, n);
+        }
+
+        r *= 2;
+    }
+
+    None
+}
+
+// A point (X : Z) on a Montgomery curve with (A + 2) / 4 = num / den, as in the u64 version.
+#[derive(Clone)]
+struct Point {
+    x: BigUint,
+    z: BigUint,
+}
+
+struct Curve<'a> {
+    n: &'a BigUint,
+    num: BigUint,
+    den: BigUint,
+}
+
+impl Curve<'_> {
+    fn sub(&self, a: &BigUint, b: &BigUint) -> BigUint {
+        if a >= b {
+            a - b
+        } else {
+            self.n - b + a
+        }
+    }
+
+    fn double(&self, p: &Point) -> Point {
+        let n = self.n;
+        let s = (&p.x + &p.z) % n;
+        let d = self.sub(&p.x, &p.z);
+        let s2 = &s * &s % n;
+        let d2 = &d * &d % n;
+        let t = self.sub(&s2, &d2);
+
+        Point {
+            x: &self.den * (&s2 * &d2 % n) % n,
+            z: &t * ((&self.den * &d2 + &self.num * &t) % n) % n,
+        }
+    }
+
+    fn add(&self, p: &Point, q: &Point, diff: &Point) -> Point {
+        let n = self.n;
+        let u = self.sub(&p.x, &p.z) * ((&q.x + &q.z) % n) % n;
+        let v = (&p.x + &p.z) * self.sub(&q.x, &q.z) % n;
+        let s = (&u + &v) % n;
+        let d = self.sub(&u, &v);
+
+        Point {
+            x: &diff.z * (&s * &s % n) % n,
+            z: &diff.x * (&d * &d % n) % n,
+        }
+    }
+
+    fn multiply(&self, p: &Point, k: u64) -> Point {
+        let mut r0 = p.clone();
+        let mut r1 = self.double(p);
+
+        for bit in (0..63 - k.leading_zeros()).rev() {
+            if (k >> bit) & 1 == 1 {
+                r0 = self.add(&r1, &r0, p);
+                r1 = self.double(&r1);
+            } else {
+                r1 = self.add(&r1, &r0, p);
+                r0 = self.double(&r0);
+            }
+        }
+
+        r0
+    }
+}
+
+// One curve from Suyama's family, with stage 2 over the primes in `primes` above b1.
+fn ecm_curve(n: &BigUint, sigma: u64, b1: u64, primes: &[u64]) -> Option<BigUint> {
+    let sigma = BigUint::from(sigma);
+    let u = (&sigma * &sigma - 5u32) % n;
+    let v = &sigma * 4u32 % n;
+    let u3 = &u * &u * &u % n;
+    let v_u = if v >= u { &v - &u } else { n - &u + &v };
+
+    let curve = Curve {
+        n,
+        num: &v_u * &v_u % n * &v_u % n * ((&u * 3u32 + &v) % n) % n,
+        den: &u3 * 16u32 * &v % n,
+    };
+    let mut q = Point {
+        x: u3,
+        z: &v * &v * &v % n,
+    };
+
+    let stage1 = primes.partition_point(|&p| p <= b1);
+
+    for &p in &primes[..stage1] {
+        let mut power = p;
+
+        while let Some(next) = power.checked_mul(p).filter(|&next| next <= b1) {
+            power = next;
+        }
+
+        q = curve.multiply(&q, power);
+    }
+
+    let g = q.z.gcd(n);
+
+    if !g.is_one() {
+        return factor_from(g, n);
+    }
+
+    let stage2 = &primes[stage1..];
+
+    if stage2.is_empty() {
+        return None;
+    }
+
+    let half = (STAGE2_D / 2) as usize;
+    let zero = Point {
+        x: BigUint::zero(),
+        z: BigUint::zero(),
+    };
+    let mut baby = vec![zero; half];
+    let q2 = curve.double(&q);
+
+    baby[3] = curve.add(&q2, &q, &q);
+    baby[1] = q.clone();
+
+    for j in (5..half).step_by(2) {
+        baby[j] = curve.add(&baby[j - 2], &q2, &baby[j - 4]);
+    }
+
+    let dq = curve.multiply(&q, STAGE2_D);
+    let mut k = ((stage2[0] + STAGE2_D / 2) / STAGE2_D).max(2);
+    let mut giant_prev = curve.multiply(&q, (k - 1) * STAGE2_D);
+    let mut giant = curve.multiply(&q, k * STAGE2_D);
+    let mut product = BigUint::one();
+
+    for (i, &p) in stage2.iter().enumerate() {
+        let target = (p + STAGE2_D / 2) / STAGE2_D;
+
+        while k < target {
+            let next = curve.add(&giant, &dq, &giant_prev);
+            giant_prev = giant;
+            giant = next;
+            k += 1;
+        }
+
+        let j = p.abs_diff(k * STAGE2_D) as usize;
+        let b = &baby[j];
+        let term = curve.sub(&(&giant.x * &b.z % n), &(&b.x * &giant.z % n));
+
+        product = product * term % n;
+
+        if i % GCD_INTERVAL == GCD_INTERVAL - 1 {
+            let g = product.gcd(n);
+
+            if !g.is_one() {
+                return factor_from(g, n);
+            }
+        }
+    }
+
+    factor_from(product.gcd(n), n)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn big(s: &str) -> BigUint {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn is_prime_biguint_01() {
+        // 2^127 − 1, 2^521 − 1, and the composite 2^128 + 1.
+        assert!(is_prime_biguint(&((BigUint::one() << 127) - 1u32)));
+        assert!(is_prime_biguint(&((BigUint::one() << 521) - 1u32)));
+        assert!(!is_prime_biguint(&((BigUint::one() << 128) + 1u32)));
+        assert!(!is_prime_biguint(&((BigUint::one() << 523) - 1u32)));
+        assert!(!is_prime_biguint(
+            &(big("100000000000000000039") * big("99999999999999999989"))
+        ));
+        assert!(is_prime_biguint(&big("100000000000000000039")));
+    }
+
+    #[test]
+    fn factorize_biguint_01() {
+        let f = |items: &[(&str, u32)]| -> Vec<(BigUint, u32)> {
+            items.iter().map(|&(p, e)| (big(p), e)).collect()
+        };
+
+        assert_eq!(factorize_biguint(&BigUint::one()), vec![]);
+        assert_eq!(
+            factorize_biguint(&BigUint::from(360u32)),
+            f(&[("2", 3), ("3", 2), ("5", 1)])
+        );
+
+        // 2^128 + 1 = 59649589127497217 · 5704689200685129054721, split by ECM or SIQS.
+        assert_eq!(
+            factorize_biguint(&((BigUint::one() << 128) + 1u32)),
+            f(&[("59649589127497217", 1), ("5704689200685129054721", 1)])
+        );
+
+        // A rho-sized factor, a square of a 70-bit prime, and a 40-digit semiprime for SIQS.
+        let n = big("1000003")
+            * big("1180591620717411303449").pow(2)
+            * big("100000000000000000039")
+            * big("99999999999999999989");
+
+        assert_eq!(
+            factorize_biguint(&n),
+            f(&[
+                ("1000003", 1),
+                ("99999999999999999989", 1),
+                ("100000000000000000039", 1),
+                ("1180591620717411303449", 2),
+            ])
+        );
+    }
+}
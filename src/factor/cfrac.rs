@@ -0,0 +1,274 @@
+use std::collections::HashMap;
+
+use super::linalg::gf2_dependencies;
+use crate::modular::{add_mod_u128, gcd_u128, mul_mod_u128};
+use crate::sieve::sieve_primes;
+
+const MULTIPLIERS: [u128; 8] = [1, 3, 5, 7, 11, 13, 15, 17];
+
+// More relations than factor base columns guarantees dependencies; a few extra give several
+// independent attempts at a nontrivial square root.
+const EXTRA_RELATIONS: usize = 16;
+
+/// The continued fraction expansion of `sqrt(d)` for non-square `d`, along with the
+/// numerators of its convergents reduced modulo `n`.
+///
+/// Each step yields `(A, Q, i)` with `A^2 ≡ (−1)^i Q (mod n)` whenever `n | d`.
+pub(crate) struct SqrtContinuedFraction {
+    root: u128,
+    n: u128,
+    p: u128,
+    q_prev: u128,
+    q: u128,
+    a_prev: u128,
+    a: u128,
+    index: u64,
+}
+
+impl SqrtContinuedFraction {
+    pub(crate) fn new(d: u128, n: u128) -> SqrtContinuedFraction {
+        let root = d.isqrt();
+
+        SqrtContinuedFraction {
+            root,
+            n,
+            p: root,
+            q_prev: 1,
+            q: d - root * root,
+            a_prev: 1,
+            a: root % n,
+            index: 1,
+        }
+    }
+}
+
+impl Iterator for SqrtContinuedFraction {
+    type Item = (u128, u128, u64);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.q == 0 {
+            return None;
+        }
+
+        let item = (self.a, self.q, self.index);
+
+        let b = (self.root + self.p) / self.q;
+        let p_next = b * self.q - self.p;
+        let q_next = if self.p >= p_next {
+            self.q_prev + b * (self.p - p_next)
+        } else {
+            self.q_prev - b * (p_next - self.p)
+        };
+        let a_next = add_mod_u128(mul_mod_u128(b, self.a, self.n), self.a_prev, self.n);
+
+        self.p = p_next;
+        self.q_prev = self.q;
+        self.q = q_next;
+        self.a_prev = self.a;
+        self.a = a_next;
+        self.index += 1;
+
+        Some(item)
+    }
+}
+
+struct Relation {
+    x: u128,
+    exponents: Vec<u32>,
+    large: u128,
+}
+
+fn factor_base_bound(n: u128) -> u64 {
+    let ln_n = (n as f64).ln();
+
+    ((0.5 * (ln_n * ln_n.ln()).sqrt()).exp() * 2.0).max(200.0) as u64
+}
+
+// The Legendre symbol (a | p) for an odd prime p, as 0, 1 or p - 1.
+fn legendre(a: u128, p: u64) -> u64 {
+    crate::modular::mod_pow((a % p as u128) as u64, (p - 1) / 2, p)
+}
+
+/// The continued fraction factoring algorithm of Morrison and Brillhart. Collects relations
+/// `A^2 ≡ ±Q (mod kn)` with smooth `Q` from the expansion of `sqrt(kn)`, and combines them into
+/// a congruence of squares by linear algebra over GF(2). `n` should be an odd composite that is
+/// not a perfect power. Multipliers `k` that would take `kn` past 2^128 are skipped.
+pub fn cfrac(n: u128) -> Option<u128> {
+    if n < 4 {
+        return None;
+    }
+
+    if n.is_multiple_of(2) {
+        return Some(2);
+    }
+
+    MULTIPLIERS
+        .iter()
+        .filter_map(|&k| k.checked_mul(n).filter(|&d| d.isqrt() < u64::MAX as u128))
+        .find_map(|d| cfrac_multiplier(n, d))
+}
+
+fn cfrac_multiplier(n: u128, d: u128) -> Option<u128> {
+    let root = d.isqrt();
+
+    if root * root == d {
+        return Some(gcd_u128(root, n)).filter(|&g| g > 1 && g < n);
+    }
+
+    // The factor base: -1 and the primes p with kn a square modulo p.
+    let bound = factor_base_bound(n);
+    let mut base = vec![];
+
+    for p in sieve_primes(bound) {
+        if d.is_multiple_of(p as u128) {
+            let g = gcd_u128(p as u128, n);
+
+            if g > 1 && g < n {
+                return Some(g);
+            }
+        }
+
+        if p == 2 || legendre(d, p) <= 1 {
+            base.push(p);
+        }
+    }
+
+    let columns = base.len() + 1;
+    let large_bound = bound as u128 * bound as u128;
+    let mut relations: Vec<Relation> = vec![];
+    let mut partials: HashMap<u128, Relation> = HashMap::new();
+
+    for (a, q, i) in SqrtContinuedFraction::new(d, n) {
+        if i > 1 && i % 2 == 1 && q == 1 {
+            // The period has ended, and the expansion now repeats.
+            break;
+        }
+
+        let mut exponents = vec![0; columns];
+        let mut rest = q;
+
+        exponents[0] = (i % 2) as u32;
+
+        for (j, &p) in base.iter().enumerate() {
+            let p = p as u128;
+
+            while rest.is_multiple_of(p) {
+                rest /= p;
+                exponents[j + 1] += 1;
+            }
+        }
+
+        let relation = Relation {
+            x: a,
+            exponents,
+            large: 1,
+        };
+
+        if rest == 1 {
+            relations.push(relation);
+        } else if rest < large_bound {
+            // A single large prime: two relations sharing it multiply to a smooth one.
+            if let Some(other) = partials.remove(&rest) {
+                relations.push(Relation {
+                    x: mul_mod_u128(relation.x, other.x, n),
+                    exponents: relation
+                        .exponents
+                        .iter()
+                        .zip(&other.exponents)
+                        .map(|(e, f)| e + f)
+                        .collect(),
+                    large: rest % n,
+                });
+            } else {
+                partials.insert(rest, relation);
+            }
+        }
+
+        if relations.len() >= columns + EXTRA_RELATIONS {
+            break;
+        }
+    }
+
+    combine(n, &base, &relations)
+}
+
+// Finds subsets of relations whose Q values multiply to a square, and tries each for a factor.
+fn combine(n: u128, base: &[u64], relations: &[Relation]) -> Option<u128> {
+    let columns = base.len() + 1;
+    let rows: Vec<Vec<usize>> = relations
+        .iter()
+        .map(|relation| {
+            (0..columns)
+                .filter(|&c| relation.exponents[c] % 2 == 1)
+                .collect()
+        })
+        .collect();
+
+    for dependency in gf2_dependencies(columns, &rows) {
+        let mut x = 1;
+        let mut y = 1;
+        let mut exponents = vec![0u32; columns];
+
+        for &r in &dependency {
+            let relation = &relations[r];
+
+            x = mul_mod_u128(x, relation.x, n);
+            y = mul_mod_u128(y, relation.large, n);
+
+            for (e, f) in exponents.iter_mut().zip(&relation.exponents) {
+                *e += f;
+            }
+        }
+
+        for (&p, &e) in base.iter().zip(&exponents[1..]) {
+            for _ in 0..e / 2 {
+                y = mul_mod_u128(y, p as u128, n);
+            }
+        }
+
+        let g = gcd_u128(x.abs_diff(y), n);
+
+        if g > 1 && g < n {
+            return Some(g);
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sqrt_continued_fraction_01() {
+        // sqrt(14) = [3; 1, 2, 1, 6], with Q running 1, 5, 2, 5, 1, ...
+        let n = 1_000_003;
+        let qs: Vec<_> = SqrtContinuedFraction::new(14, n)
+            .take(6)
+            .map(|(_, q, _)| q)
+            .collect();
+
+        assert_eq!(qs, vec![5, 2, 5, 1, 5, 2]);
+
+        for (a, q, i) in SqrtContinuedFraction::new(14 * n, n).take(100) {
+            let lhs = mul_mod_u128(a, a, n);
+            let rhs = if i % 2 == 0 { q % n } else { n - q % n };
+
+            assert_eq!(lhs, rhs);
+        }
+    }
+
+    #[test]
+    fn cfrac_01() {
+        for &(p, q) in &[
+            (1_000_003u128, 1_000_033u128),
+            (1_000_000_007, 998_244_353),
+            (1_099_511_627_791, 3_298_534_895_693),
+        ] {
+            let d = cfrac(p * q).unwrap();
+
+            assert!(d == p || d == q);
+        }
+    }
+}
@@ -0,0 +1,343 @@
+use std::ops::Rem;
+
+use crate::modular::{
+    add_mod, add_mod_u128, gcd, gcd_u128, mul_mod, mul_mod_u128, sub_mod, sub_mod_u128,
+};
+use crate::sieve::sieve_primes;
+
+// Stage 2 pairs each prime q with the nearest multiple kD, so that q = kD ± j with j < D / 2.
+const STAGE2_D: u64 = 210;
+
+const GCD_INTERVAL: usize = 64;
+
+/// Bounds and curve count for the elliptic curve method.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct EcmParams {
+    pub b1: u64,
+    pub b2: u64,
+    pub curves: usize,
+}
+
+impl Default for EcmParams {
+    fn default() -> EcmParams {
+        EcmParams {
+            b1: 2000,
+            b2: 200_000,
+            curves: 64,
+        }
+    }
+}
+
+// The words the curve arithmetic runs on, with their arithmetic modulo n for operands below n.
+trait Word: Copy + Ord + From<u64> + Rem<Output = Self> {
+    fn mul_mod(a: Self, b: Self, n: Self) -> Self;
+    fn add_mod(a: Self, b: Self, n: Self) -> Self;
+    fn sub_mod(a: Self, b: Self, n: Self) -> Self;
+    fn gcd(a: Self, b: Self) -> Self;
+}
+
+impl Word for u64 {
+    fn mul_mod(a: u64, b: u64, n: u64) -> u64 {
+        mul_mod(a, b, n)
+    }
+
+    fn add_mod(a: u64, b: u64, n: u64) -> u64 {
+        add_mod(a, b, n)
+    }
+
+    fn sub_mod(a: u64, b: u64, n: u64) -> u64 {
+        sub_mod(a, b, n)
+    }
+
+    fn gcd(a: u64, b: u64) -> u64 {
+        gcd(a, b)
+    }
+}
+
+impl Word for u128 {
+    fn mul_mod(a: u128, b: u128, n: u128) -> u128 {
+        mul_mod_u128(a, b, n)
+    }
+
+    fn add_mod(a: u128, b: u128, n: u128) -> u128 {
+        add_mod_u128(a, b, n)
+    }
+
+    fn sub_mod(a: u128, b: u128, n: u128) -> u128 {
+        sub_mod_u128(a, b, n)
+    }
+
+    fn gcd(a: u128, b: u128) -> u128 {
+        gcd_u128(a, b)
+    }
+}
+
+// A point (X : Z) on a Montgomery curve By^2 = x^3 + Ax^2 + x, with (A + 2) / 4 = num / den.
+#[derive(Clone, Copy)]
+struct Point<W> {
+    x: W,
+    z: W,
+}
+
+struct Curve<W> {
+    n: W,
+    num: W,
+    den: W,
+}
+
+impl<W: Word> Curve<W> {
+    fn mul(&self, a: W, b: W) -> W {
+        W::mul_mod(a, b, self.n)
+    }
+
+    fn double(&self, p: Point<W>) -> Point<W> {
+        let s = W::add_mod(p.x, p.z, self.n);
+        let d = W::sub_mod(p.x, p.z, self.n);
+        let s2 = self.mul(s, s);
+        let d2 = self.mul(d, d);
+        let t = W::sub_mod(s2, d2, self.n);
+
+        Point {
+            x: self.mul(self.den, self.mul(s2, d2)),
+            z: self.mul(
+                t,
+                W::add_mod(self.mul(self.den, d2), self.mul(self.num, t), self.n),
+            ),
+        }
+    }
+
+    // P + Q, given the difference P - Q.
+    fn add(&self, p: Point<W>, q: Point<W>, diff: Point<W>) -> Point<W> {
+        let u = self.mul(W::sub_mod(p.x, p.z, self.n), W::add_mod(q.x, q.z, self.n));
+        let v = self.mul(W::add_mod(p.x, p.z, self.n), W::sub_mod(q.x, q.z, self.n));
+        let s = W::add_mod(u, v, self.n);
+        let d = W::sub_mod(u, v, self.n);
+
+        Point {
+            x: self.mul(diff.z, self.mul(s, s)),
+            z: self.mul(diff.x, self.mul(d, d)),
+        }
+    }
+
+    // Montgomery's ladder for kP, k >= 1.
+    fn multiply(&self, p: Point<W>, k: u64) -> Point<W> {
+        let mut r0 = p;
+        let mut r1 = self.double(p);
+
+        for bit in (0..63 - k.leading_zeros()).rev() {
+            if (k >> bit) & 1 == 1 {
+                r0 = self.add(r1, r0, p);
+                r1 = self.double(r1);
+            } else {
+                r1 = self.add(r1, r0, p);
+                r0 = self.double(r0);
+            }
+        }
+
+        r0
+    }
+}
+
+// Suyama's parametrization, which gives curves with a group order divisible by 12.
+fn suyama_curve<W: Word>(n: W, sigma: u64) -> (Curve<W>, Point<W>) {
+    let mul = |a, b| W::mul_mod(a, b, n);
+    let small = |k: u64| W::from(k) % n;
+
+    let sigma = small(sigma);
+    let u = W::sub_mod(mul(sigma, sigma), small(5), n);
+    let v = mul(small(4), sigma);
+    let u3 = mul(mul(u, u), u);
+    let v_u = W::sub_mod(v, u, n);
+
+    let num = mul(mul(mul(v_u, v_u), v_u), W::add_mod(mul(small(3), u), v, n));
+    let den = mul(mul(small(16), u3), v);
+
+    let curve = Curve { n, num, den };
+    let point = Point {
+        x: u3,
+        z: mul(mul(v, v), v),
+    };
+
+    (curve, point)
+}
+
+fn factor_from<W: Word>(g: W, n: W) -> Option<W> {
+    Some(g).filter(|&g| g > W::from(1) && g < n)
+}
+
+fn ecm_curve<W: Word>(n: W, sigma: u64, params: &EcmParams, primes: &[u64]) -> Option<W> {
+    let (curve, mut q) = suyama_curve(n, sigma);
+
+    // Stage 1: multiply by every prime power <= b1.
+    let stage1 = primes.partition_point(|&p| p <= params.b1);
+
+    for &p in &primes[..stage1] {
+        let mut power = p;
+
+        while let Some(next) = power.checked_mul(p).filter(|&next| next <= params.b1) {
+            power = next;
+        }
+
+        q = curve.multiply(q, power);
+    }
+
+    let g = W::gcd(q.z, n);
+
+    if g > W::from(1) {
+        return factor_from(g, n);
+    }
+
+    let stage2 = &primes[stage1..];
+
+    if stage2.is_empty() {
+        return None;
+    }
+
+    // Stage 2: baby steps jQ for odd j < D / 2 and giant steps kDQ, so that qQ = O modulo the
+    // hidden prime shows up as kDQ = ±jQ, detected through X_k Z_j - X_j Z_k.
+    let half = (STAGE2_D / 2) as usize;
+    let zero = W::from(0);
+    let mut baby = vec![Point { x: zero, z: zero }; half];
+    let q2 = curve.double(q);
+
+    baby[1] = q;
+    baby[3] = curve.add(q2, q, q);
+
+    for j in (5..half).step_by(2) {
+        baby[j] = curve.add(baby[j - 2], q2, baby[j - 4]);
+    }
+
+    let dq = curve.multiply(q, STAGE2_D);
+    let mut k = ((stage2[0] + STAGE2_D / 2) / STAGE2_D).max(2);
+    let mut giant_prev = curve.multiply(q, (k - 1) * STAGE2_D);
+    let mut giant = curve.multiply(q, k * STAGE2_D);
+
+    let mut product = W::from(1);
+
+    for (i, &p) in stage2.iter().enumerate() {
+        let target = (p + STAGE2_D / 2) / STAGE2_D;
+
+        let term = if target < 2 {
+            // Too close to the origin for the giant steps; multiply directly.
+            curve.multiply(q, p).z
+        } else {
+            while k < target {
+                let next = curve.add(giant, dq, giant_prev);
+                giant_prev = giant;
+                giant = next;
+                k += 1;
+            }
+
+            let j = p.abs_diff(k * STAGE2_D) as usize;
+            let b = baby[j];
+
+            W::sub_mod(curve.mul(giant.x, b.z), curve.mul(b.x, giant.z), n)
+        };
+
+        product = curve.mul(product, term);
+
+        if i % GCD_INTERVAL == GCD_INTERVAL - 1 {
+            let g = W::gcd(product, n);
+
+            if g > W::from(1) {
+                return factor_from(g, n);
+            }
+        }
+    }
+
+    factor_from(W::gcd(product, n), n)
+}
+
+/// Lenstra's elliptic curve method. Tries up to `params.curves` curves and returns a nontrivial
+/// factor of `n`, or `None` if none of them split it.
+pub fn ecm(n: u64, params: &EcmParams) -> Option<u64> {
+    if n < 4 {
+        return None;
+    }
+
+    if n.is_multiple_of(2) {
+        return Some(2);
+    }
+
+    if n.is_multiple_of(3) {
+        return Some(3);
+    }
+
+    let primes = sieve_primes(params.b1.max(params.b2));
+
+    (0..params.curves as u64).find_map(|i| ecm_curve(n, 6 + i, params, &primes))
+}
+
+/// [`ecm`] for a `u128`, with 128-bit curve arithmetic.
+pub fn ecm_u128(n: u128, params: &EcmParams) -> Option<u128> {
+    if n <= u64::MAX as u128 {
+        return ecm(n as u64, params).map(u128::from);
+    }
+
+    if n.is_multiple_of(2) {
+        return Some(2);
+    }
+
+    if n.is_multiple_of(3) {
+        return Some(3);
+    }
+
+    let primes = sieve_primes(params.b1.max(params.b2));
+
+    (0..params.curves as u64).find_map(|i| ecm_curve(n, 6 + i, params, &primes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ecm_01() {
+        let params = EcmParams::default();
+
+        for &(p, q) in &[
+            (1_000_003, 1_000_033),
+            (1_000_000_007, 4_294_967_279),
+            (2_147_483_647, 4_294_967_291),
+        ] {
+            let d = ecm(p * q, &params).unwrap();
+
+            assert!(d == p || d == q);
+        }
+
+        assert_eq!(ecm(1_000_000_007, &params), None);
+    }
+
+    #[test]
+    fn ecm_02() {
+        // Stage 1 alone is too weak for these bounds; stage 2 has to find the factor.
+        let params = EcmParams {
+            b1: 50,
+            b2: 50_000,
+            curves: 200,
+        };
+        let n = 1_000_003 * 1_000_033;
+        let d = ecm(n, &params).unwrap();
+
+        assert!(d == 1_000_003 || d == 1_000_033);
+    }
+
+    #[test]
+    fn ecm_u128_01() {
+        let params = EcmParams::default();
+        let p89 = (1u128 << 89) - 1;
+
+        assert_eq!(ecm_u128(4_294_967_291 * p89, &params), Some(4_294_967_291));
+        assert_eq!(ecm_u128(1_000_003 * 1_000_033, &params), Some(1_000_033));
+        assert_eq!(
+            ecm_u128(
+                p89,
+                &EcmParams {
+                    curves: 2,
+                    ..params
+                }
+            ),
+            None
+        );
+    }
+}
@@ -0,0 +1,86 @@
+// Squares modulo 64, as a bitmask, for rejecting most non-squares without a square root.
+pub(crate) const SQUARES_MOD_64: u64 = 0x0202_0212_0203_0213;
+
+fn exact_sqrt(v: u128) -> Option<u128> {
+    if (SQUARES_MOD_64 >> (v & 63)) & 1 == 0 {
+        return None;
+    }
+
+    let root = v.isqrt();
+
+    Some(root).filter(|&root| root * root == v)
+}
+
+/// Fermat's method, writing `n = a^2 - b^2 = (a - b)(a + b)` with `a` counting up from
+/// `ceil(sqrt(n))`. Very fast when `n` has a factor close to `sqrt(n)`, but the number of steps
+/// grows with the distance between the factors. Returns `None` for primes and for `n < 4`.
+pub fn fermat_factor(n: u64) -> Option<u64> {
+    if crate::is_prime(n) {
+        return None;
+    }
+
+    fermat_factor_bounded(n, u64::MAX)
+}
+
+// Fermat's method limited to the given number of steps.
+pub(crate) fn fermat_factor_bounded(n: u64, steps: u64) -> Option<u64> {
+    if n < 4 {
+        return None;
+    }
+
+    if n.is_multiple_of(2) {
+        return Some(2);
+    }
+
+    let n = n as u128;
+    let mut a = n.isqrt();
+
+    if a * a < n {
+        a += 1;
+    }
+
+    let mut b2 = a * a - n;
+
+    // For odd composite n = cd this stops by a = (c + d) / 2. Only a prime gets as far as
+    // a = (n + 1) / 2, giving the trivial factor 1.
+    for _ in 0..steps {
+        if let Some(b) = exact_sqrt(b2) {
+            let d = (a - b) as u64;
+
+            return Some(d).filter(|&d| d > 1);
+        }
+
+        b2 += 2 * a + 1;
+        a += 1;
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fermat_factor_01() {
+        assert_eq!(
+            fermat_factor(4_294_967_291 * 4_294_967_279),
+            Some(4_294_967_279)
+        );
+        assert_eq!(fermat_factor(1_000_003 * 1_000_033), Some(1_000_003));
+        assert_eq!(fermat_factor(1_000_003 * 1_000_003), Some(1_000_003));
+        assert_eq!(fermat_factor(3 * 1009), Some(3));
+        assert_eq!(fermat_factor(1009), None);
+        assert_eq!(fermat_factor(1_000_000_007), None);
+        assert_eq!(fermat_factor(20), Some(2));
+    }
+
+    #[test]
+    fn fermat_factor_02() {
+        assert_eq!(
+            fermat_factor_bounded(1_000_003 * 1_000_033, 1),
+            Some(1_000_003)
+        );
+        assert_eq!(fermat_factor_bounded(1_009 * 1_000_033, 1000), None);
+    }
+}
@@ -0,0 +1,181 @@
+use crate::modular::gcd;
+use crate::sieve::sieve_primes;
+
+fn icbrt(n: u64) -> u64 {
+    let mut r = (n as f64).cbrt() as u64;
+
+    while r.checked_pow(3).is_none_or(|c| c > n) {
+        r -= 1;
+    }
+
+    while (r + 1).checked_pow(3).is_some_and(|c| c <= n) {
+        r += 1;
+    }
+
+    r
+}
+
+fn exact_sqrt(v: u128) -> Option<u128> {
+    let root = v.isqrt();
+
+    Some(root).filter(|&root| root * root == v)
+}
+
+// The smallest prime factor of n up to bound, if there is one.
+fn trial_divide(n: u64, bound: u64) -> Option<u64> {
+    sieve_primes(bound)
+        .into_iter()
+        .find(|&p| n.is_multiple_of(p) && p < n)
+}
+
+/// Lehman's method. Deterministically finds a nontrivial factor of `n` in `O(n^(1/3))` time, or
+/// proves that `n` is prime by returning `None`.
+pub fn lehman_factor(n: u64) -> Option<u64> {
+    if n < 4 {
+        return None;
+    }
+
+    let cbrt = icbrt(n);
+
+    if let Some(p) = trial_divide(n, cbrt) {
+        return Some(p);
+    }
+
+    lehman_without_small_factors(n, cbrt)
+}
+
+fn lehman_without_small_factors(n: u64, cbrt: u64) -> Option<u64> {
+    // With no factor <= n^(1/3), either n is prime or some a^2 - 4kn is a perfect square b^2 with
+    // k <= n^(1/3) and sqrt(4kn) <= a <= sqrt(4kn) + n^(1/6) / (4 sqrt(k)).
+    let sixth_root = (n as f64).powf(1.0 / 6.0);
+    let n = n as u128;
+
+    for k in 1..=cbrt as u128 {
+        let four_kn = 4 * k * n;
+        let mut a = four_kn.isqrt();
+
+        if a * a < four_kn {
+            a += 1;
+        }
+
+        let upper = four_kn.isqrt() + (sixth_root / (4.0 * (k as f64).sqrt())).ceil() as u128;
+
+        while a <= upper {
+            if let Some(b) = exact_sqrt(a * a - four_kn) {
+                let g = gcd((((a + b) % n) as u64).max(1), n as u64);
+
+                if g > 1 && (g as u128) < n {
+                    return Some(g);
+                }
+            }
+
+            a += 1;
+        }
+    }
+
+    None
+}
+
+/// Hart's one line factoring algorithm. Heuristically finds a factor of `n` in `O(n^(1/3))`
+/// steps, and is often much faster. Returns `None` for primes, or if no factor turns up within
+/// `n^(1/3)` multipliers.
+pub fn hart_factor(n: u64) -> Option<u64> {
+    if n < 4 || crate::is_prime(n) {
+        return None;
+    }
+
+    let cbrt = icbrt(n);
+
+    if let Some(p) = trial_divide(n, cbrt) {
+        return Some(p);
+    }
+
+    hart_factor_bounded(n, cbrt)
+}
+
+// Hart's suggested multiplier; 480 = 2^5 * 3 * 5 makes s^2 mod n a square far more often.
+const HART_MULTIPLIER: u128 = 480;
+
+fn hart_factor_bounded(n: u64, steps: u64) -> Option<u64> {
+    let n = n as u128;
+
+    for i in 1..=steps as u128 {
+        let ni = n * HART_MULTIPLIER * i;
+        let mut s = ni.isqrt();
+
+        if s * s < ni {
+            s += 1;
+        }
+
+        let m = (s * s) % n;
+
+        if let Some(t) = exact_sqrt(m) {
+            let g = gcd(((s - t) % n) as u64, n as u64);
+
+            if g > 1 && (g as u128) < n {
+                return Some(g);
+            }
+        }
+    }
+
+    None
+}
+
+// Splits the composite n deterministically: trial division to n^(1/3), then Hart's method for
+// the common case, then Lehman's method for its guarantee.
+pub(crate) fn split_deterministic(n: u64) -> u64 {
+    let cbrt = icbrt(n);
+
+    trial_divide(n, cbrt)
+        .or_else(|| hart_factor_bounded(n, cbrt))
+        .or_else(|| lehman_without_small_factors(n, cbrt))
+        .unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn icbrt_01() {
+        for n in [0, 1, 7, 8, 9, 26, 27, 28, 999_999_999_999, u64::MAX] {
+            let r = icbrt(n) as u128;
+
+            assert!(r * r * r <= n as u128 && (r + 1) * (r + 1) * (r + 1) > n as u128);
+        }
+    }
+
+    #[test]
+    fn lehman_factor_01() {
+        for &(p, q) in &[
+            (3, 5),
+            (101, 103),
+            (1_000_003, 1_000_033),
+            (1_000_000_007, 998_244_353),
+            (1_009, 1_000_000_007),
+        ] {
+            let d = lehman_factor(p * q).unwrap();
+
+            assert!(d == p || d == q);
+        }
+
+        assert_eq!(lehman_factor(1_000_000_007), None);
+        assert_eq!(lehman_factor(4_294_967_291), None);
+    }
+
+    #[test]
+    fn hart_factor_01() {
+        for &(p, q) in &[
+            (101, 103),
+            (1_000_003, 1_000_033),
+            (1_000_000_007, 998_244_353),
+            (1_009, 1_000_000_007),
+        ] {
+            let d = hart_factor(p * q).unwrap();
+
+            assert!(d == p || d == q);
+        }
+
+        assert_eq!(hart_factor(1_000_000_007), None);
+    }
+}
@@ -0,0 +1,98 @@
+/// Finds subsets of `rows` whose sum over GF(2) is zero, where each row lists the columns
+/// below `columns` in which it has a one.
+///
+/// The matrix is stored transposed, one bit per row, and reduced to row echelon form. Every row
+/// that does not get a pivot is then a free variable of the null space, and gives one dependency.
+pub(crate) fn gf2_dependencies(columns: usize, rows: &[Vec<usize>]) -> Vec<Vec<usize>> {
+    let words = rows.len().div_ceil(64);
+    let mut matrix = vec![vec![0u64; words]; columns];
+
+    for (r, row) in rows.iter().enumerate() {
+        for &c in row {
+            matrix[c][r / 64] ^= 1 << (r % 64);
+        }
+    }
+
+    let mut pivots = vec![];
+
+    for r in 0..rows.len() {
+        let rank = pivots.len();
+        let (word, bit) = (r / 64, 1 << (r % 64));
+
+        let Some(found) = (rank..columns).find(|&c| matrix[c][word] & bit != 0) else {
+            continue;
+        };
+
+        matrix.swap(rank, found);
+
+        let (above, rest) = matrix.split_at_mut(rank);
+        let (pivot, below) = rest.split_first_mut().unwrap();
+
+        for line in above.iter_mut().chain(below) {
+            if line[word] & bit != 0 {
+                for (w, p) in line.iter_mut().zip(pivot.iter()) {
+                    *w ^= p;
+                }
+            }
+        }
+
+        pivots.push(r);
+    }
+
+    let mut is_pivot = vec![false; rows.len()];
+
+    for &r in &pivots {
+        is_pivot[r] = true;
+    }
+
+    (0..rows.len())
+        .filter(|&r| !is_pivot[r])
+        .map(|free| {
+            let (word, bit) = (free / 64, 1 << (free % 64));
+            let mut dependency = vec![free];
+
+            dependency.extend(
+                pivots
+                    .iter()
+                    .enumerate()
+                    .filter(|&(i, _)| matrix[i][word] & bit != 0)
+                    .map(|(_, &r)| r),
+            );
+
+            dependency
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn gf2_dependencies_01() {
+        let rows = vec![
+            vec![0, 1],
+            vec![1, 2],
+            vec![0, 2],
+            vec![3],
+            vec![0, 1, 2, 3],
+            vec![],
+        ];
+        let dependencies = gf2_dependencies(4, &rows);
+
+        assert_eq!(dependencies.len(), 2);
+
+        for dependency in &dependencies {
+            let mut sum = [false; 4];
+
+            for &r in dependency {
+                for &c in &rows[r] {
+                    sum[c] ^= true;
+                }
+            }
+
+            assert!(!dependency.is_empty());
+            assert_eq!(sum, [false; 4]);
+        }
+    }
+}
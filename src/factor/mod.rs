@@ -0,0 +1,763 @@
+#[cfg(feature = "bigint")]
+mod big;
+mod cfrac;
+mod ecm;
+mod fermat;
+mod lehman;
+mod linalg;
+mod pm1;
+mod pp1;
+mod rho;
+#[cfg(feature = "bigint")]
+mod siqs;
+mod squfof;
+mod tree;
+mod wide;
+
+#[cfg(feature = "bigint")]
+pub use big::*;
+pub use cfrac::*;
+pub use ecm::*;
+pub use fermat::*;
+pub use lehman::*;
+pub use pm1::*;
+pub use pp1::*;
+pub use rho::*;
+#[cfg(feature = "bigint")]
+pub use siqs::*;
+pub use squfof::*;
+pub use tree::*;
+pub use wide::*;
+
+use std::borrow::Cow;
+use std::iter::{self, FusedIterator};
+use std::sync::OnceLock;
+use std::{slice, vec};
+
+use crate::sieve::sieve_primes;
+use crate::{is_prime, Factorization};
+
+// Trial division handles every prime below this bound before the general methods take over.
+const TRIAL_DIVISION_BOUND: u64 = 1 << 10;
+
+// The primes below the trial division bound, shared by every thread.
+pub(crate) fn trial_primes() -> &'static [u64] {
+    static PRIMES: OnceLock<Vec<u64>> = OnceLock::new();
+
+    PRIMES.get_or_init(|| sieve_primes(TRIAL_DIVISION_BOUND - 1))
+}
+
+// The primes below bound, borrowed from the shared table when it is big enough.
+fn trial_primes_below(bound: u64) -> Cow<'static, [u64]> {
+    let primes = trial_primes();
+
+    if bound <= TRIAL_DIVISION_BOUND {
+        Cow::Borrowed(&primes[..primes.partition_point(|&p| p < bound)])
+    } else {
+        Cow::Owned(sieve_primes(bound - 1))
+    }
+}
+
+// A handful of Fermat steps is cheap, and catches factors near sqrt(n) that would cost rho
+// around n^(1/4) iterations.
+const FERMAT_STEPS: u64 = 32;
+
+/// The method that splits whatever the earlier stages of the pipeline could not.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum FinalMethod {
+    /// Pollard's rho with Brent's cycle detection. Fast in practice.
+    #[default]
+    PollardRho,
+    /// Hart's one line factoring, backed by Lehman's method, which guarantees `O(n^(1/3))`.
+    Lehman,
+    /// Trial division up to the square root. `O(sqrt(n))`, but the smallest in code.
+    TrialDivision,
+}
+
+/// Selects which methods the factoring pipeline runs on cofactors that survive trial division.
+/// The final method always runs last, so every configuration factors completely.
+///
+/// [`FactorConfig::trial_division_only`] forbids every method but trial division, for targets
+/// where code size matters more than speed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FactorConfig {
+    /// Trial divide by every prime below this bound before any other method runs. A bound up to
+    /// 1024 reads a shared table of primes, but a higher one sieves its primes afresh on every
+    /// call, which costs `O(bound)` time and memory per number factored.
+    pub trial_division_bound: u64,
+    /// The number of steps of Fermat's method to try first, for factors close to the square root.
+    pub fermat_steps: u64,
+    /// Run Pollard's p − 1 method with these `(b1, b2)` bounds before rho.
+    pub pm1_bounds: Option<(u64, u64)>,
+    /// Run Williams' p + 1 method with these `(b1, b2)` bounds before rho.
+    pub pp1_bounds: Option<(u64, u64)>,
+    /// Run the elliptic curve method with these parameters before rho.
+    pub ecm: Option<EcmParams>,
+    /// Run SQUFOF before rho. Off by default, as rho is several times faster at every size.
+    pub squfof: bool,
+    pub final_method: FinalMethod,
+}
+
+impl Default for FactorConfig {
+    fn default() -> FactorConfig {
+        FactorConfig {
+            trial_division_bound: TRIAL_DIVISION_BOUND,
+            fermat_steps: FERMAT_STEPS,
+            pm1_bounds: None,
+            pp1_bounds: None,
+            ecm: None,
+            squfof: false,
+            final_method: FinalMethod::PollardRho,
+        }
+    }
+}
+
+impl FactorConfig {
+    /// Trial division alone, up to the square root of each cofactor.
+    pub fn trial_division_only() -> FactorConfig {
+        FactorConfig {
+            fermat_steps: 0,
+            final_method: FinalMethod::TrialDivision,
+            ..FactorConfig::default()
+        }
+    }
+}
+
+/// The factorization of `n` through the pipeline selected by `config`. The factorization of 1 is
+/// empty. Panics if `n` is 0, which has none; see [`try_factorization_with`].
+pub fn factorization_with(n: u64, config: &FactorConfig) -> Factorization {
+    let mut factorization = Factorization::new();
+
+    factorize_into_with(n, config, &mut factorization);
+    factorization
+}
+
+/// Replaces the contents of `factorization` with the factorization of `n` through the pipeline
+/// selected by `config`, reusing its storage. Panics if `n` is 0.
+pub fn factorize_into_with(n: u64, config: &FactorConfig, factorization: &mut Factorization) {
+    assert!(n != 0, "0 has no factorization");
+
+    factorization.clear();
+
+    let mut k = n;
+
+    for &p in trial_primes_below(config.trial_division_bound).iter() {
+        if p > k / p {
+            // k has no prime factor up to its square root, so it is 1 or prime.
+            factorization.insert(k, (k > 1) as u32);
+            return;
+        }
+
+        let mut e = 0;
+
+        while k.is_multiple_of(p) {
+            k /= p;
+            e += 1;
+        }
+
+        factorization.insert(p, e);
+    }
+
+    if k > 1 {
+        split(k, config, factorization);
+    }
+}
+
+/// The factorization of `n` through the pipeline selected by `config`, or `None` if `n` is 0.
+pub fn try_factorization_with(n: u64, config: &FactorConfig) -> Option<Factorization> {
+    (n != 0).then(|| factorization_with(n, config))
+}
+
+/// The factorizations of every `n` in `lo..hi`, found by one sieving pass over the window for
+/// each prime up to `sqrt(hi)` rather than by factoring each element. `lo` must be nonzero.
+pub fn factorize_range(lo: u64, hi: u64) -> Vec<Factorization> {
+    assert!(lo != 0, "0 has no factorization");
+
+    if hi <= lo {
+        return vec![];
+    }
+
+    let len = (hi - lo) as usize;
+    let mut remaining: Vec<u64> = (lo..hi).collect();
+    let mut factorizations = vec![Factorization::new(); len];
+
+    for p in sieve_primes((hi - 1).isqrt()) {
+        let mut i = (lo.div_ceil(p) * p - lo) as usize;
+
+        while i < len {
+            let mut e = 0;
+
+            while remaining[i].is_multiple_of(p) {
+                remaining[i] /= p;
+                e += 1;
+            }
+
+            factorizations[i].insert(p, e);
+            i += p as usize;
+        }
+    }
+
+    for (factorization, &rest) in factorizations.iter_mut().zip(&remaining) {
+        if rest > 1 {
+            factorization.insert(rest, 1);
+        }
+    }
+
+    factorizations
+}
+
+/// The factorizations of every element of `ns`, computed in parallel on the rayon thread pool.
+#[cfg(feature = "rayon")]
+pub fn par_factorize(ns: &[u64]) -> Vec<Factorization> {
+    use rayon::prelude::*;
+
+    let config = FactorConfig::default();
+
+    ns.par_iter()
+        .map(|&n| factorization_with(n, &config))
+        .collect()
+}
+
+/// Fills `factors` with the prime factors of `n` in increasing order, repeated by multiplicity,
+/// leaving it empty for 1. Panics if `n` is 0.
+pub fn factorize_with(n: u64, config: &FactorConfig, factors: &mut Vec<u64>) {
+    factors.clear();
+
+    for (p, e) in factorization_with(n, config) {
+        factors.extend(iter::repeat_n(p, e as usize));
+    }
+}
+
+/// A factorization that may have stopped short, leaving some composite cofactors unsplit.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct PartialFactorization {
+    /// The prime factors found.
+    pub factors: Factorization,
+    /// The composite cofactors left when the budget ran out, in increasing order.
+    pub composites: Vec<u64>,
+}
+
+impl PartialFactorization {
+    pub fn is_complete(&self) -> bool {
+        self.composites.is_empty()
+    }
+}
+
+// Serialized as an object with the `factors` and the `composites`. Deserializing checks that no
+// composite is 1 or prime, and sorts them.
+#[cfg(feature = "serde")]
+impl serde::Serialize for PartialFactorization {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeStruct;
+
+        let mut state = serializer.serialize_struct("PartialFactorization", 2)?;
+
+        state.serialize_field("factors", &self.factors)?;
+        state.serialize_field("composites", &self.composites)?;
+        state.end()
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for PartialFactorization {
+    fn deserialize<D: serde::Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<PartialFactorization, D::Error> {
+        deserializer.deserialize_struct(
+            "PartialFactorization",
+            &["factors", "composites"],
+            PartialFactorizationVisitor,
+        )
+    }
+}
+
+#[cfg(feature = "serde")]
+struct PartialFactorizationVisitor;
+
+#[cfg(feature = "serde")]
+impl<'de> serde::de::Visitor<'de> for PartialFactorizationVisitor {
+    type Value = PartialFactorization;
+
+    fn expecting(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "a partial factorization")
+    }
+
+    fn visit_map<A: serde::de::MapAccess<'de>>(
+        self,
+        mut map: A,
+    ) -> Result<PartialFactorization, A::Error> {
+        use serde::de::Error;
+
+        let (mut factors, mut composites) = (None, None::<Vec<u64>>);
+
+        while let Some(key) = map.next_key::<String>()? {
+            match key.as_str() {
+                "factors" => factors = Some(map.next_value()?),
+                "composites" => composites = Some(map.next_value()?),
+                _ => return Err(A::Error::unknown_field(&key, &["factors", "composites"])),
+            }
+        }
+
+        let mut composites = composites.ok_or_else(|| A::Error::missing_field("composites"))?;
+
+        if let Some(&c) = composites.iter().find(|&&c| c == 1 || is_prime(c)) {
+            return Err(A::Error::custom(format!("{c} is not composite")));
+        }
+
+        composites.sort_unstable();
+
+        Ok(PartialFactorization {
+            factors: factors.ok_or_else(|| A::Error::missing_field("factors"))?,
+            composites,
+        })
+    }
+}
+
+/// Factors `n`, spending at most about `effort` steps of Pollard's rho across all cofactors.
+///
+/// Trial division and a few Fermat steps always run. Cofactors still composite once the budget
+/// is spent are returned in [`PartialFactorization::composites`], so callers can bound the
+/// worst-case latency on adversarial inputs. 0 is returned as the only composite.
+pub fn try_factorize(n: u64, effort: u64) -> PartialFactorization {
+    let mut partial = PartialFactorization::default();
+    let mut k = n;
+
+    if n == 0 {
+        partial.composites.push(0);
+        return partial;
+    }
+
+    for &p in trial_primes() {
+        if p * p > k {
+            partial.factors.insert(k, (k > 1) as u32);
+            return partial;
+        }
+
+        let mut e = 0;
+
+        while k.is_multiple_of(p) {
+            k /= p;
+            e += 1;
+        }
+
+        partial.factors.insert(p, e);
+    }
+
+    let mut budget = effort;
+    let mut pending = vec![];
+
+    // Trial division can use up every prime and still leave nothing, as for 1021^2.
+    if k > 1 {
+        pending.push(k);
+    }
+
+    while let Some(m) = pending.pop() {
+        if is_prime(m) {
+            partial.factors.insert(m, 1);
+            continue;
+        }
+
+        let mut d = fermat::fermat_factor_bounded(m, FERMAT_STEPS);
+        let mut c = 1;
+
+        while d.is_none() && budget > 0 {
+            d = rho::pollard_brent(m, 2, c, &mut budget);
+            c += 1;
+        }
+
+        match d {
+            Some(d) => pending.extend([d, m / d]),
+            None => partial.composites.push(m),
+        }
+    }
+
+    partial.composites.sort_unstable();
+    partial
+}
+
+/// An iterator over the `(prime, exponent)` pairs of a number in increasing order of prime,
+/// which only factors as far as it is advanced.
+///
+/// Prime factors below the trial division bound come out one at a time. The cofactor left after
+/// them is factored in full on the first call that needs it.
+pub struct FactorIter {
+    remaining: u64,
+    primes: slice::Iter<'static, u64>,
+    rest: Option<vec::IntoIter<(u64, u32)>>,
+}
+
+impl Iterator for FactorIter {
+    type Item = (u64, u32);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(rest) = &mut self.rest {
+            return rest.next();
+        }
+
+        while self.remaining > 1 {
+            let Some(&p) = self.primes.next() else {
+                let mut factorization = Factorization::new();
+
+                split(self.remaining, &FactorConfig::default(), &mut factorization);
+                self.remaining = 1;
+
+                return self.rest.insert(factorization.into_iter()).next();
+            };
+
+            if p * p > self.remaining {
+                let p = self.remaining;
+
+                self.remaining = 1;
+                return Some((p, 1));
+            }
+
+            let mut e = 0;
+
+            while self.remaining.is_multiple_of(p) {
+                self.remaining /= p;
+                e += 1;
+            }
+
+            if e > 0 {
+                return Some((p, e));
+            }
+        }
+
+        None
+    }
+}
+
+impl FusedIterator for FactorIter {}
+
+/// Panics if `n` is 0.
+pub fn factor_iter(n: u64) -> FactorIter {
+    assert!(n != 0, "0 has no factorization");
+
+    FactorIter {
+        remaining: n,
+        primes: trial_primes().iter(),
+        rest: None,
+    }
+}
+
+/// The distinct prime factors of `n` in increasing order, lazily as for [`factor_iter`]. Panics
+/// if `n` is 0.
+pub fn distinct_prime_factors(n: u64) -> impl FusedIterator<Item = u64> {
+    factor_iter(n).map(|(p, _)| p)
+}
+
+// Inserts the prime factors of n > 1.
+fn split(n: u64, config: &FactorConfig, factorization: &mut Factorization) {
+    if is_prime(n) {
+        factorization.insert(n, 1);
+        return;
+    }
+
+    let (d, _) = find_factor(n, config);
+
+    split(d, config, factorization);
+    split(n / d, config, factorization);
+}
+
+// A nontrivial factor of the composite n, and the method that found it.
+fn find_factor(n: u64, config: &FactorConfig) -> (u64, SplitMethod) {
+    // A low trial division bound can leave small factors, which SQUFOF does not accept.
+    if n.is_multiple_of(2) {
+        return (2, SplitMethod::TrialDivision);
+    }
+
+    if let Some(d) = fermat::fermat_factor_bounded(n, config.fermat_steps) {
+        return (d, SplitMethod::Fermat);
+    }
+
+    if let Some((b1, b2)) = config.pm1_bounds {
+        if let Some(d) = pollard_pm1(n, b1, b2) {
+            return (d, SplitMethod::PollardPm1);
+        }
+    }
+
+    if let Some((b1, b2)) = config.pp1_bounds {
+        if let Some(d) = williams_pp1(n, b1, b2) {
+            return (d, SplitMethod::WilliamsPp1);
+        }
+    }
+
+    if config.squfof {
+        if let Some(d) = squfof(n) {
+            return (d, SplitMethod::Squfof);
+        }
+    }
+
+    if let Some(params) = &config.ecm {
+        if let Some(d) = ecm(n, params) {
+            return (d, SplitMethod::Ecm);
+        }
+    }
+
+    match config.final_method {
+        FinalMethod::PollardRho => (pollard_rho(n).unwrap(), SplitMethod::PollardRho),
+        FinalMethod::Lehman => (lehman::split_deterministic(n), SplitMethod::Lehman),
+        FinalMethod::TrialDivision => {
+            let d = (3..).step_by(2).find(|&d| n.is_multiple_of(d)).unwrap();
+
+            (d, SplitMethod::TrialDivision)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn factor_iter_01() {
+        let n = 2 * 2 * 3 * 1021 * 1_000_003 * 1_000_033;
+        let all: Vec<_> = factor_iter(n).collect();
+
+        assert_eq!(
+            all,
+            vec![(2, 2), (3, 1), (1021, 1), (1_000_003, 1), (1_000_033, 1)]
+        );
+        assert_eq!(
+            all,
+            factorization_with(n, &FactorConfig::default()).as_slice()
+        );
+        assert_eq!(factor_iter(n).next(), Some((2, 2)));
+        assert_eq!(factor_iter(1).next(), None);
+        assert_eq!(
+            factor_iter(18_446_744_073_709_551_557).collect::<Vec<_>>(),
+            vec![(18_446_744_073_709_551_557, 1)]
+        );
+    }
+
+    #[test]
+    fn distinct_prime_factors_01() {
+        assert_eq!(distinct_prime_factors(1).next(), None);
+        assert_eq!(
+            distinct_prime_factors(2 * 2 * 2 * 3 * 3 * 1_000_003 * 1_000_003).collect::<Vec<_>>(),
+            vec![2, 3, 1_000_003]
+        );
+
+        for n in 1..2000 {
+            assert!(distinct_prime_factors(n).eq(factor_iter(n).map(|(p, _)| p)));
+        }
+    }
+
+    #[test]
+    fn factorization_with_01() {
+        let config = FactorConfig::default();
+
+        assert!(factorization_with(1, &config).is_empty());
+        assert_eq!(try_factorization_with(0, &config), None);
+        assert_eq!(
+            try_factorization_with(12, &config),
+            Some(factorization_with(12, &config))
+        );
+        assert_eq!(factorization_with(2, &config).as_slice(), &[(2, 1)]);
+        assert_eq!(
+            factorization_with(1019 * 1019, &config).as_slice(),
+            &[(1019, 2)]
+        );
+        assert_eq!(
+            factorization_with(2 * 3 * 1013, &config).as_slice(),
+            &[(2, 1), (3, 1), (1013, 1)]
+        );
+        assert_eq!(
+            factorization_with(999_983, &config).as_slice(),
+            &[(999_983, 1)]
+        );
+
+        for n in 2..5000u64 {
+            let expected: Vec<_> = (2..=n)
+                .filter(|&p| n.is_multiple_of(p) && is_prime(p))
+                .map(|p| {
+                    let mut e = 0;
+                    let mut m = n;
+
+                    while m.is_multiple_of(p) {
+                        m /= p;
+                        e += 1;
+                    }
+
+                    (p, e)
+                })
+                .collect();
+
+            assert_eq!(factorization_with(n, &config).as_slice(), &expected[..]);
+            assert_eq!(factor_iter(n).collect::<Vec<_>>(), expected);
+        }
+    }
+
+    #[test]
+    fn factorize_range_01() {
+        let config = FactorConfig::default();
+
+        for (lo, hi) in [(1, 2000), (1_000_000_000_000, 1_000_000_001_000)] {
+            let factorizations = factorize_range(lo, hi);
+
+            assert_eq!(factorizations.len(), (hi - lo) as usize);
+
+            for (n, f) in (lo..hi).zip(&factorizations) {
+                assert_eq!(*f, factorization_with(n, &config), "{n}");
+            }
+        }
+
+        assert!(factorize_range(10, 10).is_empty());
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn par_factorize_01() {
+        let config = FactorConfig::default();
+        let ns: Vec<u64> = (0..2000u64)
+            .map(|i| 1 + i.wrapping_mul(0x9e37_79b9_7f4a_7c15) % (1 << 60))
+            .collect();
+        let factorizations = par_factorize(&ns);
+
+        for (&n, f) in ns.iter().zip(&factorizations) {
+            assert_eq!(*f, factorization_with(n, &config));
+        }
+
+        assert!(par_factorize(&[]).is_empty());
+    }
+
+    #[test]
+    fn try_factorize_01() {
+        let n = 3 * 3 * 1_000_003 * 1_000_033;
+        let complete = try_factorize(n, u64::MAX);
+
+        assert!(complete.is_complete());
+        assert_eq!(
+            complete.factors,
+            factorization_with(n, &FactorConfig::default())
+        );
+
+        // Fermat's method cannot split these quickly, and ten rho steps are not enough.
+        let hard = 1_000_003 * 4_294_967_291;
+        let partial = try_factorize(2 * hard, 10);
+
+        assert_eq!(partial.factors.as_slice(), &[(2, 1)]);
+        assert_eq!(partial.composites, vec![hard]);
+        assert!(!partial.is_complete());
+
+        assert_eq!(try_factorize(1, 0), PartialFactorization::default());
+        assert_eq!(try_factorize(0, u64::MAX).composites, vec![0]);
+        assert_eq!(try_factorize(1021, 0).factors.as_slice(), &[(1021, 1)]);
+        assert!(try_factorize(hard, 1_000_000).is_complete());
+
+        for n in [1021 * 1021, 2 * 1021 * 1021 * 1021] {
+            for effort in [10, u64::MAX] {
+                let partial = try_factorize(n, effort);
+
+                assert!(partial.is_complete(), "{n}");
+                assert_eq!(partial.factors.value(), Some(n));
+            }
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn partial_factorization_serde_01() {
+        let hard = 1_000_003 * 4_294_967_291;
+        let partial = try_factorize(2 * hard, 10);
+        let json = serde_json::to_string(&partial).unwrap();
+
+        assert_eq!(
+            json,
+            format!(r#"{{"factors":[[2,1]],"composites":[{hard}]}}"#)
+        );
+        assert_eq!(
+            serde_json::from_str::<PartialFactorization>(&json).unwrap(),
+            partial
+        );
+        assert_eq!(
+            serde_json::from_str::<PartialFactorization>(r#"{"composites":[15,0,4],"factors":[]}"#)
+                .unwrap()
+                .composites,
+            vec![0, 4, 15]
+        );
+
+        for bad in [
+            r#"{"factors":[],"composites":[7]}"#,
+            r#"{"factors":[],"composites":[1]}"#,
+            r#"{"factors":[[4,1]],"composites":[]}"#,
+            r#"{"factors":[]}"#,
+        ] {
+            assert!(
+                serde_json::from_str::<PartialFactorization>(bad).is_err(),
+                "{bad}"
+            );
+        }
+    }
+
+    #[test]
+    fn factorize_with_01() {
+        let configs = [
+            FactorConfig::default(),
+            FactorConfig {
+                pm1_bounds: Some((1000, 100_000)),
+                ..FactorConfig::default()
+            },
+            FactorConfig {
+                pp1_bounds: Some((1000, 100_000)),
+                ..FactorConfig::default()
+            },
+            FactorConfig {
+                pm1_bounds: Some((100, 1000)),
+                pp1_bounds: Some((100, 1000)),
+                ..FactorConfig::default()
+            },
+            FactorConfig {
+                ecm: Some(EcmParams::default()),
+                ..FactorConfig::default()
+            },
+            FactorConfig {
+                squfof: true,
+                ..FactorConfig::default()
+            },
+            FactorConfig {
+                fermat_steps: 0,
+                ..FactorConfig::default()
+            },
+            FactorConfig {
+                fermat_steps: 0,
+                final_method: FinalMethod::Lehman,
+                ..FactorConfig::default()
+            },
+            FactorConfig {
+                trial_division_bound: 0,
+                squfof: true,
+                ..FactorConfig::default()
+            },
+            FactorConfig {
+                trial_division_bound: 1 << 16,
+                ..FactorConfig::default()
+            },
+        ];
+        let mut fs = vec![];
+
+        for config in &configs {
+            factorize_with(1_000_669 * 2_000_029 * 1031, config, &mut fs);
+            assert_eq!(fs, vec![1031, 1_000_669, 2_000_029]);
+
+            factorize_with(1_000_033 * 1_000_033 * 2, config, &mut fs);
+            assert_eq!(fs, vec![2, 1_000_033, 1_000_033]);
+
+            factorize_with(4_294_967_291 * 4_294_967_279, config, &mut fs);
+            assert_eq!(fs, vec![4_294_967_279, 4_294_967_291]);
+
+            assert_eq!(
+                factorization_with(1_000_033 * 1_000_033 * 8, config).as_slice(),
+                &[(2, 3), (1_000_033, 2)]
+            );
+        }
+
+        let config = FactorConfig::trial_division_only();
+
+        factorize_with(1_000_669 * 2_000_029 * 1031, &config, &mut fs);
+        assert_eq!(fs, vec![1031, 1_000_669, 2_000_029]);
+
+        factorize_with(1_000_033 * 1_000_033 * 2, &config, &mut fs);
+        assert_eq!(fs, vec![2, 1_000_033, 1_000_033]);
+    }
+}
@@ -0,0 +1,110 @@
+use crate::modular::{gcd, mod_pow, mul_mod};
+use crate::sieve::sieve_primes;
+
+// How many stage 2 primes are accumulated into the product between gcds.
+const GCD_INTERVAL: usize = 64;
+
+/// Pollard's p − 1 method. Finds a factor `d` of `n` when some prime `p | n` has `p − 1` built
+/// from prime powers `<= b1` and at most one further prime `<= b2`.
+pub fn pollard_pm1(n: u64, b1: u64, b2: u64) -> Option<u64> {
+    if n < 4 {
+        return None;
+    }
+
+    if n.is_multiple_of(2) {
+        return Some(2);
+    }
+
+    let primes = sieve_primes(b1.max(b2));
+    let stage1 = primes.partition_point(|&p| p <= b1);
+
+    // Stage 1: a = 2^M, where M is the product of all prime powers <= b1.
+    let mut a = 2;
+
+    for &p in &primes[..stage1] {
+        let mut q = p;
+
+        while let Some(next) = q.checked_mul(p).filter(|&next| next <= b1) {
+            q = next;
+        }
+
+        a = mod_pow(a, q, n);
+    }
+
+    let g = gcd(a - 1, n);
+
+    if g == n {
+        return None;
+    }
+
+    if g > 1 {
+        return Some(g);
+    }
+
+    // Stage 2: step through the odd primes q in (b1, b2] multiplying a^q - 1 together, moving
+    // between consecutive primes with cached powers a^gap.
+    let stage2 = &primes[primes.partition_point(|&p| p <= b1.max(2))..];
+
+    if stage2.is_empty() {
+        return None;
+    }
+
+    let a2 = mul_mod(a, a, n);
+    let mut gaps = vec![a2];
+    let mut x = mod_pow(a, stage2[0], n);
+    let mut product = 1;
+
+    for (i, window) in stage2.windows(2).enumerate() {
+        product = mul_mod(product, x - 1, n);
+
+        if i % GCD_INTERVAL == 0 {
+            let g = gcd(product, n);
+
+            if g > 1 {
+                return Some(g).filter(|&g| g < n);
+            }
+        }
+
+        let gap = ((window[1] - window[0]) / 2) as usize;
+
+        while gaps.len() < gap {
+            let last = *gaps.last().unwrap();
+            gaps.push(mul_mod(last, a2, n));
+        }
+
+        x = mul_mod(x, gaps[gap - 1], n);
+    }
+
+    product = mul_mod(product, x - 1, n);
+
+    Some(gcd(product, n)).filter(|&g| g > 1 && g < n)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pollard_pm1_01() {
+        // 1_000_033 - 1 = 2^5 * 3 * 11 * 947, and 1_000_003 - 1 = 2 * 3 * 166667.
+        let n = 1_000_033 * 1_000_003;
+
+        assert_eq!(pollard_pm1(n, 1000, 1000), Some(1_000_033));
+        assert_eq!(pollard_pm1(n, 100, 200), None);
+
+        // Stage 2 picks up the single large factor 947.
+        assert_eq!(pollard_pm1(n, 100, 1000), Some(1_000_033));
+    }
+
+    #[test]
+    fn pollard_pm1_02() {
+        assert_eq!(pollard_pm1(1_000_000_007, 1000, 100_000), None);
+        assert_eq!(pollard_pm1(2 * 1_000_000_007, 1000, 100_000), Some(2));
+
+        // With no stage 1 primes, stage 2 still starts from 3.
+        let n = 1_000_033 * 1_000_003;
+
+        assert_eq!(pollard_pm1(n, 0, 1000), None);
+        assert_eq!(pollard_pm1(n, 1, 1000), None);
+    }
+}
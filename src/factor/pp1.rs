@@ -0,0 +1,113 @@
+use crate::modular::{gcd, mul_mod, sub_mod};
+use crate::sieve::sieve_primes;
+
+// Starting values A for the Lucas sequences. The square-free parts of A^2 - 4 are distinct, so
+// each seed has an independent chance of A^2 - 4 being a non-residue modulo the hidden prime.
+const SEEDS: [u64; 6] = [3, 4, 5, 6, 8, 9];
+
+const GCD_INTERVAL: usize = 64;
+
+// V_k of the Lucas sequence V_0 = 2, V_1 = v, V_(j+1) = v V_j - V_(j-1), modulo n.
+fn lucas_v(v: u64, k: u64, n: u64) -> u64 {
+    let two = 2 % n;
+    let mut x = v;
+    let mut y = sub_mod(mul_mod(v, v, n), two, n);
+
+    for bit in (0..63 - k.leading_zeros()).rev() {
+        if (k >> bit) & 1 == 1 {
+            x = sub_mod(mul_mod(x, y, n), v, n);
+            y = sub_mod(mul_mod(y, y, n), two, n);
+        } else {
+            y = sub_mod(mul_mod(x, y, n), v, n);
+            x = sub_mod(mul_mod(x, x, n), two, n);
+        }
+    }
+
+    x
+}
+
+/// Williams' p + 1 method. Finds a factor of `n` when some prime `p | n` has `p + 1` built from
+/// prime powers `<= b1` and at most one further prime `<= b2`. It also succeeds when `p − 1` is
+/// that smooth, with roughly even odds per seed.
+pub fn williams_pp1(n: u64, b1: u64, b2: u64) -> Option<u64> {
+    if n < 4 {
+        return None;
+    }
+
+    if n.is_multiple_of(2) {
+        return Some(2);
+    }
+
+    let primes = sieve_primes(b1.max(b2));
+    let stage1 = primes.partition_point(|&p| p <= b1);
+
+    SEEDS.iter().find_map(|&seed| {
+        // Stage 1: v = V_M(seed) using V_(jk)(A) = V_j(V_k(A)).
+        let mut v = seed % n;
+
+        for &p in &primes[..stage1] {
+            let mut q = p;
+
+            while let Some(next) = q.checked_mul(p).filter(|&next| next <= b1) {
+                q = next;
+            }
+
+            v = lucas_v(v, q, n);
+        }
+
+        let g = gcd(sub_mod(v, 2, n), n);
+
+        if g == n {
+            return None;
+        }
+
+        if g > 1 {
+            return Some(g);
+        }
+
+        // Stage 2: accumulate V_q(v) - 2 for each prime q in (b1, b2].
+        let mut product = 1;
+
+        for (i, &q) in primes[stage1..].iter().enumerate() {
+            product = mul_mod(product, sub_mod(lucas_v(v, q, n), 2, n), n);
+
+            if i % GCD_INTERVAL == GCD_INTERVAL - 1 {
+                let g = gcd(product, n);
+
+                if g > 1 {
+                    return Some(g).filter(|&g| g < n);
+                }
+            }
+        }
+
+        Some(gcd(product, n)).filter(|&g| g > 1 && g < n)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pollard_pm1;
+
+    #[test]
+    fn lucas_v_01() {
+        // V_k(3) with Q = 1 runs 2, 3, 7, 18, 47, 123, 322, ...
+        let expected = [3, 7, 18, 47, 123, 322, 843, 2207];
+
+        for (k, &v) in expected.iter().enumerate() {
+            assert_eq!(lucas_v(3, k as u64 + 1, 1_000_000_007), v);
+        }
+    }
+
+    #[test]
+    fn williams_pp1_01() {
+        // 1_000_669 + 1 = 2 * 5 * 11^2 * 827, while 1_000_669 - 1 = 2^2 * 3 * 83389 and
+        // 2_000_029 ± 1 both have large prime factors.
+        let n = 1_000_669 * 2_000_029;
+
+        assert_eq!(pollard_pm1(n, 1000, 1000), None);
+        assert_eq!(williams_pp1(n, 1000, 1000), Some(1_000_669));
+        assert_eq!(williams_pp1(n, 200, 1000), Some(1_000_669));
+        assert_eq!(williams_pp1(n, 100, 200), None);
+    }
+}
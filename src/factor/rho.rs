@@ -0,0 +1,122 @@
+use crate::modular::gcd;
+use crate::Montgomery;
+
+// The number of steps whose differences are multiplied together before each gcd.
+const BATCH_SIZE: u64 = 128;
+
+/// Finds a nontrivial factor of the composite `n` with Pollard's rho method and Brent's cycle
+/// detection. Returns `None` if `n` is prime or less than 4.
+pub fn pollard_rho(n: u64) -> Option<u64> {
+    if n < 4 || crate::is_prime(n) {
+        return None;
+    }
+
+    if n.is_multiple_of(2) {
+        return Some(2);
+    }
+
+    let mut budget = u64::MAX;
+
+    (1..n).find_map(|c| pollard_brent(n, 2, c, &mut budget))
+}
+
+/// A single Pollard–Brent run with the map `x -> x^2 + c` from `x0`. Returns `None` when the
+/// cycle closes without separating a factor, in which case another `c` should be tried, or when
+/// `budget` steps of the map have been spent, in which case `budget` is left at 0. `n` must be
+/// odd.
+pub(crate) fn pollard_brent(n: u64, x0: u64, c: u64, budget: &mut u64) -> Option<u64> {
+    // The sequence is followed in Montgomery form. Every difference is scaled by R, which is
+    // coprime to n and so leaves the gcds unchanged.
+    let mont = Montgomery::new(n);
+    let c = mont.to_montgomery(c);
+    let f = |x: u64| mont.add(mont.mul(x, x), c);
+
+    let mut y = mont.to_montgomery(x0);
+    let mut x = y;
+    let mut ys = y;
+    let mut q = mont.one();
+    let mut g = 1;
+    let mut r = 1;
+
+    while g == 1 {
+        if *budget < r {
+            *budget = 0;
+            return None;
+        }
+
+        x = y;
+        *budget -= r;
+
+        for _ in 0..r {
+            y = f(y);
+        }
+
+        let mut k = 0;
+
+        while k < r && g == 1 {
+            let steps = BATCH_SIZE.min(r - k);
+
+            if *budget < steps {
+                *budget = 0;
+                return None;
+            }
+
+            ys = y;
+            *budget -= steps;
+
+            for _ in 0..steps {
+                y = f(y);
+                q = mont.mul(q, x.abs_diff(y));
+            }
+
+            g = gcd(q, n);
+            k += BATCH_SIZE;
+        }
+
+        r *= 2;
+    }
+
+    if g == n {
+        // The batch overshot; retrace it one step at a time.
+        loop {
+            ys = f(ys);
+            g = gcd(x.abs_diff(ys), n);
+
+            if g > 1 {
+                break;
+            }
+        }
+    }
+
+    if g == n {
+        None
+    } else {
+        Some(g)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pollard_rho_01() {
+        assert_eq!(pollard_rho(1), None);
+        assert_eq!(pollard_rho(97), None);
+        assert_eq!(pollard_rho(1_000_000_007), None);
+        assert_eq!(pollard_rho(4), Some(2));
+
+        for &n in &[
+            15,
+            25,
+            8051,
+            10_403,
+            1_000_000_007 * 998_244_353,
+            4_294_967_291 * 4_294_967_279,
+        ] {
+            let d = pollard_rho(n).unwrap();
+
+            assert!(d > 1 && d < n && n.is_multiple_of(d));
+        }
+    }
+}
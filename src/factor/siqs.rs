@@ -0,0 +1,676 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::thread;
+
+use num_bigint::{BigInt, BigUint};
+use num_integer::Integer;
+use num_traits::{One, ToPrimitive, Zero};
+
+use super::linalg::gf2_dependencies;
+use crate::modular::{mod_inv, mod_pow, sqrt_mod_prime};
+use crate::sieve::sieve_primes;
+
+const MULTIPLIERS: [u32; 20] = [
+    1, 3, 5, 7, 11, 13, 15, 17, 19, 21, 23, 29, 31, 33, 35, 37, 39, 41, 43, 47,
+];
+
+// (decimal digits, factor base size, sieve half-width), tuned roughly after Contini's tables.
+// Sizes between rows use the next larger row.
+const PARAMETERS: [(u32, usize, usize); 11] = [
+    (30, 200, 32_768),
+    (36, 300, 32_768),
+    (40, 400, 65_536),
+    (45, 700, 65_536),
+    (50, 1_200, 65_536),
+    (55, 1_800, 98_304),
+    (60, 2_800, 98_304),
+    (65, 4_200, 131_072),
+    (70, 6_500, 131_072),
+    (75, 9_000, 196_608),
+    (80, 13_000, 196_608),
+];
+
+// Primes below this are left out of the sieve, as they cost the most and contribute little.
+const SIEVE_START: u32 = 32;
+
+// Partial relations keep a cofactor up to this multiple of the largest factor base prime.
+const LARGE_PRIME_MULTIPLIER: u64 = 64;
+
+const EXTRA_RELATIONS: usize = 32;
+
+// The number of times to collect more relations when every dependency gives a trivial factor.
+const ATTEMPTS: usize = 4;
+
+struct FactorBase {
+    primes: Vec<u32>,
+    // A square root of kn modulo each prime.
+    roots: Vec<u32>,
+    logs: Vec<u8>,
+}
+
+struct Context {
+    n: BigUint,
+    kn: BigUint,
+    base: FactorBase,
+    sieve_start: usize,
+    half_width: usize,
+    threshold: u8,
+    large_bound: u64,
+    // The number of factor base primes in each A coefficient, and where to draw them from.
+    a_factors: usize,
+    a_pool: Vec<usize>,
+    // ln(sqrt(2kn) / M), the ideal size of A.
+    ln_target: f64,
+    // Indices of primes dividing the multiplier, which have a single root and are not sieved.
+    skip: Vec<bool>,
+}
+
+// A relation Y^2 ≡ ±Π p (mod n), with the primes given as factor base columns (0 for −1),
+// repeated by multiplicity. For a pair of partial relations sharing a large prime L, `large` is
+// L, which then appears squared on the right.
+struct Relation {
+    y: BigUint,
+    columns: Vec<u32>,
+    large: u64,
+}
+
+#[derive(Default)]
+struct Relations {
+    full: Vec<Relation>,
+    partials: HashMap<u64, Relation>,
+    used: HashSet<BigUint>,
+}
+
+impl Relations {
+    fn insert(&mut self, n: &BigUint, relation: Relation) {
+        if relation.large == 1 {
+            self.full.push(relation);
+            return;
+        }
+
+        let Some(other) = self.partials.remove(&relation.large) else {
+            self.partials.insert(relation.large, relation);
+            return;
+        };
+
+        if other.y == relation.y {
+            self.partials.insert(relation.large, relation);
+            return;
+        }
+
+        let mut columns = relation.columns;
+        columns.extend(other.columns);
+
+        self.full.push(Relation {
+            y: relation.y * other.y % n,
+            columns,
+            large: relation.large,
+        });
+    }
+}
+
+// xorshift64*, for choosing the primes of each A coefficient.
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 >> 12;
+        self.0 ^= self.0 << 25;
+        self.0 ^= self.0 >> 27;
+        self.0.wrapping_mul(0x2545_f491_4f6c_dd1d)
+    }
+
+    fn below(&mut self, bound: usize) -> usize {
+        (self.next() % bound as u64) as usize
+    }
+}
+
+fn mod_inverse(a: u32, p: u32) -> u32 {
+    mod_inv(a as u64, p as u64).unwrap() as u32
+}
+
+fn rem_u32(a: &BigUint, p: u32) -> u32 {
+    (a % p).to_u32().unwrap()
+}
+
+// The Knuth–Schroeppel function: the multiplier k for which small primes divide the values of
+// the sieving polynomials most often, at the cost of the sqrt(k) growth in their size.
+fn choose_multiplier(n: &BigUint) -> u32 {
+    let primes = sieve_primes(1000);
+    let ln2 = 2f64.ln();
+
+    let score = |k: u32| {
+        let kn = n * k;
+        let mut score = -0.5 * (k as f64).ln();
+
+        score += match rem_u32(&kn, 8) {
+            1 => 2.0 * ln2,
+            5 => ln2,
+            _ => 0.5 * ln2,
+        };
+
+        for &p in &primes[1..] {
+            let p = p as u32;
+            let ln_p = (p as f64).ln();
+
+            if k.is_multiple_of(p) {
+                score += ln_p / p as f64;
+            } else if mod_pow(rem_u32(&kn, p) as u64, (p as u64 - 1) / 2, p as u64) == 1 {
+                score += 2.0 * ln_p / (p - 1) as f64;
+            }
+        }
+
+        score
+    };
+
+    MULTIPLIERS
+        .iter()
+        .copied()
+        .map(|k| (k, score(k)))
+        .max_by(|a, b| a.1.total_cmp(&b.1))
+        .unwrap()
+        .0
+}
+
+fn exact_sqrt(n: &BigUint) -> Option<BigUint> {
+    let root = n.sqrt();
+
+    if &root * &root == *n {
+        Some(root)
+    } else {
+        None
+    }
+}
+
+/// The self-initializing quadratic sieve, for `n` from about 30 up to 70 decimal digits.
+///
+/// Relations `(Ax + B)^2 ≡ A g(x) (mod n)` with smooth `g(x) = Ax^2 + 2Bx + C` are collected by
+/// sieving over many polynomials at once on every available thread, with the single large prime
+/// variation, and combined into a congruence of squares by linear algebra over GF(2). `n` should
+/// be an odd composite that is not a perfect power. A 70 digit `n` takes a few minutes on one
+/// thread, and each further ten digits multiplies the sieving time by about twenty.
+pub fn siqs(n: &BigUint) -> Option<BigUint> {
+    let threads = thread::available_parallelism().map_or(1, |t| t.get());
+
+    siqs_with_threads(n, threads)
+}
+
+/// [`siqs`], sieving on `threads` threads.
+pub fn siqs_with_threads(n: &BigUint, threads: usize) -> Option<BigUint> {
+    if *n < BigUint::from(4u32) {
+        return None;
+    }
+
+    if n.is_even() {
+        return Some(BigUint::from(2u32));
+    }
+
+    if let Some(root) = exact_sqrt(n) {
+        return Some(root);
+    }
+
+    let context = match Context::new(n) {
+        Ok(context) => context,
+        Err(factor) => return Some(factor),
+    };
+
+    let relations = Mutex::new(Relations::default());
+    let mut needed = context.base.primes.len() + 1 + EXTRA_RELATIONS;
+
+    for _ in 0..ATTEMPTS {
+        let done = AtomicBool::new(false);
+
+        thread::scope(|scope| {
+            for t in 0..threads.max(1) {
+                let context = &context;
+                let relations = &relations;
+                let done = &done;
+
+                scope.spawn(move || {
+                    let mut rng =
+                        Rng(0x9e37_79b9_7f4a_7c15 ^ ((t as u64 + 1) << 32) ^ needed as u64);
+
+                    context.sieve(&mut rng, relations, done, needed);
+                });
+            }
+        });
+
+        let relations = relations.lock().unwrap();
+
+        if let Some(factor) = context.combine(&relations.full) {
+            return Some(factor);
+        }
+
+        needed = relations.full.len() + EXTRA_RELATIONS;
+    }
+
+    None
+}
+
+impl Context {
+    // Builds the factor base for n, or returns a factor of n found along the way.
+    fn new(n: &BigUint) -> Result<Context, BigUint> {
+        let digits = n.to_string().len() as u32;
+        let &(_, size, half_width) = PARAMETERS
+            .iter()
+            .find(|&&(d, _, _)| d >= digits)
+            .unwrap_or(&PARAMETERS[PARAMETERS.len() - 1]);
+
+        let k = choose_multiplier(n);
+        let kn = n * k;
+
+        let mut base = FactorBase {
+            primes: vec![2],
+            roots: vec![1],
+            logs: vec![1],
+        };
+        let mut skip = vec![true];
+        let mut bound = 16 * size as u64;
+
+        while base.primes.len() < size {
+            let start = *base.primes.last().unwrap() as u64;
+
+            for p in sieve_primes(bound).into_iter().skip_while(|&p| p <= start) {
+                let p = p as u32;
+                let r = rem_u32(n, p);
+
+                if r == 0 {
+                    return Err(BigUint::from(p));
+                }
+
+                let Some(root) = sqrt_mod_prime(rem_u32(&kn, p) as u64, p as u64) else {
+                    continue;
+                };
+
+                base.primes.push(p);
+                base.roots.push(root as u32);
+                base.logs.push((p as f64).log2().round() as u8);
+                skip.push(k.is_multiple_of(p));
+
+                if base.primes.len() == size {
+                    break;
+                }
+            }
+
+            bound *= 2;
+        }
+
+        let sieve_start = base.primes.partition_point(|&p| p < SIEVE_START);
+        let largest = *base.primes.last().unwrap() as u64;
+        let large_bound = (largest * LARGE_PRIME_MULTIPLIER).min(largest * largest);
+
+        // |g(x)| is at most about M sqrt(kn / 2) over the interval.
+        let ln_kn = kn.bits() as f64 * 2f64.ln();
+        let max_log = (half_width as f64).log2() + (ln_kn - 2f64.ln()) / 2.0 / 2f64.ln();
+        let threshold = (max_log - (large_bound as f64).log2() - 2.0).max(1.0) as u8;
+
+        let ln_target = (2f64.ln() + ln_kn) / 2.0 - (half_width as f64).ln();
+        let reference = (base.primes[size / 4] as f64).ln();
+        let a_factors = ((ln_target / reference).ceil() as usize).max(2);
+        let ideal = (ln_target / a_factors as f64).exp();
+
+        let centre = base
+            .primes
+            .partition_point(|&p| (p as f64) < ideal)
+            .clamp(sieve_start, size - 1);
+        let width = (4 * a_factors).max(30);
+        let a_pool = (centre.saturating_sub(width / 2).max(sieve_start)..size)
+            .filter(|&i| !skip[i])
+            .take(width)
+            .collect();
+
+        for s in skip.iter_mut().take(sieve_start) {
+            *s = true;
+        }
+
+        Ok(Context {
+            n: n.clone(),
+            kn,
+            base,
+            sieve_start,
+            half_width,
+            threshold,
+            large_bound,
+            a_factors,
+            a_pool,
+            ln_target,
+            skip,
+        })
+    }
+
+    // Picks the factor base indices of a new A, the last chosen to bring A close to the target.
+    fn choose_a(&self, rng: &mut Rng) -> Vec<usize> {
+        let mut chosen: Vec<usize> = vec![];
+
+        while chosen.len() + 1 < self.a_factors {
+            let i = self.a_pool[rng.below(self.a_pool.len())];
+
+            if !chosen.contains(&i) {
+                chosen.push(i);
+            }
+        }
+
+        let ln_rest = self.ln_target
+            - chosen
+                .iter()
+                .map(|&i| (self.base.primes[i] as f64).ln())
+                .sum::<f64>();
+
+        let last = (self.sieve_start..self.base.primes.len())
+            .filter(|i| !self.skip[*i] && !chosen.contains(i))
+            .min_by(|&i, &j| {
+                let d = |i: usize| ((self.base.primes[i] as f64).ln() - ln_rest).abs();
+                d(i).total_cmp(&d(j))
+            })
+            .unwrap();
+
+        chosen.push(last);
+        chosen
+    }
+
+    // Sieves polynomials until `needed` full relations have been collected.
+    fn sieve(&self, rng: &mut Rng, relations: &Mutex<Relations>, done: &AtomicBool, needed: usize) {
+        let size = self.base.primes.len();
+        let width = 2 * self.half_width;
+        let mut sieve = vec![0u8; width];
+
+        while !done.load(Ordering::Relaxed) {
+            let indices = self.choose_a(rng);
+            let a: BigUint = indices
+                .iter()
+                .map(|&i| BigUint::from(self.base.primes[i]))
+                .product();
+
+            if !relations.lock().unwrap().used.insert(a.clone()) {
+                continue;
+            }
+
+            // B = Σ B_l with B_l ≡ sqrt(kn) modulo q_l and ≡ 0 modulo the other factors of A.
+            let terms: Vec<BigUint> = indices
+                .iter()
+                .map(|&i| {
+                    let q = self.base.primes[i];
+                    let rest = &a / q;
+                    let mut gamma = (self.base.roots[i] as u64
+                        * mod_inverse(rem_u32(&rest, q), q) as u64
+                        % q as u64) as u32;
+
+                    if gamma > q / 2 {
+                        gamma = q - gamma;
+                    }
+
+                    rest * gamma
+                })
+                .collect();
+            let mut b = BigInt::from(terms.iter().sum::<BigUint>());
+            let terms: Vec<BigInt> = terms.into_iter().map(BigInt::from).collect();
+
+            // Sieve offsets of the two roots of g modulo each odd prime, and how they move when
+            // the sign of each B_l flips.
+            let mut roots = vec![(u32::MAX, u32::MAX); size];
+            let mut deltas = vec![vec![0u32; size]; indices.len()];
+
+            for i in 0..size {
+                if self.skip[i] || indices.contains(&i) {
+                    continue;
+                }
+
+                let p = self.base.primes[i];
+                let p64 = p as u64;
+                let a_inv = mod_inverse(rem_u32(&a, p), p) as u64;
+                let b_mod = rem_u32(b.magnitude(), p) as u64;
+                let t = self.base.roots[i] as u64;
+                let m = self.half_width as u64 % p64;
+
+                let root = |t: u64| ((a_inv * ((t + p64 - b_mod) % p64) + m) % p64) as u32;
+
+                roots[i] = (root(t), root((p64 - t) % p64));
+
+                for (l, term) in terms.iter().enumerate() {
+                    let t = rem_u32(term.magnitude(), p) as u64;
+
+                    deltas[l][i] = (2 * t * a_inv % p64) as u32;
+                }
+            }
+
+            let mut signs = vec![true; indices.len()];
+
+            for poly in 0..1usize << (indices.len() - 1) {
+                if poly > 0 {
+                    let v = poly.trailing_zeros() as usize + 1;
+
+                    signs[v] = !signs[v];
+
+                    // B changes by 2 B_v, moving each root by ∓ 2 B_v / A.
+                    let two_term = &terms[v] * 2u32;
+
+                    for i in 0..size {
+                        let (r1, r2) = roots[i];
+
+                        if r1 == u32::MAX {
+                            continue;
+                        }
+
+                        let p = self.base.primes[i];
+                        let d = deltas[v][i];
+                        let shift = |r: u32| {
+                            if signs[v] {
+                                ((r as u64 + (p - d) as u64) % p as u64) as u32
+                            } else {
+                                ((r as u64 + d as u64) % p as u64) as u32
+                            }
+                        };
+
+                        roots[i] = (shift(r1), shift(r2));
+                    }
+
+                    if signs[v] {
+                        b += two_term;
+                    } else {
+                        b -= two_term;
+                    }
+                }
+
+                sieve.fill(0);
+
+                for ((&(r1, r2), &p), &log) in roots
+                    .iter()
+                    .zip(&self.base.primes)
+                    .zip(&self.base.logs)
+                    .skip(self.sieve_start)
+                {
+                    if r1 == u32::MAX {
+                        continue;
+                    }
+
+                    let p = p as usize;
+
+                    for r in [r1, r2] {
+                        let mut j = r as usize;
+
+                        while j < width {
+                            sieve[j] = sieve[j].wrapping_add(log);
+                            j += p;
+                        }
+                    }
+                }
+
+                let c = (&b * &b - BigInt::from(self.kn.clone())) / BigInt::from(a.clone());
+                let found: Vec<Relation> = (0..width)
+                    .filter(|&j| sieve[j] >= self.threshold)
+                    .filter_map(|j| self.check(j, &a, &indices, &b, &c, &roots))
+                    .collect();
+
+                let mut relations = relations.lock().unwrap();
+
+                for relation in found {
+                    relations.insert(&self.n, relation);
+                }
+
+                if relations.full.len() >= needed {
+                    done.store(true, Ordering::Relaxed);
+                }
+
+                if done.load(Ordering::Relaxed) {
+                    return;
+                }
+            }
+        }
+    }
+
+    // Trial divides g(x) at sieve offset j, returning a relation if it is smooth or has a
+    // single large prime.
+    fn check(
+        &self,
+        j: usize,
+        a: &BigUint,
+        indices: &[usize],
+        b: &BigInt,
+        c: &BigInt,
+        roots: &[(u32, u32)],
+    ) -> Option<Relation> {
+        let x = BigInt::from(j as i64 - self.half_width as i64);
+        let ax = BigInt::from(a.clone()) * &x;
+        let g = (&ax + b * 2u32) * &x + c;
+        let y = (ax + b).magnitude() % &self.n;
+
+        let mut columns: Vec<u32> = indices.iter().map(|&i| i as u32 + 1).collect();
+        let mut rest = g.magnitude().clone();
+
+        if g.sign() == num_bigint::Sign::Minus {
+            columns.push(0);
+        }
+
+        if rest.is_zero() {
+            return None;
+        }
+
+        for (i, &p) in self.base.primes.iter().enumerate() {
+            let (r1, r2) = roots[i];
+
+            if r1 != u32::MAX {
+                let r = (j % p as usize) as u32;
+
+                if r != r1 && r != r2 {
+                    continue;
+                }
+            }
+
+            while rem_u32(&rest, p) == 0 {
+                rest /= p;
+                columns.push(i as u32 + 1);
+            }
+        }
+
+        let large = if rest.is_one() {
+            1
+        } else {
+            rest.to_u64().filter(|&r| r < self.large_bound)?
+        };
+
+        Some(Relation { y, columns, large })
+    }
+
+    fn combine(&self, relations: &[Relation]) -> Option<BigUint> {
+        let columns = self.base.primes.len() + 1;
+        let rows: Vec<Vec<usize>> = relations
+            .iter()
+            .map(|relation| {
+                let mut parity = vec![];
+
+                for &c in &relation.columns {
+                    let c = c as usize;
+
+                    match parity.iter().position(|&d| d == c) {
+                        Some(i) => {
+                            parity.swap_remove(i);
+                        }
+                        None => parity.push(c),
+                    }
+                }
+
+                parity
+            })
+            .collect();
+
+        for dependency in gf2_dependencies(columns, &rows) {
+            let mut x = BigUint::one();
+            let mut y = BigUint::one();
+            let mut exponents = vec![0u32; columns];
+
+            for &r in &dependency {
+                let relation = &relations[r];
+
+                x = x * &relation.y % &self.n;
+                y = y * relation.large % &self.n;
+
+                for &c in &relation.columns {
+                    exponents[c as usize] += 1;
+                }
+            }
+
+            for (&p, &e) in self.base.primes.iter().zip(&exponents[1..]) {
+                if e > 0 {
+                    y = y * BigUint::from(p).modpow(&BigUint::from(e / 2), &self.n) % &self.n;
+                }
+            }
+
+            let diff = if x >= y { x - y } else { y - x };
+            let g = diff.gcd(&self.n);
+
+            if !g.is_one() && g != self.n {
+                return Some(g);
+            }
+        }
+
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn siqs_01() {
+        // 30 and 40 digit semiprimes.
+        for (p, q) in [
+            ("1000000000000037", "100000000000000003"),
+            ("99999999999999999989", "100000000000000000039"),
+        ] {
+            let p: BigUint = p.parse().unwrap();
+            let q: BigUint = q.parse().unwrap();
+            let d = siqs(&(&p * &q)).unwrap();
+
+            assert!(d == p || d == q);
+        }
+    }
+
+    #[test]
+    fn siqs_02() {
+        let n = BigUint::from(1_000_003u64 * 1_000_033);
+
+        assert_eq!(siqs(&BigUint::from(3u32)), None);
+        assert_eq!(siqs(&BigUint::from(1u64 << 40)), Some(BigUint::from(2u32)));
+        assert_eq!(
+            siqs(&BigUint::from(1_000_003u64 * 1_000_003)),
+            Some(BigUint::from(1_000_003u32))
+        );
+
+        let d = siqs_with_threads(&n, 1).unwrap();
+
+        assert!(d == BigUint::from(1_000_003u32) || d == BigUint::from(1_000_033u32));
+    }
+
+    #[test]
+    #[ignore = "takes minutes in a release build"]
+    fn siqs_03() {
+        // A 70 digit semiprime, at the top of the supported range.
+        let p: BigUint = "10000000000000000000000000000000193".parse().unwrap();
+        let q: BigUint = "100000000000000000000000000000000069".parse().unwrap();
+        let d = siqs(&(&p * &q)).unwrap();
+
+        assert!(d == p || d == q);
+    }
+}
@@ -0,0 +1,143 @@
+use super::fermat::SQUARES_MOD_64;
+use crate::modular::gcd;
+
+// Square-free multipliers k; running the algorithm on kN avoids its failure cases for N alone.
+const MULTIPLIERS: [u64; 16] = [
+    1, 3, 5, 7, 11, 15, 21, 33, 35, 55, 77, 105, 165, 231, 385, 1155,
+];
+
+/// Shanks' square forms factorization. Finds a nontrivial factor of the odd composite `n` in
+/// roughly `n^(1/4)` steps, or returns `None` if every multiplier fails. Multipliers `k` with
+/// `kn >= 2^64` are skipped, so it is most reliable somewhat below that size.
+pub fn squfof(n: u64) -> Option<u64> {
+    if n < 4 {
+        return None;
+    }
+
+    if n.is_multiple_of(2) {
+        return Some(2);
+    }
+
+    let s = n.isqrt();
+
+    if s * s == n {
+        return Some(s);
+    }
+
+    MULTIPLIERS.iter().find_map(|&k| squfof_multiplier(n, k))
+}
+
+fn is_square(q: i64) -> Option<i64> {
+    if (SQUARES_MOD_64 >> (q & 63)) & 1 == 0 {
+        return None;
+    }
+
+    let root = (q as u64).isqrt() as i64;
+
+    Some(root).filter(|&root| root * root == q)
+}
+
+fn squfof_multiplier(n: u64, k: u64) -> Option<u64> {
+    // Only kN itself needs the full 64 bits: P stays below sqrt(kN) and Q below 2 sqrt(kN).
+    let d = k.checked_mul(n)?;
+    let p0 = d.isqrt() as i64;
+    let limit = 6 * (2 * n.isqrt()).isqrt() as i64;
+
+    // Forward cycle: step through the continued fraction of sqrt(kN), trying every square Q
+    // found at an even index.
+    let mut p_prev = p0;
+    let mut p = p0;
+    let mut q_prev = 1;
+    let mut q = (d - p0 as u64 * p0 as u64) as i64;
+
+    if q == 0 {
+        return None;
+    }
+
+    for i in 2..limit {
+        let b = (p0 + p) / q;
+        p = b * q - p;
+
+        let q_next = q_prev + b * (p_prev - p);
+        q_prev = q;
+        q = q_next;
+
+        if q == 0 {
+            return None;
+        }
+
+        if i % 2 == 0 {
+            if let Some(r) = is_square(q) {
+                if let Some(factor) = reverse_cycle(n, d, p0, p, r, limit) {
+                    return Some(factor);
+                }
+            }
+        }
+
+        p_prev = p;
+    }
+
+    None
+}
+
+// Starts from the square root of the form with Q = r^2 and iterates until P repeats, at which
+// point gcd(n, P) is usually a nontrivial factor.
+fn reverse_cycle(n: u64, d: u64, p0: i64, p: i64, r: i64, limit: i64) -> Option<u64> {
+    let b = (p0 - p) / r;
+    let mut p = b * r + p;
+    let mut q_prev = r;
+    let mut q = ((d - p as u64 * p as u64) / q_prev as u64) as i64;
+
+    if q == 0 {
+        return None;
+    }
+
+    for _ in 0..limit {
+        let b = (p0 + p) / q;
+        let p_prev = p;
+        p = b * q - p;
+
+        if p == p_prev {
+            let g = gcd(n, p as u64);
+
+            return Some(g).filter(|&g| g > 1 && g < n);
+        }
+
+        let q_next = q_prev + b * (p_prev - p);
+        q_prev = q;
+        q = q_next;
+
+        if q == 0 {
+            return None;
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn squfof_01() {
+        for &(p, q) in &[
+            (41, 271),
+            (1_000_003, 1_000_033),
+            (1_000_000_007, 998_244_353),
+            (2_147_483_647, 2_147_483_629),
+            (65_537, 4_294_967_291),
+        ] {
+            let d = squfof(p * q).unwrap();
+
+            assert!(d == p || d == q);
+        }
+    }
+
+    #[test]
+    fn squfof_02() {
+        assert_eq!(squfof(1_000_003 * 1_000_003), Some(1_000_003));
+        assert_eq!(squfof(14), Some(2));
+        assert_eq!(squfof(3), None);
+    }
+}
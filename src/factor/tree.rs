@@ -0,0 +1,384 @@
+use std::fmt;
+
+use super::{find_factor, trial_primes_below, FactorConfig};
+use crate::{is_prime, Factorization};
+
+/// The method that split a composite in a [`FactorTree`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum SplitMethod {
+    TrialDivision,
+    Fermat,
+    PollardPm1,
+    WilliamsPp1,
+    Squfof,
+    Ecm,
+    PollardRho,
+    Lehman,
+}
+
+impl fmt::Display for SplitMethod {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            SplitMethod::TrialDivision => "trial division",
+            SplitMethod::Fermat => "Fermat",
+            SplitMethod::PollardPm1 => "Pollard p − 1",
+            SplitMethod::WilliamsPp1 => "Williams p + 1",
+            SplitMethod::Squfof => "SQUFOF",
+            SplitMethod::Ecm => "ECM",
+            SplitMethod::PollardRho => "Pollard rho",
+            SplitMethod::Lehman => "Lehman",
+        };
+
+        write!(f, "{name}")
+    }
+}
+
+// The variants in declaration order, and their names, which are the serde form.
+#[cfg(feature = "serde")]
+const SPLIT_METHODS: [SplitMethod; 8] = [
+    SplitMethod::TrialDivision,
+    SplitMethod::Fermat,
+    SplitMethod::PollardPm1,
+    SplitMethod::WilliamsPp1,
+    SplitMethod::Squfof,
+    SplitMethod::Ecm,
+    SplitMethod::PollardRho,
+    SplitMethod::Lehman,
+];
+
+#[cfg(feature = "serde")]
+const SPLIT_METHOD_NAMES: &[&str] = &[
+    "TrialDivision",
+    "Fermat",
+    "PollardPm1",
+    "WilliamsPp1",
+    "Squfof",
+    "Ecm",
+    "PollardRho",
+    "Lehman",
+];
+
+// Serialized as the variant name.
+#[cfg(feature = "serde")]
+impl serde::Serialize for SplitMethod {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(SPLIT_METHOD_NAMES[*self as usize])
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for SplitMethod {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<SplitMethod, D::Error> {
+        let name = String::deserialize(deserializer)?;
+
+        SPLIT_METHOD_NAMES
+            .iter()
+            .position(|&variant| variant == name)
+            .map(|i| SPLIT_METHODS[i])
+            .ok_or_else(|| serde::de::Error::unknown_variant(&name, SPLIT_METHOD_NAMES))
+    }
+}
+
+/// The recursive splitting of a number into primes, recording which method found each split.
+///
+/// Trial division peels off one small prime at a time, so its splits form a chain down the
+/// right-hand side. The smaller part of a split is always on the left.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum FactorTree {
+    Prime(u64),
+    Split {
+        n: u64,
+        method: SplitMethod,
+        left: Box<FactorTree>,
+        right: Box<FactorTree>,
+    },
+}
+
+impl FactorTree {
+    /// The number at the root of the tree.
+    pub fn value(&self) -> u64 {
+        match *self {
+            FactorTree::Prime(p) => p,
+            FactorTree::Split { n, .. } => n,
+        }
+    }
+
+    /// The number of splits on the longest path from the root to a prime.
+    pub fn depth(&self) -> usize {
+        match self {
+            FactorTree::Prime(_) => 0,
+            FactorTree::Split { left, right, .. } => 1 + left.depth().max(right.depth()),
+        }
+    }
+
+    /// The primes at the leaves, collected into a factorization.
+    pub fn factorization(&self) -> Factorization {
+        let mut factorization = Factorization::new();
+        let mut stack = vec![self];
+
+        while let Some(tree) = stack.pop() {
+            match tree {
+                FactorTree::Prime(p) => factorization.insert(*p, 1),
+                FactorTree::Split { left, right, .. } => stack.extend([&**left, &**right]),
+            }
+        }
+
+        factorization
+    }
+
+    fn write_indented(&self, f: &mut fmt::Formatter<'_>, depth: usize) -> fmt::Result {
+        write!(f, "{:width$}", "", width = 2 * depth)?;
+
+        match self {
+            FactorTree::Prime(p) => writeln!(f, "{p}"),
+            FactorTree::Split {
+                n,
+                method,
+                left,
+                right,
+            } => {
+                writeln!(f, "{n} ({method})")?;
+                left.write_indented(f, depth + 1)?;
+                right.write_indented(f, depth + 1)
+            }
+        }
+    }
+}
+
+// Serialized as the prime itself at a leaf, and otherwise as an object with `n`, `method`, and
+// the `left` and `right` subtrees. Deserializing checks that every leaf is prime and every split
+// multiplies back to its `n`.
+#[cfg(feature = "serde")]
+impl serde::Serialize for FactorTree {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeStruct;
+
+        match self {
+            FactorTree::Prime(p) => serializer.serialize_u64(*p),
+            FactorTree::Split {
+                n,
+                method,
+                left,
+                right,
+            } => {
+                let mut state = serializer.serialize_struct("FactorTree", 4)?;
+
+                state.serialize_field("n", n)?;
+                state.serialize_field("method", method)?;
+                state.serialize_field("left", left)?;
+                state.serialize_field("right", right)?;
+                state.end()
+            }
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for FactorTree {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<FactorTree, D::Error> {
+        let tree = deserializer.deserialize_any(FactorTreeVisitor)?;
+
+        match &tree {
+            FactorTree::Prime(p) if !is_prime(*p) => {
+                Err(serde::de::Error::custom(format!("{p} is not prime")))
+            }
+            FactorTree::Split { n, left, right, .. }
+                if left.value().checked_mul(right.value()) != Some(*n) =>
+            {
+                Err(serde::de::Error::custom(format!(
+                    "{} · {} is not {n}",
+                    left.value(),
+                    right.value()
+                )))
+            }
+            _ => Ok(tree),
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+struct FactorTreeVisitor;
+
+#[cfg(feature = "serde")]
+impl<'de> serde::de::Visitor<'de> for FactorTreeVisitor {
+    type Value = FactorTree;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "a prime or a split")
+    }
+
+    fn visit_u64<E: serde::de::Error>(self, p: u64) -> Result<FactorTree, E> {
+        Ok(FactorTree::Prime(p))
+    }
+
+    fn visit_map<A: serde::de::MapAccess<'de>>(self, mut map: A) -> Result<FactorTree, A::Error> {
+        use serde::de::Error;
+
+        const FIELDS: &[&str] = &["n", "method", "left", "right"];
+
+        let (mut n, mut method, mut left, mut right) = (None, None, None, None);
+
+        while let Some(key) = map.next_key::<String>()? {
+            match key.as_str() {
+                "n" => n = Some(map.next_value()?),
+                "method" => method = Some(map.next_value()?),
+                "left" => left = Some(Box::new(map.next_value()?)),
+                "right" => right = Some(Box::new(map.next_value()?)),
+                _ => return Err(A::Error::unknown_field(&key, FIELDS)),
+            }
+        }
+
+        Ok(FactorTree::Split {
+            n: n.ok_or_else(|| A::Error::missing_field("n"))?,
+            method: method.ok_or_else(|| A::Error::missing_field("method"))?,
+            left: left.ok_or_else(|| A::Error::missing_field("left"))?,
+            right: right.ok_or_else(|| A::Error::missing_field("right"))?,
+        })
+    }
+}
+
+impl fmt::Display for FactorTree {
+    /// Formats one number per line, each split followed by its two parts indented beneath it.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.write_indented(f, 0)
+    }
+}
+
+/// The factor tree of `n` under the default pipeline. Panics if `n < 2`.
+pub fn factor_tree(n: u64) -> FactorTree {
+    factor_tree_with(n, &FactorConfig::default())
+}
+
+/// The factor tree of `n` under the pipeline selected by `config`. Panics if `n < 2`.
+pub fn factor_tree_with(n: u64, config: &FactorConfig) -> FactorTree {
+    assert!(n >= 2, "{n} has no factor tree");
+
+    let primes = trial_primes_below(config.trial_division_bound);
+
+    build(n, &primes, config)
+}
+
+// primes holds the trial division primes that may still divide n.
+fn build(n: u64, primes: &[u64], config: &FactorConfig) -> FactorTree {
+    for (i, &p) in primes.iter().enumerate() {
+        if p > n / p {
+            return FactorTree::Prime(n);
+        }
+
+        if n.is_multiple_of(p) {
+            return split_node(n, p, SplitMethod::TrialDivision, &primes[i..], config);
+        }
+    }
+
+    if is_prime(n) {
+        return FactorTree::Prime(n);
+    }
+
+    let (d, method) = find_factor(n, config);
+
+    split_node(n, d, method, &[], config)
+}
+
+fn split_node(
+    n: u64,
+    d: u64,
+    method: SplitMethod,
+    primes: &[u64],
+    config: &FactorConfig,
+) -> FactorTree {
+    let (small, large) = if d <= n / d { (d, n / d) } else { (n / d, d) };
+
+    FactorTree::Split {
+        n,
+        method,
+        left: Box::new(build(small, primes, config)),
+        right: Box::new(build(large, primes, config)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{factorization, FinalMethod};
+
+    #[test]
+    fn factor_tree_01() {
+        assert_eq!(factor_tree(7), FactorTree::Prime(7));
+
+        let tree = factor_tree(12);
+
+        assert_eq!(
+            tree.to_string(),
+            "12 (trial division)\n  2\n  6 (trial division)\n    2\n    3\n"
+        );
+        assert_eq!(tree.depth(), 2);
+
+        // 1_000_003 · 1_000_033 is close to a square, so Fermat's method splits it.
+        assert_eq!(
+            factor_tree(1_000_003 * 1_000_033),
+            FactorTree::Split {
+                n: 1_000_003 * 1_000_033,
+                method: SplitMethod::Fermat,
+                left: Box::new(FactorTree::Prime(1_000_003)),
+                right: Box::new(FactorTree::Prime(1_000_033)),
+            }
+        );
+
+        let config = FactorConfig {
+            fermat_steps: 0,
+            final_method: FinalMethod::Lehman,
+            ..FactorConfig::default()
+        };
+        let tree = factor_tree_with(1_000_003 * 4_294_967_291, &config);
+
+        assert!(matches!(
+            tree,
+            FactorTree::Split {
+                method: SplitMethod::Lehman,
+                ..
+            }
+        ));
+
+        for n in [
+            2,
+            360,
+            1 << 63,
+            1_000_003 * 1_000_033,
+            18_446_744_073_709_551_557,
+        ] {
+            let tree = factor_tree(n);
+
+            assert_eq!(tree.value(), n);
+            assert_eq!(tree.factorization(), factorization(n));
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn factor_tree_serde_01() {
+        let tree = factor_tree(1_000_003 * 1_000_033 * 4);
+        let json = serde_json::to_string(&factor_tree(6)).unwrap();
+
+        assert_eq!(
+            json,
+            r#"{"n":6,"method":"TrialDivision","left":2,"right":3}"#
+        );
+        assert_eq!(
+            serde_json::from_str::<FactorTree>(&serde_json::to_string(&tree).unwrap()).unwrap(),
+            tree
+        );
+        assert_eq!(
+            serde_json::from_str::<FactorTree>("1000003").unwrap(),
+            FactorTree::Prime(1_000_003)
+        );
+
+        for bad in [
+            "15",
+            r#"{"n":7,"method":"TrialDivision","left":2,"right":3}"#,
+            r#"{"n":6,"method":"Guess","left":2,"right":3}"#,
+            r#"{"n":6,"method":"Fermat","left":2}"#,
+        ] {
+            assert!(serde_json::from_str::<FactorTree>(bad).is_err(), "{bad}");
+        }
+    }
+}
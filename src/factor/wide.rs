@@ -0,0 +1,248 @@
+use super::{cfrac, ecm_u128, factorization_with, trial_primes, EcmParams, FactorConfig};
+use crate::modular::{gcd_u128, mul_mod_u128};
+use crate::primality::baillie_psw;
+
+// The number of steps of rho to spend on a cofactor before the elliptic curve method, which
+// finds factors up to about 2^40 at this budget.
+const RHO_STEPS: u64 = 1 << 16;
+
+const BATCH_SIZE: u64 = 128;
+
+// A short run of the elliptic curve method after rho, for factors a little too large for rho
+// that would otherwise wait on the continued fraction method.
+const ECM_PARAMS: EcmParams = EcmParams {
+    b1: 1000,
+    b2: 50_000,
+    curves: 8,
+};
+
+/// The prime factorization of `n` as `(prime, exponent)` pairs in increasing order of prime.
+///
+/// Cofactors that fit in a `u64` go through the usual pipeline. Wider ones are tested with
+/// Baillie–PSW, and split by a bounded run of Pollard's rho with 128-bit arithmetic, then a few
+/// curves of the elliptic curve method, and then the continued fraction method. Panics if `n`
+/// is 0.
+pub fn factorize_u128(n: u128) -> Vec<(u128, u32)> {
+    assert!(n != 0, "0 has no factorization");
+
+    let mut primes = vec![];
+    let mut k = n;
+
+    for &p in trial_primes() {
+        let p = p as u128;
+
+        if p * p > k {
+            break;
+        }
+
+        while k.is_multiple_of(p) {
+            primes.push(p);
+            k /= p;
+        }
+    }
+
+    if k > 1 {
+        split(k, &mut primes);
+    }
+
+    primes.sort_unstable();
+
+    let mut factors: Vec<(u128, u32)> = vec![];
+
+    for p in primes {
+        match factors.last_mut() {
+            Some((q, e)) if *q == p => *e += 1,
+            _ => factors.push((p, 1)),
+        }
+    }
+
+    factors
+}
+
+fn split(n: u128, primes: &mut Vec<u128>) {
+    if n <= u64::MAX as u128 {
+        for (p, e) in factorization_with(n as u64, &FactorConfig::default()) {
+            primes.extend(std::iter::repeat_n(p as u128, e as usize));
+        }
+
+        return;
+    }
+
+    if baillie_psw(n) {
+        primes.push(n);
+        return;
+    }
+
+    let d = find_factor(n);
+
+    split(d, primes);
+    split(n / d, primes);
+}
+
+fn find_factor(n: u128) -> u128 {
+    let mut budget = RHO_STEPS;
+
+    for c in 1.. {
+        if budget == 0 {
+            break;
+        }
+
+        if let Some(d) = pollard_brent_u128(n, c, &mut budget) {
+            return d;
+        }
+    }
+
+    // The continued fraction method cannot split perfect powers.
+    for e in 2..=(128 - n.leading_zeros()) / 10 {
+        if let Some(root) = exact_root(n, e) {
+            return root;
+        }
+    }
+
+    if let Some(d) = ecm_u128(n, &ECM_PARAMS) {
+        return d;
+    }
+
+    if let Some(d) = cfrac(n) {
+        return d;
+    }
+
+    let mut budget = u64::MAX;
+
+    (1..)
+        .find_map(|c| pollard_brent_u128(n, c, &mut budget))
+        .unwrap()
+}
+
+// The integer e-th root of n, if n is a perfect e-th power.
+fn exact_root(n: u128, e: u32) -> Option<u128> {
+    let estimate = (n as f64).powf(1.0 / e as f64).round() as u128;
+
+    (estimate.saturating_sub(1)..=estimate + 1).find(|&r| r > 1 && r.checked_pow(e) == Some(n))
+}
+
+// Pollard–Brent with the map x -> x^2 + c from 2, as in the u64 version.
+fn pollard_brent_u128(n: u128, c: u128, budget: &mut u64) -> Option<u128> {
+    let f = |x: u128| {
+        let square = mul_mod_u128(x, x, n);
+
+        if square >= n - c {
+            square - (n - c)
+        } else {
+            square + c
+        }
+    };
+
+    let mut y = 2;
+    let mut x = y;
+    let mut ys = y;
+    let mut q = 1;
+    let mut g = 1;
+    let mut r = 1;
+
+    while g == 1 {
+        if *budget < r {
+            *budget = 0;
+            return None;
+        }
+
+        x = y;
+        *budget -= r;
+
+        for _ in 0..r {
+            y = f(y);
+        }
+
+        let mut k = 0;
+
+        while k < r && g == 1 {
+            let steps = BATCH_SIZE.min(r - k);
+
+            if *budget < steps {
+                *budget = 0;
+                return None;
+            }
+
+            ys = y;
+            *budget -= steps;
+
+            for _ in 0..steps {
+                y = f(y);
+                q = mul_mod_u128(q, x.abs_diff(y), n);
+            }
+
+            g = gcd_u128(q, n);
+            k += BATCH_SIZE;
+        }
+
+        r *= 2;
+    }
+
+    if g == n {
+        loop {
+            ys = f(ys);
+            g = gcd_u128(x.abs_diff(ys), n);
+
+            if g > 1 {
+                break;
+            }
+        }
+    }
+
+    if g == n {
+        None
+    } else {
+        Some(g)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn factorize_u128_01() {
+        let p64 = 18_446_744_073_709_551_557u128;
+        let p89 = (1u128 << 89) - 1;
+        let p40 = 1_099_511_627_791u128;
+
+        assert_eq!(factorize_u128(1), vec![]);
+        assert_eq!(factorize_u128(360), vec![(2, 3), (3, 2), (5, 1)]);
+        assert_eq!(factorize_u128(p89), vec![(p89, 1)]);
+        assert_eq!(factorize_u128(u128::MAX - 158), vec![(u128::MAX - 158, 1)]);
+        assert_eq!(factorize_u128(1 << 127), vec![(2, 127)]);
+        assert_eq!(factorize_u128(p64 * p64), vec![(p64, 2)]);
+        assert_eq!(factorize_u128(3 * p40 * p40 * p40), vec![(3, 1), (p40, 3)]);
+
+        // 2^128 − 1 = 3 · 5 · 17 · 257 · 641 · 65537 · 274177 · 6700417 · 67280421310721.
+        assert_eq!(
+            factorize_u128(u128::MAX),
+            vec![
+                (3, 1),
+                (5, 1),
+                (17, 1),
+                (257, 1),
+                (641, 1),
+                (65_537, 1),
+                (274_177, 1),
+                (6_700_417, 1),
+                (67_280_421_310_721, 1)
+            ]
+        );
+
+        // A factor within reach of rho, and two 40-bit factors for the later methods.
+        assert_eq!(
+            factorize_u128(1_000_003 * p89),
+            vec![(1_000_003, 1), (p89, 1)]
+        );
+        assert_eq!(
+            factorize_u128(p40 * 3_298_534_895_693 * 1021),
+            vec![(1021, 1), (p40, 1), (3_298_534_895_693, 1)]
+        );
+
+        // Two 64-bit primes, whose product is past 2^127.
+        let q64 = 18_446_744_073_709_551_533u128;
+
+        assert_eq!(factorize_u128(p64 * q64), vec![(q64, 1), (p64, 1)]);
+    }
+}
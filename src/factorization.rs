@@ -0,0 +1,414 @@
+use std::error::Error;
+use std::fmt;
+use std::ops::Mul;
+use std::slice;
+use std::str::FromStr;
+
+use crate::is_prime;
+
+/// A factorization stored as `(prime, exponent)` pairs in increasing order of prime.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Factorization {
+    factors: Vec<(u64, u32)>,
+}
+
+impl Factorization {
+    pub fn new() -> Factorization {
+        Factorization { factors: vec![] }
+    }
+
+    pub fn len(&self) -> usize {
+        self.factors.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.factors.is_empty()
+    }
+
+    pub fn iter(&self) -> slice::Iter<'_, (u64, u32)> {
+        self.factors.iter()
+    }
+
+    pub fn as_slice(&self) -> &[(u64, u32)] {
+        &self.factors
+    }
+
+    pub fn clear(&mut self) {
+        self.factors.clear();
+    }
+
+    pub fn exponent(&self, p: u64) -> u32 {
+        match self.factors.binary_search_by_key(&p, |&(q, _)| q) {
+            Ok(i) => self.factors[i].1,
+            Err(_) => 0,
+        }
+    }
+
+    /// The number this is a factorization of, or `None` if it does not fit in a `u64`.
+    pub fn value(&self) -> Option<u64> {
+        self.factors
+            .iter()
+            .try_fold(1u64, |acc, &(p, e)| acc.checked_mul(p.checked_pow(e)?))
+    }
+
+    /// The number of divisors, or `None` if it does not fit in a `u64`.
+    pub fn num_divisors(&self) -> Option<u64> {
+        self.factors
+            .iter()
+            .try_fold(1u64, |acc, &(_, e)| acc.checked_mul(e as u64 + 1))
+    }
+
+    /// Euler's totient of the value, or `None` if it does not fit in a `u64`.
+    pub fn totient(&self) -> Option<u64> {
+        self.factors.iter().try_fold(1u64, |acc, &(p, e)| {
+            acc.checked_mul((p - 1).checked_mul(p.checked_pow(e - 1)?)?)
+        })
+    }
+
+    // Combines the exponents of every prime in either factorization with f, treating a missing
+    // prime as exponent 0 and dropping primes whose combined exponent is 0.
+    fn merge(&self, other: &Factorization, f: impl Fn(u32, u32) -> u32) -> Factorization {
+        let mut factors = Vec::with_capacity(self.factors.len() + other.factors.len());
+        let mut a = self.factors.iter().peekable();
+        let mut b = other.factors.iter().peekable();
+
+        loop {
+            let (p, e) = match (a.peek(), b.peek()) {
+                (Some(&&(p, e)), Some(&&(q, g))) if p == q => {
+                    a.next();
+                    b.next();
+                    (p, f(e, g))
+                }
+                (Some(&&(p, e)), Some(&&(q, _))) if p < q => {
+                    a.next();
+                    (p, f(e, 0))
+                }
+                (Some(&&(p, e)), None) => {
+                    a.next();
+                    (p, f(e, 0))
+                }
+                (_, Some(&&(q, g))) => {
+                    b.next();
+                    (q, f(0, g))
+                }
+                (None, None) => break,
+            };
+
+            if e > 0 {
+                factors.push((p, e));
+            }
+        }
+
+        Factorization { factors }
+    }
+
+    /// The quotient `self / other`, or `None` if `other` does not divide `self`.
+    pub fn checked_div(&self, other: &Factorization) -> Option<Factorization> {
+        if other.iter().any(|&(p, e)| self.exponent(p) < e) {
+            return None;
+        }
+
+        Some(self.merge(other, |e, f| e - f))
+    }
+
+    pub fn gcd(&self, other: &Factorization) -> Factorization {
+        self.merge(other, u32::min)
+    }
+
+    pub fn lcm(&self, other: &Factorization) -> Factorization {
+        self.merge(other, u32::max)
+    }
+
+    /// The factorization raised to the power `k`. Panics if an exponent overflows a `u32`.
+    pub fn pow(&self, k: u32) -> Factorization {
+        if k == 0 {
+            return Factorization::new();
+        }
+
+        let factors = self
+            .factors
+            .iter()
+            .map(|&(p, e)| (p, e.checked_mul(k).expect("exponent overflow")))
+            .collect();
+
+        Factorization { factors }
+    }
+
+    /// Multiplies the factorization by `p^e`, where `p` is assumed to be prime. Panics if the
+    /// exponent of `p` overflows a `u32`.
+    pub fn insert(&mut self, p: u64, e: u32) {
+        if e == 0 {
+            return;
+        }
+
+        if let Some(last) = self.factors.last_mut() {
+            if last.0 == p {
+                last.1 = last.1.checked_add(e).expect("exponent overflow");
+                return;
+            }
+
+            if last.0 < p {
+                self.factors.push((p, e));
+                return;
+            }
+        } else {
+            self.factors.push((p, e));
+            return;
+        }
+
+        match self.factors.binary_search_by_key(&p, |&(q, _)| q) {
+            Ok(i) => {
+                let exponent = &mut self.factors[i].1;
+
+                *exponent = exponent.checked_add(e).expect("exponent overflow");
+            }
+            Err(i) => self.factors.insert(i, (p, e)),
+        }
+    }
+}
+
+impl Mul for &Factorization {
+    type Output = Factorization;
+
+    /// Panics if an exponent overflows a `u32`.
+    fn mul(self, other: &Factorization) -> Factorization {
+        self.merge(other, |e, f| e.checked_add(f).expect("exponent overflow"))
+    }
+}
+
+impl Mul for Factorization {
+    type Output = Factorization;
+
+    fn mul(self, other: Factorization) -> Factorization {
+        &self * &other
+    }
+}
+
+impl<'a> IntoIterator for &'a Factorization {
+    type Item = &'a (u64, u32);
+    type IntoIter = slice::Iter<'a, (u64, u32)>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.factors.iter()
+    }
+}
+
+impl IntoIterator for Factorization {
+    type Item = (u64, u32);
+    type IntoIter = std::vec::IntoIter<(u64, u32)>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.factors.into_iter()
+    }
+}
+
+impl FromIterator<(u64, u32)> for Factorization {
+    fn from_iter<I: IntoIterator<Item = (u64, u32)>>(iter: I) -> Factorization {
+        let mut factorization = Factorization::new();
+
+        for (p, e) in iter {
+            factorization.insert(p, e);
+        }
+
+        factorization
+    }
+}
+
+impl fmt::Display for Factorization {
+    /// Formats as `2^3 · 3 · 5^2`, or `1` when empty.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.factors.is_empty() {
+            return write!(f, "1");
+        }
+
+        for (i, &(p, e)) in self.factors.iter().enumerate() {
+            if i > 0 {
+                write!(f, " · ")?;
+            }
+
+            if e == 1 {
+                write!(f, "{p}")?;
+            } else {
+                write!(f, "{p}^{e}")?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// The error returned when parsing a [`Factorization`] fails.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseFactorizationError {
+    term: String,
+}
+
+impl fmt::Display for ParseFactorizationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid prime power `{}` in factorization", self.term)
+    }
+}
+
+impl Error for ParseFactorizationError {}
+
+impl FromStr for Factorization {
+    type Err = ParseFactorizationError;
+
+    /// Parses the [`Display`](fmt::Display) form, with terms separated by `·` or `*`. Every base
+    /// must be prime and every exponent positive, and `1` stands for the empty factorization.
+    fn from_str(s: &str) -> Result<Factorization, ParseFactorizationError> {
+        if s.trim() == "1" {
+            return Ok(Factorization::new());
+        }
+
+        s.split(['·', '*'])
+            .map(|term| {
+                let term = term.trim();
+                let error = || ParseFactorizationError {
+                    term: term.to_string(),
+                };
+
+                let (p, e) = match term.split_once('^') {
+                    Some((p, e)) => (p.trim(), e.trim().parse().map_err(|_| error())?),
+                    None => (term, 1),
+                };
+                let p: u64 = p.parse().map_err(|_| error())?;
+
+                if e == 0 || !is_prime(p) {
+                    return Err(error());
+                }
+
+                Ok((p, e))
+            })
+            .collect()
+    }
+}
+
+// Serialized as the list of `(prime, exponent)` pairs. Deserializing checks that every base is
+// prime, and merges and sorts the pairs like `FromIterator` does.
+#[cfg(feature = "serde")]
+impl serde::Serialize for Factorization {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(&self.factors)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Factorization {
+    fn deserialize<D: serde::Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Factorization, D::Error> {
+        let factors = Vec::<(u64, u32)>::deserialize(deserializer)?;
+
+        if let Some(&(p, _)) = factors.iter().find(|&&(p, _)| !is_prime(p)) {
+            return Err(serde::de::Error::custom(format!("{p} is not prime")));
+        }
+
+        Ok(factors.into_iter().collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn factorization_insert_01() {
+        let mut f = Factorization::new();
+
+        f.insert(5, 1);
+        f.insert(2, 3);
+        f.insert(5, 1);
+        f.insert(3, 0);
+        f.insert(7, 1);
+
+        assert_eq!(f.as_slice(), &[(2, 3), (5, 2), (7, 1)]);
+        assert_eq!(f.exponent(5), 2);
+        assert_eq!(f.exponent(3), 0);
+    }
+
+    #[test]
+    fn factorization_value_01() {
+        let f: Factorization = [(2, 3), (3, 1), (5, 2)].into_iter().collect();
+
+        assert_eq!(f.value(), Some(600));
+        assert_eq!(f.num_divisors(), Some(24));
+        assert_eq!(f.totient(), Some(160));
+
+        let empty = Factorization::new();
+
+        assert_eq!(empty.value(), Some(1));
+        assert_eq!(empty.num_divisors(), Some(1));
+        assert_eq!(empty.totient(), Some(1));
+
+        let big: Factorization = [(2, 64)].into_iter().collect();
+
+        assert_eq!(big.value(), None);
+        assert_eq!(big.num_divisors(), Some(65));
+        assert_eq!(big.totient(), Some(1 << 63));
+    }
+
+    #[test]
+    fn factorization_arithmetic_01() {
+        let a: Factorization = [(2, 3), (3, 1), (7, 2)].into_iter().collect();
+        let b: Factorization = [(2, 1), (5, 1), (7, 4)].into_iter().collect();
+        let (x, y) = (a.value().unwrap(), b.value().unwrap());
+
+        assert_eq!((&a * &b).value(), Some(x * y));
+        assert_eq!(a.gcd(&b).as_slice(), &[(2, 1), (7, 2)]);
+        assert_eq!(a.lcm(&b).as_slice(), &[(2, 3), (3, 1), (5, 1), (7, 4)]);
+        assert_eq!(a.pow(3).value(), Some(x.pow(3)));
+        assert!(a.pow(0).is_empty());
+
+        assert_eq!((&a * &b).checked_div(&b), Some(a.clone()));
+        assert_eq!(a.checked_div(&a), Some(Factorization::new()));
+        assert_eq!(a.checked_div(&b), None);
+
+        // 1000! / (500!)^2 is the central binomial coefficient C(1000, 500), far beyond u64.
+        let factorial = |n: u64| -> Factorization {
+            (2..=n)
+                .map(crate::factorization)
+                .fold(Factorization::new(), |acc, f| acc * f)
+        };
+        let binomial = factorial(1000).checked_div(&factorial(500).pow(2)).unwrap();
+
+        assert_eq!(binomial.exponent(2), 6);
+        assert_eq!(binomial.exponent(997), 1);
+        assert_eq!(binomial.exponent(499), 0);
+    }
+
+    #[test]
+    fn factorization_display_01() {
+        let f: Factorization = [(2, 3), (3, 1), (5, 2)].into_iter().collect();
+
+        assert_eq!(f.to_string(), "2^3 · 3 · 5^2");
+        assert_eq!(Factorization::new().to_string(), "1");
+
+        assert_eq!("2^3 · 3 · 5^2".parse(), Ok(f.clone()));
+        assert_eq!("5^2*2^3 * 3".parse(), Ok(f));
+        assert_eq!("1".parse(), Ok(Factorization::new()));
+
+        let g: Factorization = [(3, 4), (1_000_003, 1)].into_iter().collect();
+
+        assert_eq!(g.to_string().parse(), Ok(g));
+
+        for bad in ["", "4", "2^0", "2^", "2 · · 3", "x^2", "0"] {
+            assert!(bad.parse::<Factorization>().is_err(), "{bad}");
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn factorization_serde_01() {
+        let f: Factorization = [(2, 3), (1_000_003, 1)].into_iter().collect();
+        let json = serde_json::to_string(&f).unwrap();
+
+        assert_eq!(json, "[[2,3],[1000003,1]]");
+        assert_eq!(serde_json::from_str::<Factorization>(&json).unwrap(), f);
+        assert_eq!(
+            serde_json::from_str::<Factorization>("[[1000003,1],[2,2],[2,1]]").unwrap(),
+            f
+        );
+        assert!(serde_json::from_str::<Factorization>("[[4,1]]").is_err());
+    }
+}
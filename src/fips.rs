@@ -0,0 +1,474 @@
+use num_bigint::BigUint;
+use num_integer::Integer;
+use num_traits::{One, ToPrimitive, Zero};
+#[cfg(feature = "rand")]
+use rand::Rng;
+use sha2::{Digest, Sha256};
+
+#[cfg(feature = "rand")]
+use crate::factor::{is_strong_probable_prime_biguint, trial_primes};
+#[cfg(feature = "rand")]
+use crate::random::{random_biguint, Wiped};
+#[cfg(feature = "rand")]
+use crate::RsaPrimes;
+use crate::{is_prime, PrimeCertificate};
+
+// The output length of SHA-256 in bits, outlen in FIPS 186-4.
+const OUTLEN: u64 = 256;
+
+/// A prime from the Shawe-Taylor procedure of FIPS 186-4 Appendix C.6, with the values the
+/// standard returns alongside it for auditing, and a certificate proving it prime.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ShaweTaylorPrime {
+    pub prime: BigUint,
+    /// The seed after the procedure, as bytes of the input seed's length.
+    pub prime_seed: Vec<u8>,
+    pub prime_gen_counter: u64,
+    pub certificate: PrimeCertificate,
+}
+
+// Serialized as an object with the `prime` as a decimal string, the `prime_seed` bytes, the
+// `prime_gen_counter` and the `certificate`. Deserializing verifies the certificate, and checks
+// that it is for the prime.
+#[cfg(feature = "serde")]
+impl serde::Serialize for ShaweTaylorPrime {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeStruct;
+
+        let mut state = serializer.serialize_struct("ShaweTaylorPrime", 4)?;
+
+        state.serialize_field("prime", &self.prime.to_string())?;
+        state.serialize_field("prime_seed", &self.prime_seed)?;
+        state.serialize_field("prime_gen_counter", &self.prime_gen_counter)?;
+        state.serialize_field("certificate", &self.certificate)?;
+        state.end()
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for ShaweTaylorPrime {
+    fn deserialize<D: serde::Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<ShaweTaylorPrime, D::Error> {
+        deserializer.deserialize_struct(
+            "ShaweTaylorPrime",
+            SHAWE_TAYLOR_PRIME_FIELDS,
+            ShaweTaylorPrimeVisitor,
+        )
+    }
+}
+
+#[cfg(feature = "serde")]
+const SHAWE_TAYLOR_PRIME_FIELDS: &[&str] =
+    &["prime", "prime_seed", "prime_gen_counter", "certificate"];
+
+#[cfg(feature = "serde")]
+struct ShaweTaylorPrimeVisitor;
+
+#[cfg(feature = "serde")]
+impl<'de> serde::de::Visitor<'de> for ShaweTaylorPrimeVisitor {
+    type Value = ShaweTaylorPrime;
+
+    fn expecting(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "a Shawe-Taylor prime")
+    }
+
+    fn visit_map<A: serde::de::MapAccess<'de>>(
+        self,
+        mut map: A,
+    ) -> Result<ShaweTaylorPrime, A::Error> {
+        use serde::de::Error;
+
+        let (mut prime, mut prime_seed, mut prime_gen_counter, mut certificate) =
+            (None, None, None, None::<PrimeCertificate>);
+
+        while let Some(key) = map.next_key::<String>()? {
+            match key.as_str() {
+                "prime" => {
+                    let s: String = map.next_value()?;
+
+                    prime =
+                        Some(s.parse::<BigUint>().map_err(|_| {
+                            A::Error::custom(format!("{s} is not a decimal integer"))
+                        })?);
+                }
+                "prime_seed" => prime_seed = Some(map.next_value()?),
+                "prime_gen_counter" => prime_gen_counter = Some(map.next_value()?),
+                "certificate" => certificate = Some(map.next_value()?),
+                _ => return Err(A::Error::unknown_field(&key, SHAWE_TAYLOR_PRIME_FIELDS)),
+            }
+        }
+
+        let prime = prime.ok_or_else(|| A::Error::missing_field("prime"))?;
+        let certificate = certificate.ok_or_else(|| A::Error::missing_field("certificate"))?;
+
+        if certificate.n() != prime {
+            return Err(A::Error::custom(format!(
+                "the certificate is not for {prime}"
+            )));
+        }
+
+        Ok(ShaweTaylorPrime {
+            prime,
+            prime_seed: prime_seed.ok_or_else(|| A::Error::missing_field("prime_seed"))?,
+            prime_gen_counter: prime_gen_counter
+                .ok_or_else(|| A::Error::missing_field("prime_gen_counter"))?,
+            certificate,
+        })
+    }
+}
+
+/// The provable prime of `length` bits that FIPS 186-4 Appendix C.6 derives from `input_seed`
+/// with SHA-256, or `None` where the standard returns FAILURE, when `length < 2`, the seed is
+/// empty, or the generation counter runs out. The same seed always gives the same prime, so a
+/// third party can repeat the derivation, and each recursive step is a Pocklington proof.
+pub fn shawe_taylor_random_prime(length: u64, input_seed: &[u8]) -> Option<ShaweTaylorPrime> {
+    if length < 2 || input_seed.is_empty() {
+        return None;
+    }
+
+    let seed = BigUint::from_bytes_be(input_seed);
+    let (prime, prime_seed, prime_gen_counter, certificate) =
+        shawe_taylor(length, seed, input_seed.len())?;
+
+    Some(ShaweTaylorPrime {
+        prime,
+        prime_seed: seed_bytes(&prime_seed, input_seed.len()),
+        prime_gen_counter,
+        certificate,
+    })
+}
+
+/// The minimum rounds of Miller–Rabin that FIPS 186-4 Table C.3 mandates when generating the
+/// probable primes of an RSA modulus of `nlen` bits with no Lucas test, as
+/// `(auxiliary primes, p and q)`, or `None` for a modulus size the table does not list.
+pub fn fips_186_4_rsa_miller_rabin_rounds(nlen: u64) -> Option<(usize, usize)> {
+    match nlen {
+        1024 => Some((28, 7)),
+        2048 => Some((38, 5)),
+        3072 => Some((41, 4)),
+        _ => None,
+    }
+}
+
+/// The primes `p > q` of an RSA modulus of `nlen` bits with public exponent `e`, generated as
+/// probable primes by FIPS 186-4 Appendix B.3.3. Each candidate that meets the size, distance
+/// and `gcd(p − 1, e) = 1` conditions is trial divided and then given the rounds of Miller–Rabin
+/// from Appendix C.3 that [`fips_186_4_rsa_miller_rabin_rounds`] lists for `nlen`. `None` where
+/// the standard returns FAILURE: for a modulus size the table does not list, an `e` that is even
+/// or outside (2^16, 2^256), or when either search tests 5 nlen / 2 candidates without finding a
+/// prime.
+#[cfg(feature = "rand")]
+pub fn fips_186_4_probable_primes<R: Rng + ?Sized>(
+    nlen: u64,
+    e: &BigUint,
+    rng: &mut R,
+) -> Option<RsaPrimes<BigUint>> {
+    let (_, rounds) = fips_186_4_rsa_miller_rabin_rounds(nlen)?;
+
+    if e.is_even() || e.bits() <= 16 || e.bits() > 256 {
+        return None;
+    }
+
+    let half = nlen / 2;
+    // p >= sqrt(2) 2^(nlen / 2 − 1) exactly when p^2 >= 2^(nlen − 1).
+    let square_bound = BigUint::one() << (nlen - 1);
+    let distance_bound = BigUint::one() << (half - 100);
+
+    // Only candidates that reach the gcd and Miller–Rabin steps count toward the limit; those
+    // too small or too close to p are drawn again, as in steps 4.4, 5.4 and 5.5.
+    let prime = |p: Option<&Wiped>, rng: &mut R| {
+        let mut tested = 0;
+
+        while tested < 5 * half {
+            let candidate = Wiped(random_biguint(half, rng) | BigUint::one());
+
+            if let Some(p) = p {
+                let distance = Wiped(if **p > *candidate {
+                    &**p - &*candidate
+                } else {
+                    &*candidate - &**p
+                });
+
+                if *distance <= distance_bound {
+                    continue;
+                }
+            }
+
+            if *Wiped(&*candidate * &*candidate) < square_bound {
+                continue;
+            }
+
+            if Wiped(&*candidate - 1u32).gcd(e).is_one() && miller_rabin(&candidate, rounds, rng) {
+                return Some(candidate);
+            }
+
+            tested += 1;
+        }
+
+        None
+    };
+
+    let p = prime(None, rng)?;
+    let q = prime(Some(&p), rng)?;
+    let (p, q) = if *p > *q { (p, q) } else { (q, p) };
+
+    Some(RsaPrimes {
+        p: p.into_inner(),
+        q: q.into_inner(),
+    })
+}
+
+// The probabilistic primality test of Appendix C.3.1 with the given rounds, after trial division
+// by the small primes, with bases drawn uniformly from [2, w − 2] by rejection.
+#[cfg(feature = "rand")]
+fn miller_rabin<R: Rng + ?Sized>(w: &BigUint, rounds: usize, rng: &mut R) -> bool {
+    if trial_primes().iter().any(|&p| (w % p).is_zero()) {
+        return false;
+    }
+
+    let top = Wiped(w - 1u32);
+
+    (0..rounds).all(|_| {
+        let base = loop {
+            let b = Wiped(random_biguint(w.bits(), rng));
+
+            if *b > BigUint::one() && *b < *top {
+                break b;
+            }
+        };
+
+        is_strong_probable_prime_biguint(w, &base)
+    })
+}
+
+// Steps 2 to 33 of ST_Random_Prime, returning (c, prime_seed, prime_gen_counter, certificate).
+fn shawe_taylor(
+    length: u64,
+    input_seed: BigUint,
+    seedlen: usize,
+) -> Option<(BigUint, BigUint, u64, PrimeCertificate)> {
+    if length < 33 {
+        let mut prime_seed = input_seed;
+        let mut prime_gen_counter = 0;
+
+        loop {
+            let c = hash(&prime_seed, seedlen) ^ hash(&(&prime_seed + 1u32), seedlen);
+            let c = (1u64 << (length - 1)) + (c % (1u64 << (length - 1))).to_u64().unwrap();
+            let c = c | 1;
+
+            prime_gen_counter += 1;
+            prime_seed += 2u32;
+
+            if is_prime(c) {
+                return Some((
+                    BigUint::from(c),
+                    prime_seed,
+                    prime_gen_counter,
+                    PrimeCertificate::Small(c),
+                ));
+            }
+
+            if prime_gen_counter > 4 * length {
+                return None;
+            }
+        }
+    }
+
+    let (c0, mut prime_seed, mut prime_gen_counter, c0_certificate) =
+        shawe_taylor(length.div_ceil(2) + 1, input_seed, seedlen)?;
+    let iterations = length.div_ceil(OUTLEN) - 1;
+    let old_counter = prime_gen_counter;
+    let top = BigUint::one() << (length - 1);
+    let two_c0 = &c0 * 2u32;
+
+    let x = hash_blocks(&mut prime_seed, seedlen, iterations);
+    let x = &top + x % &top;
+    let mut t = x.div_ceil(&two_c0);
+
+    loop {
+        if &two_c0 * &t + 1u32 > BigUint::one() << length {
+            t = top.div_ceil(&two_c0);
+        }
+
+        let c = &two_c0 * &t + 1u32;
+
+        prime_gen_counter += 1;
+
+        let a = hash_blocks(&mut prime_seed, seedlen, iterations);
+        let a = a % (&c - 3u32) + 2u32;
+        let z = a.modpow(&(&t * 2u32), &c);
+
+        // z = 0 has no z − 1 to take the gcd of, and such a c simply fails step 30.
+        if !z.is_zero() && (&z - 1u32).gcd(&c).is_one() && z.modpow(&c0, &c).is_one() {
+            let certificate = PrimeCertificate::Pocklington {
+                n: c.clone(),
+                witness: a,
+                factors: vec![c0_certificate],
+            };
+
+            return Some((c, prime_seed, prime_gen_counter, certificate));
+        }
+
+        if prime_gen_counter >= 4 * length + old_counter {
+            return None;
+        }
+
+        t += 1u32;
+    }
+}
+
+// Σ Hash(prime_seed + i) 2^(i outlen) for i in 0..=iterations, advancing prime_seed past them.
+fn hash_blocks(prime_seed: &mut BigUint, seedlen: usize, iterations: u64) -> BigUint {
+    let mut x = BigUint::default();
+
+    for i in 0..=iterations {
+        x += hash(&(&*prime_seed + i), seedlen) << (i * OUTLEN);
+    }
+
+    *prime_seed += iterations + 1;
+    x
+}
+
+// SHA-256 of the seed as a big-endian string of seedlen bytes, as an integer.
+fn hash(seed: &BigUint, seedlen: usize) -> BigUint {
+    BigUint::from_bytes_be(&Sha256::digest(seed_bytes(seed, seedlen)))
+}
+
+// The seed reduced modulo 2^(8 seedlen), as exactly seedlen big-endian bytes.
+fn seed_bytes(seed: &BigUint, seedlen: usize) -> Vec<u8> {
+    let bytes = seed.to_bytes_be();
+
+    if bytes.len() >= seedlen {
+        bytes[bytes.len() - seedlen..].to_vec()
+    } else {
+        [vec![0; seedlen - bytes.len()], bytes].concat()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::is_prime_biguint;
+    #[cfg(feature = "rand")]
+    use crate::Drbg;
+
+    #[test]
+    fn shawe_taylor_random_prime_01() {
+        let seed = [0x5a; 32];
+
+        for length in [2, 3, 16, 32, 33, 64, 65, 256, 257, 512] {
+            let st = shawe_taylor_random_prime(length, &seed).unwrap();
+
+            assert_eq!(st.prime.bits(), length);
+            assert!(is_prime_biguint(&st.prime));
+            assert!(st.certificate.verify());
+            assert_eq!(st.certificate.n(), st.prime);
+            assert_eq!(st.prime_seed.len(), seed.len());
+            assert_eq!(shawe_taylor_random_prime(length, &seed), Some(st));
+        }
+
+        // Different seeds give different primes, and the seed wraps at its own length.
+        assert_ne!(
+            shawe_taylor_random_prime(512, &[1; 32]).unwrap().prime,
+            shawe_taylor_random_prime(512, &[2; 32]).unwrap().prime
+        );
+        assert!(shawe_taylor_random_prime(64, &[0xff; 4]).is_some());
+        assert_eq!(shawe_taylor_random_prime(1, &seed), None);
+        assert_eq!(shawe_taylor_random_prime(64, &[]), None);
+    }
+
+    #[test]
+    fn shawe_taylor_random_prime_02() {
+        // Regression values rather than CAVP vectors, cross-checked against a separate
+        // implementation of Appendix C.6 written from the text of the standard.
+        let seed = [0x5a; 32];
+        let hex = |s: &str| BigUint::parse_bytes(s.as_bytes(), 16).unwrap();
+
+        for (length, prime, counter, last) in [
+            (32, "9baf10d3", 1, 0x5c),
+            (64, "b415407c2175971d", 19, 0x75),
+            (
+                512,
+                "fcb3a23d5182d85fe2453d8ddcf45bdb53bf0809fd52502bbbeaae77518ffbe3\
+                 5e7504f3eb4cc441d639b499695770697a1b069e653b5f46558664d725269c91",
+                592,
+                0x96,
+            ),
+        ] {
+            let st = shawe_taylor_random_prime(length, &seed).unwrap();
+            assert_eq!(st.prime, hex(prime));
+            assert_eq!(st.prime_gen_counter, counter);
+            assert_eq!(st.prime_seed[31], last);
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn shawe_taylor_prime_serde_01() {
+        let st = shawe_taylor_random_prime(256, &[0x5a; 32]).unwrap();
+        let json = serde_json::to_string(&st).unwrap();
+
+        assert_eq!(serde_json::from_str::<ShaweTaylorPrime>(&json).unwrap(), st);
+
+        // A valid certificate for a different prime is rejected.
+        let other = shawe_taylor_random_prime(256, &[0xa5; 32]).unwrap();
+        let forged = serde_json::to_string(&ShaweTaylorPrime {
+            certificate: other.certificate,
+            ..st
+        })
+        .unwrap();
+
+        assert!(serde_json::from_str::<ShaweTaylorPrime>(&forged).is_err());
+    }
+
+    #[cfg(feature = "rand")]
+    #[test]
+    fn fips_186_4_probable_primes_01() {
+        let e = BigUint::from(65_537u32);
+        let mut rng = Drbg::from_u64(1);
+        let RsaPrimes { p, q } = fips_186_4_probable_primes(1024, &e, &mut rng).unwrap();
+
+        // Regression values for this seed, checked independently against the conditions of
+        // Appendix B.3.3.
+        assert_eq!(
+            p,
+            BigUint::parse_bytes(
+                b"e7895b014e41a516bf3181de4eecf87b490973cb2431178c915d7747d81732ef\
+                  e2e5f7b55c573b1236d6b10ca0564f6ead11324ff63b25dc174512163b4bce85",
+                16
+            )
+            .unwrap()
+        );
+
+        for prime in [&p, &q] {
+            assert_eq!(prime.bits(), 512);
+            assert!(prime * prime >= BigUint::one() << 1023);
+            assert!((prime - 1u32).gcd(&e).is_one());
+            assert!(is_prime_biguint(prime));
+        }
+
+        assert!(p > q && (&p - &q).bits() > 412);
+        assert_eq!((&p * &q).bits(), 1024);
+
+        assert_eq!(fips_186_4_probable_primes(4096, &e, &mut rng), None);
+        assert_eq!(
+            fips_186_4_probable_primes(2048, &BigUint::from(3u32), &mut rng),
+            None
+        );
+        assert_eq!(
+            fips_186_4_probable_primes(2048, &BigUint::from(65_536u32), &mut rng),
+            None
+        );
+        assert_eq!(
+            fips_186_4_probable_primes(2048, &((BigUint::one() << 256) + 1u32), &mut rng),
+            None
+        );
+    }
+
+    #[test]
+    fn fips_186_4_rsa_miller_rabin_rounds_01() {
+        assert_eq!(fips_186_4_rsa_miller_rabin_rounds(2048), Some((38, 5)));
+        assert_eq!(fips_186_4_rsa_miller_rabin_rounds(4096), None);
+    }
+}
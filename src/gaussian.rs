@@ -0,0 +1,249 @@
+use std::ops::Mul;
+
+use crate::{factorization, is_prime, is_prime_u128, sqrt_mod_prime};
+
+/// A Gaussian integer `re + im i` in `ℤ[i]`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct GaussianInt {
+    pub re: i64,
+    pub im: i64,
+}
+
+impl GaussianInt {
+    pub const fn new(re: i64, im: i64) -> GaussianInt {
+        GaussianInt { re, im }
+    }
+
+    /// The norm `re^2 + im^2`, multiplicative and never overflowing.
+    pub fn norm(self) -> u128 {
+        let re = self.re.unsigned_abs() as u128;
+        let im = self.im.unsigned_abs() as u128;
+
+        re * re + im * im
+    }
+
+    pub fn conj(self) -> GaussianInt {
+        GaussianInt::new(self.re, -self.im)
+    }
+
+    /// Whether this is one of the units 1, i, −1 and −i.
+    pub fn is_unit(self) -> bool {
+        self.norm() == 1
+    }
+
+    // self / other if other divides self, for norms that fit in a u64.
+    fn div_exact(self, other: GaussianInt) -> Option<GaussianInt> {
+        let (a, b) = (self.re as i128, self.im as i128);
+        let (c, d) = (other.re as i128, other.im as i128);
+        let n = c * c + d * d;
+        let (re, im) = (a * c + b * d, b * c - a * d);
+
+        (re % n == 0 && im % n == 0).then(|| GaussianInt::new((re / n) as i64, (im / n) as i64))
+    }
+}
+
+impl Mul for GaussianInt {
+    type Output = GaussianInt;
+
+    /// Panics if the product does not fit.
+    fn mul(self, other: GaussianInt) -> GaussianInt {
+        let (a, b) = (self.re as i128, self.im as i128);
+        let (c, d) = (other.re as i128, other.im as i128);
+        let part = |x: i128| i64::try_from(x).expect("Gaussian integer overflow");
+
+        GaussianInt::new(part(a * c - b * d), part(a * d + b * c))
+    }
+}
+
+/// Whether `a + b i` is a Gaussian prime: either its norm `a^2 + b^2` is a rational prime, or it
+/// is an associate of a rational prime `≡ 3 (mod 4)`, which stays prime in `ℤ[i]`.
+pub fn is_gaussian_prime(a: i64, b: i64) -> bool {
+    if a == 0 || b == 0 {
+        let n = a.unsigned_abs() + b.unsigned_abs();
+
+        return n % 4 == 3 && is_prime(n);
+    }
+
+    is_prime_u128(GaussianInt::new(a, b).norm())
+}
+
+/// The decomposition `p = a^2 + b^2` with `a > b > 0` of a prime `p ≡ 1 (mod 4)`, or `(1, 1)`
+/// for `p = 2`, or `None` for primes `≡ 3 (mod 4)`, which have none. Found from a square root
+/// `x` of −1 modulo `p` by running Euclid's algorithm on `p` and `x` down to the first
+/// remainder below `sqrt(p)`. Panics if `p` is not prime.
+pub fn prime_two_squares(p: u64) -> Option<(u64, u64)> {
+    assert!(is_prime(p), "{p} is not prime");
+
+    if p == 2 {
+        return Some((1, 1));
+    }
+
+    let x = sqrt_mod_prime(p - 1, p)?;
+    let (mut r0, mut r1) = (p, x);
+
+    while r1 as u128 * r1 as u128 > p as u128 {
+        (r0, r1) = (r1, r0 % r1);
+    }
+
+    let a = r1;
+    let b = (p - a * a).isqrt();
+
+    Some((a.max(b), a.min(b)))
+}
+
+/// The factorization of `a + b i` into Gaussian primes, as a unit and pairs of a prime and its
+/// exponent, with the product of the unit and every prime power equal to `a + b i`. Each prime
+/// is the associate in the first quadrant, with positive real part and nonnegative imaginary
+/// part, and they come in increasing order of norm. The norm is factored over the integers:
+/// 2 gives powers of `1 + i`, primes `p ≡ 3 (mod 4)` give `p` itself, and primes
+/// `p ≡ 1 (mod 4)` split as `(x + y i)(x − y i)` by [`prime_two_squares`], each side found by
+/// trial division. Panics if `a + b i` is zero or its norm does not fit in a `u64`.
+pub fn gaussian_factorization(a: i64, b: i64) -> (GaussianInt, Vec<(GaussianInt, u32)>) {
+    let mut z = GaussianInt::new(a, b);
+
+    assert!(z != GaussianInt::default(), "0 has no factorization");
+
+    let norm = u64::try_from(z.norm()).expect("norm must fit in a u64");
+    let mut factors = vec![];
+
+    // Divides z by prime as often as it goes, and records the power.
+    let mut divide_out = |z: &mut GaussianInt, prime: GaussianInt, max: u32| {
+        let mut e = 0;
+
+        while e < max {
+            match z.div_exact(prime) {
+                Some(q) => *z = q,
+                None => break,
+            }
+
+            e += 1;
+        }
+
+        if e > 0 {
+            factors.push((prime, e));
+        }
+
+        e
+    };
+
+    for &(p, e) in factorization(norm).iter() {
+        let p = p as i64;
+
+        match p % 4 {
+            2 => {
+                divide_out(&mut z, GaussianInt::new(1, 1), e);
+            }
+            3 => {
+                divide_out(&mut z, GaussianInt::new(p, 0), e / 2);
+            }
+            _ => {
+                let (x, y) = prime_two_squares(p as u64).unwrap();
+                let (x, y) = (x as i64, y as i64);
+                let k = divide_out(&mut z, GaussianInt::new(x, y), e);
+
+                // The conjugate x − y i, rotated into the first quadrant.
+                divide_out(&mut z, GaussianInt::new(y, x), e - k);
+            }
+        }
+    }
+
+    factors.sort_by_key(|&(prime, _)| (prime.norm(), prime.re));
+
+    (z, factors)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::primes_upto;
+
+    #[test]
+    fn is_gaussian_prime_01() {
+        // Against trial division by every Gaussian integer of smaller norm.
+        for a in -20i64..=20 {
+            for b in -20i64..=20 {
+                let z = GaussianInt::new(a, b);
+                let n = z.norm();
+                let has_divisor = (-20i64..=20).any(|c| {
+                    (-20i64..=20).any(|d| {
+                        let w = GaussianInt::new(c, d);
+
+                        w.norm() > 1 && w.norm() < n && z.div_exact(w).is_some()
+                    })
+                });
+
+                assert_eq!(is_gaussian_prime(a, b), n > 1 && !has_divisor, "{a} {b}");
+            }
+        }
+
+        assert!(is_gaussian_prime(0, -7) && !is_gaussian_prime(5, 0));
+        assert!(is_gaussian_prime(i64::MAX, 0) == is_prime(i64::MAX as u64));
+    }
+
+    #[test]
+    fn prime_two_squares_01() {
+        for p in primes_upto(20_000) {
+            match prime_two_squares(p) {
+                Some((a, b)) => {
+                    assert!(a > b || p == 2);
+                    assert_eq!(a * a + b * b, p);
+                }
+                None => assert_eq!(p % 4, 3),
+            }
+        }
+
+        assert_eq!(prime_two_squares(13), Some((3, 2)));
+
+        for p in [1_000_000_009, 18_446_744_073_709_551_557] {
+            let (a, b) = prime_two_squares(p).unwrap();
+
+            assert_eq!(a * a + b * b, p);
+        }
+    }
+
+    #[test]
+    fn gaussian_factorization_01() {
+        let check = |a: i64, b: i64| {
+            let (unit, factors) = gaussian_factorization(a, b);
+            let product = factors
+                .iter()
+                .fold(unit, |z, &(prime, e)| (0..e).fold(z, |z, _| z * prime));
+
+            assert!(unit.is_unit());
+            assert_eq!(product, GaussianInt::new(a, b), "{a} {b}");
+
+            for &(prime, _) in &factors {
+                assert!(is_gaussian_prime(prime.re, prime.im));
+                assert!(prime.re > 0 && prime.im >= 0);
+            }
+
+            assert!(factors.windows(2).all(|w| w[0].0 != w[1].0));
+        };
+
+        for a in -40..=40 {
+            for b in -40..=40 {
+                if (a, b) != (0, 0) {
+                    check(a, b);
+                }
+            }
+        }
+
+        check(4_294_967_295, 0);
+        check(3_037_000_499, 3_037_000_499);
+        check(i32::MAX as i64, i32::MIN as i64);
+
+        // 2 = −i (1 + i)^2, and 5 splits as (2 + i)(1 + 2 i) up to a unit.
+        assert_eq!(
+            gaussian_factorization(2, 0),
+            (GaussianInt::new(0, -1), vec![(GaussianInt::new(1, 1), 2)])
+        );
+        assert_eq!(
+            gaussian_factorization(5, 0).1,
+            [(GaussianInt::new(1, 2), 1), (GaussianInt::new(2, 1), 1)]
+        );
+        assert_eq!(
+            gaussian_factorization(0, 9),
+            (GaussianInt::new(0, 1), vec![(GaussianInt::new(3, 0), 2)])
+        );
+    }
+}
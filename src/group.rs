@@ -0,0 +1,461 @@
+use std::collections::HashMap;
+
+use crate::modular::{crt, ext_gcd, gcd, mod_inv, mod_pow, mul_mod};
+use crate::{factorization, is_prime, Factorization};
+
+/// The least `x >= 0` with `base^x ≡ target (mod modulus)`, or `None` if there is none, by
+/// Shanks' baby-step giant-step algorithm in `O(sqrt(modulus))` time and space. `base` need not
+/// be coprime to `modulus`. Moduli up to 2^48 fit in a few hundred megabytes; above that, once
+/// the factors shared with `base` are divided out, the log is found by [`pohlig_hellman`]
+/// instead, which bounds the search by the largest prime factor of the order of `base`. Panics
+/// if `modulus` is 0, or if that prime factor is also above 2^48.
+pub fn discrete_log(base: u64, target: u64, modulus: u64) -> Option<u64> {
+    assert!(modulus != 0, "modulus must be nonzero");
+
+    let base = base % modulus;
+    let mut target = target % modulus;
+    let mut m = modulus;
+
+    if target == 1 % m {
+        return Some(0);
+    }
+
+    // Divide out the common factors of base and m, leaving coefficient · base^y ≡ target with
+    // base coprime to m, and x = y + offset.
+    let mut coefficient = 1 % m;
+    let mut offset = 0;
+
+    loop {
+        let g = gcd(base, m);
+
+        if g == 1 {
+            break;
+        }
+
+        if target == coefficient {
+            return Some(offset);
+        }
+
+        if !target.is_multiple_of(g) {
+            return None;
+        }
+
+        target /= g;
+        m /= g;
+        coefficient = (coefficient as u128 * base as u128 / g as u128 % m as u128) as u64;
+        offset += 1;
+    }
+
+    // coefficient is a unit modulo m, since each base / g is coprime to the next m.
+    let y = if m <= MAX_STEPS_BOUND {
+        baby_step_giant_step(base, coefficient, target, m, m)?
+    } else {
+        let target = mul_mod(target, mod_inv(coefficient, m).unwrap(), m);
+
+        pohlig_hellman(base, target, m)?
+    };
+
+    Some(y + offset)
+}
+
+// The largest search bound for baby-step giant-step, whose table then holds 2^24 entries.
+const MAX_STEPS_BOUND: u64 = 1 << 48;
+
+// The least x with coefficient · base^x ≡ target (mod m) for base coprime to m, searching every
+// x up to bound, which should be at least the order of base. Panics if bound is above
+// MAX_STEPS_BOUND.
+fn baby_step_giant_step(
+    base: u64,
+    coefficient: u64,
+    target: u64,
+    m: u64,
+    bound: u64,
+) -> Option<u64> {
+    assert!(
+        bound <= MAX_STEPS_BOUND,
+        "a discrete log in a group of order {bound} needs too large a baby-step table"
+    );
+
+    let steps = bound.isqrt() + 1;
+    let mut baby = HashMap::with_capacity(steps as usize + 1);
+    let mut current = target;
+
+    // Later, larger j overwrite earlier ones, so the first giant step match gives the least x.
+    for j in 0..=steps {
+        baby.insert(current, j);
+        current = mul_mod(current, base, m);
+    }
+
+    let giant = mod_pow(base, steps, m);
+    let mut current = coefficient;
+
+    for i in 1..=steps {
+        current = mul_mod(current, giant, m);
+
+        if let Some(&j) = baby.get(&current) {
+            return Some(i * steps - j);
+        }
+    }
+
+    None
+}
+
+// The factorization of φ(n), from those of n and of p − 1 for each prime p | n.
+fn totient_factorization(n: u64) -> Factorization {
+    factorization(n)
+        .iter()
+        .fold(Factorization::new(), |phi, &(p, e)| {
+            let mut local = factorization(p - 1);
+
+            local.insert(p, e - 1);
+            &phi * &local
+        })
+}
+
+// The factorization of the Carmichael function λ(n), the exponent of (Z/nZ)*: the lcm over
+// p^e || n of φ(p^e), except that λ(2^e) = 2^(e − 2) for e >= 3.
+fn carmichael_factorization(n: u64) -> Factorization {
+    factorization(n)
+        .iter()
+        .fold(Factorization::new(), |lambda, &(p, e)| {
+            let local = if p == 2 {
+                let k = if e >= 3 { e - 2 } else { e - 1 };
+
+                Factorization::from_iter([(2, k)])
+            } else {
+                let mut local = factorization(p - 1);
+
+                local.insert(p, e - 1);
+                local
+            };
+
+            lambda.lcm(&local)
+        })
+}
+
+// The order of the unit a modulo n, given a multiple of it in factored form, by removing each
+// prime from the multiple for as long as a^(multiple / p) is still 1.
+fn order_dividing(a: u64, n: u64, multiple: &Factorization) -> Factorization {
+    let mut value = multiple.value().unwrap();
+    let mut order = Factorization::new();
+
+    for &(p, e) in multiple.iter() {
+        let mut kept = e;
+
+        while kept > 0 && mod_pow(a, value / p, n) == 1 % n {
+            value /= p;
+            kept -= 1;
+        }
+
+        order.insert(p, kept);
+    }
+
+    order
+}
+
+/// The least primitive root modulo `n`, a generator of the units `(Z/nZ)*`, or `None` if the
+/// group is not cyclic. One exists exactly when `n` is 1, 2, 4, `p^k` or `2 p^k` for an odd
+/// prime `p`. Candidates are tested against each prime factor of `φ(n)`. Panics if `n` is 0.
+pub fn primitive_root(n: u64) -> Option<u64> {
+    assert!(n != 0, "modulus must be nonzero");
+
+    if n <= 2 {
+        return Some(n - 1);
+    }
+
+    if n == 4 {
+        return Some(3);
+    }
+
+    let odd = if n.is_multiple_of(2) { n / 2 } else { n };
+    let f = factorization(odd);
+
+    if odd.is_multiple_of(2) || f.len() != 1 {
+        return None;
+    }
+
+    let phi = totient_factorization(n);
+    let phi_value = phi.value().unwrap();
+
+    (2..n).find(|&g| gcd(g, n) == 1 && phi.iter().all(|&(p, _)| mod_pow(g, phi_value / p, n) != 1))
+}
+
+/// The multiplicative order of `a` modulo `n`, the least `k > 0` with `a^k ≡ 1`, or `None` if
+/// `a` is not coprime to `n`. Found by factoring the Carmichael function `λ(n)`, which every
+/// order divides, and stripping primes from it. Panics if `n` is 0.
+pub fn multiplicative_order(a: u64, n: u64) -> Option<u64> {
+    assert!(n != 0, "modulus must be nonzero");
+
+    if gcd(a, n) != 1 {
+        return None;
+    }
+
+    order_dividing(a, n, &carmichael_factorization(n)).value()
+}
+
+/// The least `x >= 0` with `base^x ≡ target (mod modulus)`, or `None` if there is none, by the
+/// Pohlig–Hellman algorithm. The order of `base` is factored with the crate's factorizer, a log
+/// is found in each prime power subgroup by baby-step giant-step over the prime alone, and the
+/// results are combined by the Chinese remainder theorem. The time is governed by the square
+/// root of the largest prime factor of the order, so this is fast whenever the order is smooth.
+/// Panics unless `base` is coprime to `modulus`, or if that prime factor is above 2^48.
+pub fn pohlig_hellman(base: u64, target: u64, modulus: u64) -> Option<u64> {
+    assert!(
+        modulus != 0 && gcd(base, modulus) == 1,
+        "base must be a unit modulo {modulus}"
+    );
+
+    let n = modulus;
+    let order = order_dividing(base, n, &carmichael_factorization(n));
+    let q = order.value().unwrap();
+    let mut congruences = vec![];
+
+    for &(p, e) in order.iter() {
+        let pe = p.pow(e);
+
+        // Project onto the subgroup of order p^e, then find the log one base-p digit at a time
+        // in the subgroup of order p generated by gamma.
+        let g = mod_pow(base, q / pe, n);
+        let h = mod_pow(target, q / pe, n);
+        let g_inv = mod_inv(g, n).unwrap();
+        let gamma = mod_pow(g, pe / p, n);
+        let mut x = 0;
+        let mut radix = 1;
+
+        for _ in 0..e {
+            let reduced = mul_mod(mod_pow(g_inv, x, n), h, n);
+            let digit = mod_pow(reduced, pe / p / radix, n);
+
+            x += baby_step_giant_step(gamma, 1 % n, digit, n, p)? * radix;
+            radix *= p;
+        }
+
+        congruences.push((x, pe));
+    }
+
+    let (x, _) = crt(&congruences)?;
+
+    (mod_pow(base, x, n) == target % n).then_some(x)
+}
+
+/// Every `x` with `x^k ≡ a (mod p)` for the prime `p`, in increasing order.
+///
+/// A nonzero `a` has either no `k`-th roots or exactly `gcd(k, p − 1)` of them. Taking
+/// `k s ≡ g (mod p − 1)` with `g = gcd(k, p − 1)` turns the problem into a `g`-th root, which
+/// is found by inverting `g` on the part of the group of order coprime to `g` and correcting
+/// the rest with a discrete log in the Sylow subgroups for the primes of `g`. As with the
+/// Adleman–Manders–Miller method, that log is the expensive step when `g` has a large prime
+/// factor. Panics if `k` is 0, `p` is not prime, or a prime factor of `g` is above 2^48.
+pub fn kth_root_mod_prime(a: u64, k: u64, p: u64) -> Vec<u64> {
+    assert!(k != 0, "k must be nonzero");
+    assert!(is_prime(p), "{p} is not prime");
+
+    let a = a % p;
+
+    if a == 0 || p == 2 {
+        return vec![a];
+    }
+
+    let n = p - 1;
+    let g = gcd(k, n);
+
+    if mod_pow(a, n / g, p) != 1 {
+        return vec![];
+    }
+
+    let generator = primitive_root(p).unwrap();
+
+    // The largest divisor m of n coprime to g.
+    let mut m = n;
+
+    for &(q, _) in factorization(g).iter() {
+        while m.is_multiple_of(q) {
+            m /= q;
+        }
+    }
+
+    // y1^g is a up to an error in the subgroup of order n / m, generated by h; a / y1^g = h^j
+    // with g | j, and y1 h^(j / g) is a g-th root.
+    let y1 = mod_pow(a, mod_inv(g % m, m).unwrap(), p);
+    let h = mod_pow(generator, m, p);
+    let error = mul_mod(a, mod_inv(mod_pow(y1, g, p), p).unwrap(), p);
+    let j = pohlig_hellman(h, error, p).unwrap();
+    let y = mul_mod(y1, mod_pow(h, j / g, p), p);
+
+    let (_, s, _) = ext_gcd(k % n, n);
+    let x = mod_pow(y, s.rem_euclid(n as i128) as u64, p);
+
+    // The roots differ by the g-th roots of unity.
+    let zeta = mod_pow(generator, n / g, p);
+    let mut roots: Vec<_> = std::iter::successors(Some(x), |&x| Some(mul_mod(x, zeta, p)))
+        .take(g as usize)
+        .collect();
+
+    roots.sort_unstable();
+    roots
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn primitive_root_01() {
+        assert_eq!(primitive_root(1), Some(0));
+        assert_eq!(primitive_root(2), Some(1));
+        assert_eq!(primitive_root(4), Some(3));
+        assert_eq!(primitive_root(7), Some(3));
+        assert_eq!(primitive_root(8), None);
+        assert_eq!(primitive_root(15), None);
+        assert_eq!(primitive_root(2 * 3u64.pow(30)), Some(5));
+
+        // The NTT primes 998244353 = 119 · 2^23 + 1 and the largest u64 prime.
+        assert_eq!(primitive_root(998_244_353), Some(3));
+        assert_eq!(primitive_root(18_446_744_073_709_551_557), Some(2));
+
+        for n in 1..300u64 {
+            let units: Vec<_> = (0..n).filter(|&a| gcd(a, n) == 1).collect();
+            let generates = |g: u64| {
+                let mut seen: Vec<_> = (0..units.len() as u64).map(|k| mod_pow(g, k, n)).collect();
+
+                seen.sort_unstable();
+                seen == units
+            };
+
+            assert_eq!(
+                primitive_root(n),
+                units.iter().copied().find(|&g| generates(g)),
+                "{n}"
+            );
+        }
+    }
+
+    #[test]
+    fn multiplicative_order_01() {
+        assert_eq!(multiplicative_order(0, 1), Some(1));
+        assert_eq!(multiplicative_order(2, 7), Some(3));
+        assert_eq!(multiplicative_order(3, 7), Some(6));
+        assert_eq!(multiplicative_order(2, 4), None);
+        assert_eq!(multiplicative_order(3, 1 << 63), Some(1 << 61));
+        assert_eq!(
+            multiplicative_order(2, 18_446_744_073_709_551_557),
+            Some(18_446_744_073_709_551_556)
+        );
+
+        for n in 1..300u64 {
+            for a in 0..n {
+                let expected =
+                    (gcd(a, n) == 1).then(|| (1..=n).find(|&k| mod_pow(a, k, n) == 1 % n).unwrap());
+
+                assert_eq!(multiplicative_order(a, n), expected, "{a} mod {n}");
+            }
+        }
+    }
+
+    #[test]
+    fn kth_root_mod_prime_01() {
+        assert_eq!(kth_root_mod_prime(0, 3, 7), vec![0]);
+        assert_eq!(kth_root_mod_prime(1, 3, 7), vec![1, 2, 4]);
+        assert!(kth_root_mod_prime(3, 3, 7).is_empty());
+        assert_eq!(kth_root_mod_prime(5, 5, 7), vec![3]);
+        assert_eq!(kth_root_mod_prime(1, 1, 2), vec![1]);
+
+        for p in crate::primes_upto(80) {
+            for k in 1..p + 2 {
+                for a in 0..p {
+                    let expected: Vec<_> = (0..p).filter(|&x| mod_pow(x, k, p) == a).collect();
+
+                    assert_eq!(kth_root_mod_prime(a, k, p), expected, "x^{k} = {a} mod {p}");
+                }
+            }
+        }
+
+        // 998244353 − 1 = 2^23 · 7 · 17, so cube and 2^20-th roots exercise the Sylow subgroups.
+        let p = 998_244_353;
+
+        for (a, k) in [
+            (mod_pow(5, 3, p), 3),
+            (mod_pow(7, 1 << 20, p), 1 << 20),
+            (mod_pow(11, 119, p), 119),
+        ] {
+            let roots = kth_root_mod_prime(a, k, p);
+
+            assert_eq!(roots.len() as u64, gcd(k, p - 1));
+            assert!(roots.iter().all(|&x| mod_pow(x, k, p) == a));
+        }
+    }
+
+    #[test]
+    fn pohlig_hellman_01() {
+        assert_eq!(pohlig_hellman(3, 13, 17), Some(4));
+        assert_eq!(pohlig_hellman(2, 3, 7), None);
+        assert_eq!(pohlig_hellman(5, 1, 1), Some(0));
+
+        for m in 1..80u64 {
+            for b in (0..m).filter(|&b| gcd(b, m) == 1) {
+                for t in 0..m {
+                    assert_eq!(
+                        pohlig_hellman(b, t, m),
+                        discrete_log(b, t, m),
+                        "{b}^x = {t} mod {m}"
+                    );
+                }
+            }
+        }
+
+        // p − 1 = 2 · 3 · 11^2 · 13^3 · 17 · 19^2 · 23^3 · 31 · 37 · 41 is smooth for this prime
+        // near 2^62, which is out of reach of BSGS.
+        let p = 5_600_845_381_483_335_127;
+        let x = 1_234_567_890_123_456_789;
+
+        let y = pohlig_hellman(3, mod_pow(3, x, p), p).unwrap();
+
+        assert_eq!(mod_pow(3, y, p), mod_pow(3, x, p));
+    }
+
+    #[test]
+    fn discrete_log_01() {
+        assert_eq!(discrete_log(2, 1, 1), Some(0));
+        assert_eq!(discrete_log(3, 13, 17), Some(4));
+        assert_eq!(discrete_log(2, 3, 7), None);
+        assert_eq!(discrete_log(2, 0, 8), Some(3));
+        assert_eq!(discrete_log(0, 0, 5), Some(1));
+
+        for m in 1..60u64 {
+            for b in 0..m {
+                for t in 0..m {
+                    let expected = (0..2 * m).find(|&x| mod_pow(b, x, m) == t);
+
+                    assert_eq!(discrete_log(b, t, m), expected, "{b}^x = {t} mod {m}");
+                }
+            }
+        }
+
+        // Modulo the prime 2^32 − 5, with 2^16 baby steps.
+        let p = 4_294_967_291;
+        let x = 3_141_592_653;
+
+        assert_eq!(
+            discrete_log(2, mod_pow(2, x, p), p).map(|y| mod_pow(2, y, p)),
+            Some(mod_pow(2, x, p))
+        );
+        assert_eq!(
+            discrete_log(17, mod_pow(17, 1_000_000, p), p),
+            Some(1_000_000)
+        );
+
+        // Past 2^48 the table would be too large, but 2^61 − 2 is smooth enough for
+        // Pohlig–Hellman, also after dividing out a factor 2 shared with the base.
+        let p = (1 << 61) - 1;
+        let x = 1_234_567_890_123_456_789;
+
+        assert_eq!(
+            discrete_log(37, mod_pow(37, x, p), p).map(|y| mod_pow(37, y, p)),
+            Some(mod_pow(37, x, p))
+        );
+        assert_eq!(
+            discrete_log(74, mod_pow(74, x, 2 * p), 2 * p).map(|y| mod_pow(74, y, 2 * p)),
+            Some(mod_pow(74, x, 2 * p))
+        );
+    }
+}
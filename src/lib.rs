@@ -1,6 +1,55 @@
 use std::cell::RefCell;
+use std::collections::BTreeMap;
 use std::iter::FusedIterator;
 
+mod analytic;
+#[cfg(feature = "bigint")]
+mod certificate;
+mod counting;
+mod digits;
+mod eisenstein;
+mod factor;
+mod factorization;
+#[cfg(feature = "fips")]
+mod fips;
+mod gaussian;
+mod group;
+mod lucas;
+mod modint;
+mod modular;
+mod montgomery;
+mod multiplicative;
+mod primality;
+mod primorial;
+#[cfg(feature = "rand")]
+mod random;
+mod sieve;
+mod special;
+mod spf;
+
+pub use analytic::*;
+#[cfg(feature = "bigint")]
+pub use certificate::*;
+pub use counting::*;
+pub use digits::*;
+pub use eisenstein::*;
+pub use factor::*;
+pub use factorization::*;
+#[cfg(feature = "fips")]
+pub use fips::*;
+pub use gaussian::*;
+pub use group::*;
+pub use lucas::*;
+pub use modint::*;
+pub use modular::*;
+pub use montgomery::*;
+pub use multiplicative::*;
+pub use primorial::*;
+#[cfg(feature = "rand")]
+pub use random::*;
+pub use special::*;
+pub use spf::*;
+
 const WHEEL_PRIMES: [u64; 3] = [2, 3, 5];
 const WHEEL_MODULUS: u64 = 30;
 const WHEEL: [u64; 8] = [1, 7, 11, 13, 17, 19, 23, 29];
@@ -49,7 +98,11 @@ impl GlobalPrimes {
                 self.wheel_base += WHEEL_MODULUS;
             }
 
-            if self.primes.iter().all(|&prime| candidate % prime != 0) {
+            if self
+                .primes
+                .iter()
+                .all(|&prime| !candidate.is_multiple_of(prime))
+            {
                 self.primes.push(candidate);
 
                 if candidate >= max {
@@ -59,7 +112,29 @@ impl GlobalPrimes {
         }
     }
 
+    pub fn sieve_upto(&mut self, limit: u64) {
+        if limit <= self.last_prime() {
+            return;
+        }
+
+        self.primes = sieve::sieve_primes(limit);
+
+        // Resume the wheel at the first candidate past the sieved range.
+        self.wheel_base = limit - limit % WHEEL_MODULUS;
+        match WHEEL.iter().position(|&w| self.wheel_base + w > limit) {
+            Some(index) => self.wheel_index = index,
+            None => {
+                self.wheel_index = 0;
+                self.wheel_base += WHEEL_MODULUS;
+            }
+        }
+    }
+
     pub fn generate_count(&mut self, count: usize) {
+        if count > self.primes.len() {
+            self.sieve_upto(nth_prime_bounds(count - 1).1);
+        }
+
         while self.primes.len() < count {
             let candidate = self.wheel_base + WHEEL[self.wheel_index];
 
@@ -69,7 +144,11 @@ impl GlobalPrimes {
                 self.wheel_base += WHEEL_MODULUS;
             }
 
-            if self.primes.iter().all(|&prime| candidate % prime != 0) {
+            if self
+                .primes
+                .iter()
+                .all(|&prime| !candidate.is_multiple_of(prime))
+            {
                 self.primes.push(candidate);
             }
         }
@@ -90,6 +169,12 @@ impl Primes {
     }
 }
 
+impl Default for Primes {
+    fn default() -> Primes {
+        Primes::new()
+    }
+}
+
 impl Iterator for Primes {
     type Item = u64;
 
@@ -129,36 +214,60 @@ pub fn nth_prime(k: usize) -> u64 {
     })
 }
 
-pub fn factorize(n: u64, factors: &mut Vec<u64>) {
-    let mut k = n;
+/// The factorization of `n` as `(prime, exponent)` pairs. The factorization of 1 is empty.
+/// Panics if `n` is 0.
+pub fn factorization(n: u64) -> Factorization {
+    factorization_with(n, &FactorConfig::default())
+}
 
-    factors.clear();
+/// Replaces the contents of `factorization` with the factorization of `n`, reusing its storage
+/// across calls. Panics if `n` is 0.
+pub fn factorize_into(n: u64, factorization: &mut Factorization) {
+    factorize_into_with(n, &FactorConfig::default(), factorization);
+}
 
-    for p in primes() {
-        while k % p == 0 {
-            factors.push(p);
-            k /= p;
-        }
+/// The factorization of `n`, or `None` if `n` is 0.
+pub fn try_factorization(n: u64) -> Option<Factorization> {
+    try_factorization_with(n, &FactorConfig::default())
+}
 
-        if k == 1 {
-            break;
-        }
-    }
+/// The factorization of `n` as a map from each prime factor to its exponent.
+pub fn factorize_map(n: u64) -> BTreeMap<u64, u32> {
+    factorization(n).into_iter().collect()
+}
 
-    if factors.is_empty() {
-        factors.push(n);
-    }
+/// The factorization of a signed `n` as the unit `1` or `-1` and the factorization of `|n|`.
+/// `i64::MIN` is `-1 · 2^63`. Panics if `n` is 0.
+pub fn factorize_i64(n: i64) -> (i64, Factorization) {
+    let unit = if n < 0 { -1 } else { 1 };
+
+    (unit, factorization(n.unsigned_abs()))
 }
 
+/// Fills `factors` with the prime factors of `n` in increasing order, repeated by multiplicity,
+/// leaving it empty for 1. Panics if `n` is 0.
+pub fn factorize(n: u64, factors: &mut Vec<u64>) {
+    factorize_with(n, &FactorConfig::default(), factors);
+}
+
+/// Whether `n` is prime. 0 and 1 are not.
 pub fn is_prime(n: u64) -> bool {
     GLOBAL_PRIMES.with(|global_primes| {
-        let mut global_primes = global_primes.borrow_mut();
+        let global_primes = global_primes.borrow();
 
-        global_primes.generate_upto(n);
-        global_primes.primes.binary_search(&n).is_ok()
+        if n <= global_primes.last_prime() {
+            global_primes.primes.binary_search(&n).is_ok()
+        } else {
+            primality::miller_rabin(n)
+        }
     })
 }
 
+/// Whether `n` is prime, by the Baillie–PSW test above 2^64. No composite is known to pass it.
+pub fn is_prime_u128(n: u128) -> bool {
+    primality::baillie_psw(n)
+}
+
 pub fn clear_prime_cache() {
     GLOBAL_PRIMES.with(|global_primes| {
         global_primes.borrow_mut().reset();
@@ -173,7 +282,7 @@ mod tests {
         let mut ps = vec![];
 
         for candidate in 2..=max {
-            if ps.iter().all(|&prime| candidate % prime != 0) {
+            if ps.iter().all(|&prime| !candidate.is_multiple_of(prime)) {
                 ps.push(candidate);
             }
         }
@@ -227,6 +336,33 @@ mod tests {
         assert_eq!(a, ps);
     }
 
+    #[test]
+    fn nth_prime_01() {
+        let ps = dumb_prime_generator(2000);
+
+        clear_prime_cache();
+
+        for (k, &p) in ps.iter().enumerate().step_by(37) {
+            assert_eq!(nth_prime(k), p);
+        }
+
+        let a: Vec<_> = primes_upto(2000).collect();
+
+        assert_eq!(a, ps);
+    }
+
+    #[test]
+    fn is_prime_01() {
+        let ps = dumb_prime_generator(1000);
+
+        for n in 0..1000 {
+            assert_eq!(is_prime(n), ps.binary_search(&n).is_ok());
+        }
+
+        assert!(is_prime(999_999_999_989));
+        assert!(!is_prime(999_999_999_991));
+    }
+
     #[test]
     fn factorize_01() {
         let mut fs = vec![];
@@ -248,4 +384,82 @@ mod tests {
 
         assert_eq!(fs, expected);
     }
+
+    #[test]
+    fn factorize_03() {
+        let mut fs = vec![];
+
+        factorize(4_294_967_291 * 4_294_967_279, &mut fs);
+        assert_eq!(fs, vec![4_294_967_279, 4_294_967_291]);
+
+        factorize(1_000_000_007 * 1021 * 1021, &mut fs);
+        assert_eq!(fs, vec![1021, 1021, 1_000_000_007]);
+
+        factorize(18_446_744_073_709_551_557, &mut fs);
+        assert_eq!(fs, vec![18_446_744_073_709_551_557]);
+
+        factorize(1, &mut fs);
+        assert!(fs.is_empty());
+    }
+
+    #[test]
+    fn factorization_01() {
+        let f = factorization(2 * 2 * 2 * 3 * 1021 * 1021 * 1_000_003);
+
+        assert_eq!(f.as_slice(), &[(2, 3), (3, 1), (1021, 2), (1_000_003, 1)]);
+        assert_eq!(f.value(), Some(2 * 2 * 2 * 3 * 1021 * 1021 * 1_000_003));
+        assert_eq!(f.num_divisors(), Some(4 * 2 * 3 * 2));
+        assert!(factorization(1).is_empty());
+        assert_eq!(try_factorization(1), Some(Factorization::new()));
+        assert_eq!(try_factorization(0), None);
+
+        let mut f = factorization(1_000_003 * 1_000_003);
+
+        factorize_into(360, &mut f);
+        assert_eq!(f, factorization(360));
+
+        factorize_into(1, &mut f);
+        assert!(f.is_empty());
+    }
+
+    #[test]
+    fn factorize_i64_01() {
+        let (unit, f) = factorize_i64(-360);
+
+        assert_eq!(unit, -1);
+        assert_eq!(f, factorization(360));
+
+        let (unit, f) = factorize_i64(i64::MIN);
+
+        assert_eq!(unit, -1);
+        assert_eq!(f.as_slice(), &[(2, 63)]);
+
+        let (unit, f) = factorize_i64(i64::MAX);
+
+        assert_eq!(unit, 1);
+        assert_eq!(
+            f.as_slice(),
+            &[
+                (7, 2),
+                (73, 1),
+                (127, 1),
+                (337, 1),
+                (92_737, 1),
+                (649_657, 1)
+            ]
+        );
+
+        assert_eq!(factorize_i64(-1), (-1, Factorization::new()));
+        assert_eq!(factorize_i64(1), (1, Factorization::new()));
+    }
+
+    #[test]
+    fn factorize_map_01() {
+        let a = factorize_map(360);
+        let b = factorize_map(1_000_003 * 27);
+
+        assert_eq!(a, BTreeMap::from([(2, 3), (3, 2), (5, 1)]));
+        assert_eq!(b, BTreeMap::from([(3, 3), (1_000_003, 1)]));
+        assert!(factorize_map(1).is_empty());
+    }
 }
@@ -0,0 +1,255 @@
+use crate::modular::{add_mod, mul_mod, sub_mod};
+use crate::{factorization, Factorization};
+
+/// The Lucas sequences `(U_n mod m, V_n mod m)` for the parameters `P` and `Q`, defined by
+/// `U_0 = 0`, `U_1 = 1`, `V_0 = 2`, `V_1 = P` and `X_{k+1} = P X_k − Q X_{k−1}`. Computed by
+/// fast doubling in `O(log n)` steps, with no division, so `m` need not be odd or coprime to
+/// the discriminant. Panics if `m` is 0.
+pub fn lucas_uv_mod(p: i64, q: i64, n: u64, m: u64) -> (u64, u64) {
+    assert!(m != 0, "modulus must be nonzero");
+
+    let p = reduce_signed(p, m);
+    let q = reduce_signed(q, m);
+
+    // (U_k, U_{k+1}) from the top bit of n down, by U_{2k} = U_k (2 U_{k+1} − P U_k) and
+    // U_{2k+1} = U_{k+1}^2 − Q U_k^2.
+    let mut u = 0;
+    let mut u_next = 1 % m;
+
+    for bit in (0..64 - n.leading_zeros()).rev() {
+        let v = sub_mod(mul_mod(2, u_next, m), mul_mod(p, u, m), m);
+        let u_2k = mul_mod(u, v, m);
+        let u_2k1 = sub_mod(
+            mul_mod(u_next, u_next, m),
+            mul_mod(q, mul_mod(u, u, m), m),
+            m,
+        );
+
+        (u, u_next) = if n >> bit & 1 == 1 {
+            (u_2k1, sub_mod(mul_mod(p, u_2k1, m), mul_mod(q, u_2k, m), m))
+        } else {
+            (u_2k, u_2k1)
+        };
+    }
+
+    // V_n = 2 U_{n+1} − P U_n.
+    let v = sub_mod(mul_mod(2, u_next, m), mul_mod(p, u, m), m);
+
+    (u, v)
+}
+
+/// The Fibonacci number `F_n mod m`, by fast doubling. Panics if `m` is 0.
+pub fn fibonacci_mod(n: u64, m: u64) -> u64 {
+    lucas_uv_mod(1, -1, n, m).0
+}
+
+/// The Lucas number `L_n mod m`, with `L_0 = 2` and `L_1 = 1`, by fast doubling. Panics if `m`
+/// is 0.
+pub fn lucas_number_mod(n: u64, m: u64) -> u64 {
+    lucas_uv_mod(1, -1, n, m).1
+}
+
+/// The Pisano period `π(m)`, the period of the Fibonacci numbers modulo `m`, or `None` if it
+/// overflows a `u64`. It is the lcm of the periods modulo each prime power `p^e` dividing `m`,
+/// each found by removing primes from the multiple `p^(e − 1) π(p)` while it remains a period,
+/// where `π(p)` divides `p − 1` for `p ≡ ±1 (mod 5)` and `2 (p + 1)` for `p ≡ ±2 (mod 5)`.
+/// Panics if `m` is 0.
+pub fn pisano_period(m: u64) -> Option<u64> {
+    assert!(m != 0, "modulus must be nonzero");
+
+    factorization(m)
+        .iter()
+        .fold(Factorization::new(), |period, &(p, e)| {
+            period.lcm(&pisano_period_prime_power(p, e))
+        })
+        .value()
+}
+
+// The Pisano period modulo p^e in factored form.
+fn pisano_period_prime_power(p: u64, e: u32) -> Factorization {
+    let mut multiple = match p {
+        2 => Factorization::from_iter([(3, 1)]),
+        5 => Factorization::from_iter([(2, 2), (5, 1)]),
+        _ if matches!(p % 5, 1 | 4) => factorization(p - 1),
+        _ => {
+            let mut multiple = factorization(p + 1);
+
+            multiple.insert(2, 1);
+            multiple
+        }
+    };
+
+    multiple.insert(p, e - 1);
+
+    let m = p.pow(e);
+    let mut period = multiple.clone();
+
+    for &(q, k) in multiple.iter() {
+        for _ in 0..k {
+            let smaller = period
+                .checked_div(&Factorization::from_iter([(q, 1)]))
+                .unwrap();
+
+            if fibonacci_pair_pow(&smaller, m) != (0, 1) {
+                break;
+            }
+
+            period = smaller;
+        }
+    }
+
+    period
+}
+
+// (F_k mod m, F_{k+1} mod m) for k given in factored form, which may not fit in a u64, by
+// raising the pair for 1 to each prime in turn.
+fn fibonacci_pair_pow(k: &Factorization, m: u64) -> (u64, u64) {
+    // The pair for a + b from the pairs for a and b, by F_{a+b} = F_a F_{b+1} + F_{a−1} F_b.
+    let mul = |(a0, a1): (u64, u64), (b0, b1): (u64, u64)| {
+        (
+            sub_mod(
+                add_mod(mul_mod(a0, b1, m), mul_mod(a1, b0, m), m),
+                mul_mod(a0, b0, m),
+                m,
+            ),
+            add_mod(mul_mod(a1, b1, m), mul_mod(a0, b0, m), m),
+        )
+    };
+
+    let mut pair = (1 % m, 1 % m);
+
+    for &(q, e) in k.iter() {
+        for _ in 0..e {
+            let mut result = (0, 1 % m);
+            let mut base = pair;
+            let mut exp = q;
+
+            while exp > 0 {
+                if exp & 1 == 1 {
+                    result = mul(result, base);
+                }
+
+                base = mul(base, base);
+                exp >>= 1;
+            }
+
+            pair = result;
+        }
+    }
+
+    pair
+}
+
+// a mod m for signed a.
+fn reduce_signed(a: i64, m: u64) -> u64 {
+    if a >= 0 {
+        a as u64 % m
+    } else {
+        sub_mod(0, a.unsigned_abs() % m, m)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lucas_uv_mod_01() {
+        // The recurrence directly, over the integers, against a range of moduli.
+        for (p, q) in [(1i64, -1i64), (3, 2), (2, 1), (-4, 7), (5, -3), (0, 1)] {
+            let (mut u, mut v) = (vec![0i128, 1], vec![2i128, p as i128]);
+
+            for k in 2..40 {
+                u.push(p as i128 * u[k - 1] - q as i128 * u[k - 2]);
+                v.push(p as i128 * v[k - 1] - q as i128 * v[k - 2]);
+            }
+
+            for m in [1u64, 2, 7, 12, 1_000_000_007, u64::MAX] {
+                for n in 0..40 {
+                    assert_eq!(
+                        lucas_uv_mod(p, q, n as u64, m),
+                        (
+                            u[n].rem_euclid(m as i128) as u64,
+                            v[n].rem_euclid(m as i128) as u64
+                        ),
+                        "P = {p}, Q = {q}, n = {n}, m = {m}"
+                    );
+                }
+            }
+        }
+
+        // U_{p − (D | p)} ≡ 0 mod p for primes p not dividing 2QD; here D = 5 for the Fibonacci
+        // numbers, and 1_000_000_007 ≡ 2 mod 5, so (5 | p) = −1.
+        let p = 1_000_000_007;
+
+        assert_eq!(lucas_uv_mod(1, -1, p + 1, p).0, 0);
+        assert_eq!(lucas_uv_mod(1, -1, p, p).1, 1);
+    }
+
+    #[test]
+    fn fibonacci_mod_01() {
+        let (mut f, mut l) = (vec![0u128, 1], vec![2u128, 1]);
+
+        for k in 2..150 {
+            f.push(f[k - 1] + f[k - 2]);
+            l.push(l[k - 1] + l[k - 2]);
+        }
+
+        for m in [1u64, 2, 10, 1_000_000_007, u64::MAX] {
+            for n in 0..150 {
+                assert_eq!(fibonacci_mod(n as u64, m) as u128, f[n] % m as u128);
+                assert_eq!(lucas_number_mod(n as u64, m) as u128, l[n] % m as u128);
+            }
+        }
+
+        // F_{10^18} mod 10^9 + 7, and F_n L_n = F_{2n}.
+        assert_eq!(
+            fibonacci_mod(1_000_000_000_000_000_000, 1_000_000_007),
+            209_783_453
+        );
+
+        for n in [12_345, 1 << 40, u64::MAX / 2] {
+            let m = 998_244_353;
+
+            assert_eq!(
+                mul_mod(fibonacci_mod(n, m), lucas_number_mod(n, m), m),
+                fibonacci_mod(2 * n, m)
+            );
+        }
+    }
+
+    #[test]
+    fn pisano_period_01() {
+        // Against the first return of (F_k, F_{k+1}) to (0, 1).
+        for m in 1..500u64 {
+            let (mut a, mut b, mut k) = (0, 1 % m, 0);
+
+            loop {
+                (a, b) = (b, (a + b) % m);
+                k += 1;
+
+                if (a, b) == (0, 1 % m) {
+                    break;
+                }
+            }
+
+            assert_eq!(pisano_period(m), Some(k), "{m}");
+        }
+
+        assert_eq!(pisano_period(1_000_000), Some(1_500_000));
+        assert_eq!(pisano_period(1 << 63), Some(3 << 62));
+
+        // 1_000_000_007 ≡ 2 mod 5, so its period divides 2 (p + 1).
+        let p = 1_000_000_007;
+        let period = pisano_period(p).unwrap();
+
+        assert_eq!((2 * (p + 1)) % period, 0);
+        assert_eq!(
+            (fibonacci_mod(period, p), fibonacci_mod(period + 1, p)),
+            (0, 1)
+        );
+
+        // π(5^27) = 4 · 5^27 overflows.
+        assert_eq!(pisano_period(5u64.pow(27)), None);
+    }
+}
@@ -0,0 +1,217 @@
+use std::fmt;
+use std::iter::{Product, Sum};
+use std::ops::{Add, AddAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+
+use crate::modular::{add_mod, mod_inv, sub_mod};
+use crate::{Barrett, Montgomery};
+
+/// An integer modulo the constant `M`, so arithmetic reads `a * b + c` rather than a chain of
+/// `mul_mod` and `add_mod` calls. Values are kept in [`Montgomery`] form for odd `M` and reduced
+/// by [`Barrett`] reduction for even `M`, whose contexts are computed at compile time. Using a
+/// modulus of 0 fails to compile.
+#[derive(Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct ModInt<const M: u64> {
+    // In Montgomery form when M is odd, and the plain residue otherwise.
+    value: u64,
+}
+
+// How a ModInt with a given modulus multiplies.
+enum Reducer {
+    Montgomery(Montgomery),
+    Barrett(Barrett),
+}
+
+impl<const M: u64> ModInt<M> {
+    const REDUCER: Reducer = if M % 2 == 1 {
+        Reducer::Montgomery(Montgomery::new(M))
+    } else {
+        Reducer::Barrett(Barrett::new(M))
+    };
+
+    /// `a mod M`.
+    pub fn new(a: u64) -> ModInt<M> {
+        let value = match Self::REDUCER {
+            Reducer::Montgomery(mont) => mont.to_montgomery(a),
+            Reducer::Barrett(_) => a % M,
+        };
+
+        ModInt { value }
+    }
+
+    /// The residue in `0..M`.
+    pub fn value(self) -> u64 {
+        match Self::REDUCER {
+            Reducer::Montgomery(mont) => mont.from_montgomery(self.value),
+            Reducer::Barrett(_) => self.value,
+        }
+    }
+
+    pub const fn modulus() -> u64 {
+        M
+    }
+
+    pub fn pow(self, exp: u64) -> ModInt<M> {
+        let value = match Self::REDUCER {
+            Reducer::Montgomery(mont) => mont.pow(self.value, exp),
+            Reducer::Barrett(barrett) => barrett.pow(self.value, exp),
+        };
+
+        ModInt { value }
+    }
+
+    /// The multiplicative inverse, or `None` if the value is not coprime to `M`.
+    pub fn inv(self) -> Option<ModInt<M>> {
+        mod_inv(self.value(), M).map(ModInt::new)
+    }
+}
+
+impl<const M: u64> From<u64> for ModInt<M> {
+    fn from(a: u64) -> ModInt<M> {
+        ModInt::new(a)
+    }
+}
+
+impl<const M: u64> Add for ModInt<M> {
+    type Output = ModInt<M>;
+
+    fn add(self, other: ModInt<M>) -> ModInt<M> {
+        // Montgomery form is linear, so both representations add as plain residues.
+        ModInt {
+            value: add_mod(self.value, other.value, M),
+        }
+    }
+}
+
+impl<const M: u64> Sub for ModInt<M> {
+    type Output = ModInt<M>;
+
+    fn sub(self, other: ModInt<M>) -> ModInt<M> {
+        ModInt {
+            value: sub_mod(self.value, other.value, M),
+        }
+    }
+}
+
+impl<const M: u64> Mul for ModInt<M> {
+    type Output = ModInt<M>;
+
+    fn mul(self, other: ModInt<M>) -> ModInt<M> {
+        let value = match Self::REDUCER {
+            Reducer::Montgomery(mont) => mont.mul(self.value, other.value),
+            Reducer::Barrett(barrett) => barrett.mul(self.value, other.value),
+        };
+
+        ModInt { value }
+    }
+}
+
+impl<const M: u64> Neg for ModInt<M> {
+    type Output = ModInt<M>;
+
+    fn neg(self) -> ModInt<M> {
+        ModInt::default() - self
+    }
+}
+
+impl<const M: u64> AddAssign for ModInt<M> {
+    fn add_assign(&mut self, other: ModInt<M>) {
+        *self = *self + other;
+    }
+}
+
+impl<const M: u64> SubAssign for ModInt<M> {
+    fn sub_assign(&mut self, other: ModInt<M>) {
+        *self = *self - other;
+    }
+}
+
+impl<const M: u64> MulAssign for ModInt<M> {
+    fn mul_assign(&mut self, other: ModInt<M>) {
+        *self = *self * other;
+    }
+}
+
+impl<const M: u64> Sum for ModInt<M> {
+    fn sum<I: Iterator<Item = ModInt<M>>>(iter: I) -> ModInt<M> {
+        iter.fold(ModInt::default(), Add::add)
+    }
+}
+
+impl<const M: u64> Product for ModInt<M> {
+    fn product<I: Iterator<Item = ModInt<M>>>(iter: I) -> ModInt<M> {
+        iter.fold(ModInt::new(1), Mul::mul)
+    }
+}
+
+impl<const M: u64> fmt::Debug for ModInt<M> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} (mod {M})", self.value())
+    }
+}
+
+impl<const M: u64> fmt::Display for ModInt<M> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.value())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::modular::{mod_pow, mul_mod};
+
+    fn check<const M: u64>() {
+        let samples = [0, 1, 2, M / 2, M - 1, 12_345_678_901, u64::MAX];
+
+        for &a in &samples {
+            let x = ModInt::<M>::new(a);
+
+            assert_eq!(x.value(), a % M);
+            assert_eq!((-x).value(), sub_mod(0, a, M));
+            assert_eq!(x.pow(1_000_003).value(), mod_pow(a, 1_000_003, M));
+
+            match x.inv() {
+                Some(inverse) => assert_eq!((x * inverse).value(), 1 % M),
+                None => assert!(mod_inv(a, M).is_none()),
+            }
+
+            for &b in &samples {
+                let y = ModInt::<M>::from(b);
+
+                assert_eq!((x + y).value(), add_mod(a, b, M));
+                assert_eq!((x - y).value(), sub_mod(a, b, M));
+                assert_eq!((x * y).value(), mul_mod(a, b, M));
+
+                let mut z = x;
+
+                z *= y;
+                z += y;
+                z -= x;
+
+                assert_eq!(z.value(), sub_mod(add_mod(mul_mod(a, b, M), b, M), a, M));
+            }
+        }
+    }
+
+    #[test]
+    fn modint_01() {
+        check::<1>();
+        check::<2>();
+        check::<10>();
+        check::<998_244_353>();
+        check::<{ 1 << 63 }>();
+        check::<18_446_744_073_709_551_557>();
+        check::<{ u64::MAX }>();
+
+        type F = ModInt<1_000_000_007>;
+
+        // Σ k and 20! by the iterator impls, and Fermat's little theorem.
+        assert_eq!((1..=100).map(F::new).sum::<F>(), F::new(5050));
+        assert_eq!((1..=20).map(F::new).product::<F>().value(), 146_326_063);
+        assert_eq!(F::new(3).pow(F::modulus() - 1), F::new(1));
+        assert_eq!(
+            format!("{} {:?}", F::new(7), F::new(7)),
+            "7 7 (mod 1000000007)"
+        );
+    }
+}
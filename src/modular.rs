@@ -0,0 +1,1160 @@
+use std::hint::black_box;
+use std::iter::FusedIterator;
+
+use crate::Montgomery;
+
+#[cfg(feature = "bigint")]
+mod big;
+
+#[cfg(feature = "bigint")]
+pub use big::*;
+
+/// `a b mod m`, through a 128-bit product so it never overflows. Panics if `m` is 0.
+pub fn mul_mod(a: u64, b: u64, m: u64) -> u64 {
+    ((a as u128 * b as u128) % m as u128) as u64
+}
+
+/// `base^exp mod modulus`, by square-and-multiply with 128-bit products, so it is exact for
+/// every `u64` modulus. `0^0` is 1. Panics if `modulus` is 0.
+pub fn mod_pow(base: u64, exp: u64, modulus: u64) -> u64 {
+    assert!(modulus != 0, "modulus must be nonzero");
+
+    if modulus == 1 {
+        return 0;
+    }
+
+    let mut result = 1;
+    let mut base = base % modulus;
+    let mut exp = exp;
+
+    while exp > 0 {
+        if exp & 1 == 1 {
+            result = mul_mod(result, base, modulus);
+        }
+
+        base = mul_mod(base, base, modulus);
+        exp >>= 1;
+    }
+
+    result
+}
+
+/// `base^exp mod modulus` like [`mod_pow`], for secret exponents: a fixed 4-bit window ladder
+/// over all 64 bits of `exp`. The same squarings and multiplications run in the same order for
+/// every exponent, and each window reads the whole table and picks its entry with a mask rather
+/// than an index. Odd moduli use [`Montgomery`] multiplication, which needs no division; even
+/// moduli fall back to [`mul_mod`], whose hardware division may take time that depends on its
+/// operands. This is a best effort: Rust makes no promise about the timing of the code it emits,
+/// so the mask and selection are passed through [`black_box`] to keep
+/// the optimizer from turning them into a branch, but nothing stops it from compiling the
+/// conditional subtraction in Montgomery reduction to one. Slower than `mod_pow`, especially for
+/// short exponents. Panics if `modulus` is 0.
+pub fn mod_pow_ct(base: u64, exp: u64, modulus: u64) -> u64 {
+    assert!(modulus != 0, "modulus must be nonzero");
+
+    if !modulus.is_multiple_of(2) {
+        let mont = Montgomery::new(modulus);
+        let result = window_ladder(mont.to_montgomery(base), exp, mont.one(), |a, b| {
+            mont.mul(a, b)
+        });
+
+        mont.from_montgomery(result)
+    } else {
+        window_ladder(base % modulus, exp, 1 % modulus, |a, b| {
+            mul_mod(a, b, modulus)
+        })
+    }
+}
+
+// The fixed window ladder behind mod_pow_ct, with one the identity and mul the product in
+// whichever representation base is in.
+fn window_ladder(base: u64, exp: u64, one: u64, mul: impl Fn(u64, u64) -> u64) -> u64 {
+    let mut table = [one; 16];
+
+    for i in 1..16 {
+        table[i] = mul(table[i - 1], base);
+    }
+
+    let mut result = one;
+
+    for window in (0..16).rev() {
+        for _ in 0..4 {
+            result = mul(result, result);
+        }
+
+        let digit = exp >> (4 * window) & 15;
+        let mut entry = 0;
+
+        for (i, &power) in table.iter().enumerate() {
+            // All ones when i == digit, else zero, without a branch.
+            let diff = i as u64 ^ digit;
+            let mask = black_box(((diff | diff.wrapping_neg()) >> 63).wrapping_sub(1));
+
+            entry = black_box(entry | power & mask);
+        }
+
+        result = mul(result, entry);
+    }
+
+    result
+}
+
+/// The inverse of `a` modulo `m` by the extended Euclidean algorithm, or `None` if
+/// `gcd(a, m) != 1`. Every inverse modulo 1 is 0. Panics if `m` is 0.
+pub fn mod_inv(a: u64, m: u64) -> Option<u64> {
+    assert!(m != 0, "modulus must be nonzero");
+
+    let (g, x, _) = ext_gcd(a % m, m);
+
+    (g == 1).then(|| x.rem_euclid(m as i128) as u64)
+}
+
+/// The extended Euclidean algorithm: `(g, x, y)` with `g = gcd(a, b) = a x + b y`. The
+/// coefficients are the minimal ones the algorithm produces, with `|x| <= max(b / 2g, 1)` and
+/// `|y| <= max(a / 2g, 1)`, and are widened to `i128` so they never overflow.
+pub fn ext_gcd(a: u64, b: u64) -> (u64, i128, i128) {
+    let (mut r0, mut r1) = (a, b);
+    let (mut x0, mut x1) = (1i128, 0i128);
+    let (mut y0, mut y1) = (0i128, 1i128);
+
+    while r1 != 0 {
+        let q = r0 / r1;
+
+        (r0, r1) = (r1, r0 - q * r1);
+        (x0, x1) = (x1, x0 - q as i128 * x1);
+        (y0, y1) = (y1, y0 - q as i128 * y1);
+    }
+
+    (r0, x0, y0)
+}
+
+/// The greatest common divisor, with `gcd(0, 0) = 0`.
+pub fn gcd(a: u64, b: u64) -> u64 {
+    let mut a = a;
+    let mut b = b;
+
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+
+    a
+}
+
+/// The least common multiple, or `None` if it overflows a `u64`. `lcm(0, b) = 0`.
+pub fn lcm(a: u64, b: u64) -> Option<u64> {
+    if a == 0 || b == 0 {
+        return Some(0);
+    }
+
+    (a / gcd(a, b)).checked_mul(b)
+}
+
+/// The gcd of every element of `values`, which is 0 for an empty slice.
+pub fn gcd_all(values: &[u64]) -> u64 {
+    values.iter().fold(0, |g, &v| gcd(g, v))
+}
+
+/// The lcm of every element of `values`, which is 1 for an empty slice and 0 whenever one of
+/// them is 0, or otherwise `None` if it overflows a `u64`.
+pub fn lcm_all(values: &[u64]) -> Option<u64> {
+    if values.contains(&0) {
+        return Some(0);
+    }
+
+    values.iter().try_fold(1, |l, &v| lcm(l, v))
+}
+
+/// Solves the system `x ≡ r (mod m)` for every `(r, m)` in `congruences`, returning
+/// `(x, l)` with `l` the lcm of the moduli and `0 <= x < l`. The moduli need not be coprime.
+/// Returns `None` if the congruences are inconsistent or `l` overflows a `u64`. The empty
+/// system gives `(0, 1)`. Panics if a modulus is 0.
+pub fn crt(congruences: &[(u64, u64)]) -> Option<(u64, u64)> {
+    congruences.iter().try_fold((0, 1), |(r1, m1), &(r2, m2)| {
+        assert!(m2 != 0, "modulus must be nonzero");
+
+        // x = r1 + m1 k, with m1 k ≡ r2 − r1 (mod m2), which needs g | r2 − r1.
+        let r2 = r2 % m2;
+        let (g, inverse, _) = ext_gcd(m1, m2);
+        let diff = r2 as i128 - r1 as i128;
+
+        if diff % g as i128 != 0 {
+            return None;
+        }
+
+        let step = m2 / g;
+        let l = m1.checked_mul(step)?;
+        let k = (diff / g as i128).rem_euclid(step as i128) as u128
+            * inverse.rem_euclid(step as i128) as u128
+            % step as u128;
+
+        Some(((r1 as u128 + m1 as u128 * k) as u64, l))
+    })
+}
+
+/// Every solution `x` of `a x ≡ b (mod m)` with `0 <= x < m`, in increasing order, or `None` if
+/// there is none. With `g = gcd(a, m)`, a solution exists exactly when `g | b`, and then the `g`
+/// solutions are spaced `m / g` apart. Panics if `m` is 0.
+pub fn solve_linear_congruence(
+    a: u64,
+    b: u64,
+    m: u64,
+) -> Option<impl DoubleEndedIterator<Item = u64> + FusedIterator> {
+    assert!(m != 0, "modulus must be nonzero");
+
+    let (a, b) = (a % m, b % m);
+    let g = gcd(a, m);
+
+    if !b.is_multiple_of(g) {
+        return None;
+    }
+
+    let step = m / g;
+    let x = mul_mod(b / g, mod_inv(a / g, step)?, step);
+
+    Some((0..g).map(move |k| x + k * step))
+}
+
+/// The mixed-radix digits `v` of the solution of `x ≡ r (mod m)` for every `(r, m)` in
+/// `congruences`, by Garner's algorithm: `x = v[0] + v[1] m[0] + v[2] m[0] m[1] + ...` with
+/// `0 <= v[i] < m[i]`. Unlike [`crt`], the product of the moduli may be arbitrarily large, but
+/// they must be pairwise coprime; returns `None` if they are not. Panics if a modulus is 0.
+pub fn garner_digits(congruences: &[(u64, u64)]) -> Option<Vec<u64>> {
+    let mut digits: Vec<u64> = Vec::with_capacity(congruences.len());
+
+    for (i, &(r, m)) in congruences.iter().enumerate() {
+        assert!(m != 0, "modulus must be nonzero");
+
+        let mut t = r % m;
+
+        for (&v, &(_, mj)) in digits.iter().zip(&congruences[..i]) {
+            let inverse = mod_inv(mj % m, m)?;
+
+            t = mul_mod(sub_mod(t, v % m, m), inverse, m);
+        }
+
+        digits.push(t);
+    }
+
+    Some(digits)
+}
+
+/// The solution of the system of congruences with pairwise coprime moduli, reduced modulo
+/// `modulus`, from the digits of [`garner_digits`]. This is exact modulo `modulus` even when the
+/// product of the moduli overflows. Panics if any modulus is 0.
+pub fn garner(congruences: &[(u64, u64)], modulus: u64) -> Option<u64> {
+    assert!(modulus != 0, "modulus must be nonzero");
+
+    let digits = garner_digits(congruences)?;
+    let mut x = 0;
+    let mut radix = 1 % modulus;
+
+    for (&v, &(_, m)) in digits.iter().zip(congruences) {
+        x = add_mod(x, mul_mod(v % modulus, radix, modulus), modulus);
+        radix = mul_mod(radix, m % modulus, modulus);
+    }
+
+    Some(x)
+}
+
+/// The exact solution of the system of congruences with pairwise coprime moduli, from the digits
+/// of [`garner_digits`].
+#[cfg(feature = "bigint")]
+pub fn garner_biguint(congruences: &[(u64, u64)]) -> Option<num_bigint::BigUint> {
+    let digits = garner_digits(congruences)?;
+
+    // Horner's rule from the most significant digit down.
+    Some(
+        digits
+            .iter()
+            .zip(congruences)
+            .rev()
+            .fold(num_bigint::BigUint::default(), |x, (&v, &(_, m))| x * m + v),
+    )
+}
+
+/// `a − b mod m`, for any `a` and `b`. Panics if `m` is 0.
+pub fn sub_mod(a: u64, b: u64, m: u64) -> u64 {
+    let (a, b) = (reduce(a, m), reduce(b, m));
+
+    if a >= b {
+        a - b
+    } else {
+        m - (b - a)
+    }
+}
+
+/// `a + b mod m`, for any `a` and `b`, without overflowing. Panics if `m` is 0.
+pub fn add_mod(a: u64, b: u64, m: u64) -> u64 {
+    let (a, b) = (reduce(a, m), reduce(b, m));
+
+    if a >= m - b {
+        a - (m - b)
+    } else {
+        a + b
+    }
+}
+
+// a mod m, skipping the division when a is already reduced, as it is in the hot loops.
+fn reduce(a: u64, m: u64) -> u64 {
+    if a >= m {
+        a % m
+    } else {
+        a
+    }
+}
+
+pub(crate) fn gcd_u128(a: u128, b: u128) -> u128 {
+    let mut a = a;
+    let mut b = b;
+
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+
+    a
+}
+
+pub(crate) fn add_mod_u128(a: u128, b: u128, m: u128) -> u128 {
+    if a >= m - b {
+        a - (m - b)
+    } else {
+        a + b
+    }
+}
+
+pub(crate) fn mul_mod_u128(a: u128, b: u128, m: u128) -> u128 {
+    if m <= u64::MAX as u128 {
+        return mul_mod((a % m) as u64, (b % m) as u64, m as u64) as u128;
+    }
+
+    // Double-and-add, since the full product may need 256 bits.
+    let mut a = a % m;
+    let mut b = b % m;
+    let mut result = 0;
+
+    while b > 0 {
+        if b & 1 == 1 {
+            result = add_mod_u128(result, a, m);
+        }
+
+        a = add_mod_u128(a, a, m);
+        b >>= 1;
+    }
+
+    result
+}
+
+pub(crate) fn sub_mod_u128(a: u128, b: u128, m: u128) -> u128 {
+    if a >= b {
+        a - b
+    } else {
+        m - (b - a)
+    }
+}
+
+pub(crate) fn mod_pow_u128(base: u128, exp: u128, modulus: u128) -> u128 {
+    if modulus == 1 {
+        return 0;
+    }
+
+    let mut result = 1;
+    let mut base = base % modulus;
+    let mut exp = exp;
+
+    while exp > 0 {
+        if exp & 1 == 1 {
+            result = mul_mod_u128(result, base, modulus);
+        }
+
+        base = mul_mod_u128(base, base, modulus);
+        exp >>= 1;
+    }
+
+    result
+}
+
+/// The Jacobi symbol `(a | n)` for odd positive `n`, by quadratic reciprocity. It is 0 when
+/// `gcd(a, n) != 1`. Panics if `n` is even.
+pub fn jacobi(a: i64, n: u64) -> i8 {
+    assert!(n % 2 == 1, "the Jacobi symbol needs an odd modulus");
+
+    let a = if a >= 0 {
+        a as u64 % n
+    } else {
+        sub_mod(0, a.unsigned_abs() % n, n)
+    };
+
+    jacobi_u128(a as u128, n as u128) as i8
+}
+
+/// The Legendre symbol `(a | p)` by Euler's criterion, `a^((p − 1) / 2) mod p`. Panics unless
+/// `p` is an odd prime.
+pub fn legendre(a: i64, p: u64) -> i8 {
+    assert!(p != 2 && crate::is_prime(p), "{p} is not an odd prime");
+
+    let a = if a >= 0 {
+        a as u64 % p
+    } else {
+        sub_mod(0, a.unsigned_abs() % p, p)
+    };
+
+    match mod_pow(a, (p - 1) / 2, p) {
+        0 => 0,
+        1 => 1,
+        _ => -1,
+    }
+}
+
+/// The Kronecker symbol `(a | n)`, which extends the Jacobi symbol to every `n`: `(a | 2)` is 0
+/// for even `a` and otherwise 1 or −1 as `a ≡ ±1` or `±3 (mod 8)`, `(a | −1)` is the sign of
+/// `a`, and `(a | 0)` is 1 for `a = ±1` and 0 otherwise.
+pub fn kronecker(a: i64, n: i64) -> i8 {
+    if n == 0 {
+        return (a.unsigned_abs() == 1) as i8;
+    }
+
+    let mut result = if n < 0 && a < 0 { -1 } else { 1 };
+    let n = n.unsigned_abs();
+    let twos = n.trailing_zeros();
+
+    if twos > 0 {
+        if a % 2 == 0 {
+            return 0;
+        }
+
+        if twos % 2 == 1 && matches!(a.rem_euclid(8), 3 | 5) {
+            result = -result;
+        }
+    }
+
+    result * jacobi(a, n >> twos)
+}
+
+// The Jacobi symbol (a | n) for odd n, by quadratic reciprocity.
+pub(crate) fn jacobi_u128(a: u128, n: u128) -> i32 {
+    let mut a = a % n;
+    let mut n = n;
+    let mut result = 1;
+
+    while a != 0 {
+        let twos = a.trailing_zeros();
+
+        a >>= twos;
+
+        // (2 | n) = -1 exactly when n ≡ ±3 (mod 8).
+        if twos % 2 == 1 && matches!(n % 8, 3 | 5) {
+            result = -result;
+        }
+
+        if a % 4 == 3 && n % 4 == 3 {
+            result = -result;
+        }
+
+        (a, n) = (n % a, a);
+    }
+
+    if n == 1 {
+        result
+    } else {
+        0
+    }
+}
+
+/// The algorithm [`sqrt_mod_prime_with`] uses.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SqrtMethod {
+    /// Tonelli–Shanks when the 2-adic part of `p − 1` is small, and Cipolla otherwise.
+    #[default]
+    Auto,
+    /// Tonelli–Shanks, which costs up to `s^2` multiplications for `p − 1 = 2^s q`.
+    TonelliShanks,
+    /// Cipolla's algorithm, one exponentiation in `F_p[√(t^2 − a)]` whatever the shape of `p`.
+    Cipolla,
+}
+
+/// A square root of `a` modulo the prime `p`, or `None` if `a` is a quadratic non-residue. The
+/// other root is `p` minus this one. Panics if `p` is not prime.
+pub fn sqrt_mod_prime(a: u64, p: u64) -> Option<u64> {
+    sqrt_mod_prime_with(a, p, SqrtMethod::Auto)
+}
+
+/// A square root of `a` modulo the prime `p` by the given method, or `None` if `a` is a
+/// quadratic non-residue. Panics if `p` is not prime.
+pub fn sqrt_mod_prime_with(a: u64, p: u64, method: SqrtMethod) -> Option<u64> {
+    assert!(crate::is_prime(p), "{p} is not prime");
+
+    let a = a % p;
+
+    if a == 0 || p == 2 {
+        return Some(a);
+    }
+
+    if mod_pow(a, (p - 1) / 2, p) != 1 {
+        return None;
+    }
+
+    let s = (p - 1).trailing_zeros();
+
+    // Both take about log p multiplications for the exponentiation; Tonelli–Shanks adds up to
+    // s^2 more, and Cipolla's are each several times the cost of one in F_p.
+    let cipolla = match method {
+        SqrtMethod::Auto => (s * s) as u64 > 4 * p.ilog2() as u64,
+        SqrtMethod::TonelliShanks => false,
+        SqrtMethod::Cipolla => true,
+    };
+
+    Some(if cipolla {
+        cipolla_sqrt(a, p)
+    } else {
+        tonelli_shanks(a, p)
+    })
+}
+
+// A square root of the nonzero residue a modulo the odd prime p.
+fn tonelli_shanks(a: u64, p: u64) -> u64 {
+    let s = (p - 1).trailing_zeros();
+    let q = (p - 1) >> s;
+    let z = (2..p)
+        .find(|&z| mod_pow(z, (p - 1) / 2, p) == p - 1)
+        .unwrap();
+
+    let mut m = s;
+    let mut c = mod_pow(z, q, p);
+    let mut t = mod_pow(a, q, p);
+    let mut r = mod_pow(a, q.div_ceil(2), p);
+
+    while t != 1 {
+        let mut i = 0;
+        let mut t2 = t;
+
+        while t2 != 1 {
+            t2 = mul_mod(t2, t2, p);
+            i += 1;
+        }
+
+        let b = mod_pow(c, 1 << (m - i - 1), p);
+
+        m = i;
+        c = mul_mod(b, b, p);
+        t = mul_mod(t, c, p);
+        r = mul_mod(r, b, p);
+    }
+
+    r
+}
+
+// A square root of the nonzero residue a modulo the odd prime p, as (t + ω)^((p + 1) / 2) for
+// ω^2 = t^2 − a a non-residue, which lies in F_p.
+fn cipolla_sqrt(a: u64, p: u64) -> u64 {
+    let (t, w2) = (0..p)
+        .map(|t| (t, sub_mod(mul_mod(t, t, p), a, p)))
+        .find(|&(_, w2)| mod_pow(w2, (p - 1) / 2, p) == p - 1)
+        .unwrap();
+
+    // (x0 + x1 ω)(y0 + y1 ω) in F_p[ω].
+    let mul = |(x0, x1): (u64, u64), (y0, y1): (u64, u64)| {
+        (
+            add_mod(mul_mod(x0, y0, p), mul_mod(mul_mod(x1, y1, p), w2, p), p),
+            add_mod(mul_mod(x0, y1, p), mul_mod(x1, y0, p), p),
+        )
+    };
+
+    let mut result = (1, 0);
+    let mut base = (t, 1);
+    let mut exp = p.div_ceil(2);
+
+    while exp > 0 {
+        if exp & 1 == 1 {
+            result = mul(result, base);
+        }
+
+        base = mul(base, base);
+        exp >>= 1;
+    }
+
+    result.0
+}
+
+/// Every square root of `a` modulo `n`, in increasing order. Roots modulo each prime power of
+/// `n` are lifted by Hensel's lemma and combined by the Chinese remainder theorem. There can be
+/// many: `x^2 ≡ 0 (mod 2^2k)` has `2^k`. Panics if `n` is 0.
+pub fn sqrt_mod(a: u64, n: u64) -> Vec<u64> {
+    assert!(n != 0, "modulus must be nonzero");
+
+    let mut roots = vec![0];
+    let mut modulus = 1;
+
+    for (p, e) in crate::factorization(n) {
+        let q = p.pow(e);
+        let local = sqrt_mod_prime_power(a % q, p, e);
+
+        roots = roots
+            .iter()
+            .flat_map(|&x| local.iter().map(move |&y| (x, y)))
+            .map(|(x, y)| crt(&[(x, modulus), (y, q)]).unwrap().0)
+            .collect();
+        modulus *= q;
+    }
+
+    roots.sort_unstable();
+    roots
+}
+
+/// Whether `x^2 ≡ a (mod n)` has a solution, from the local conditions at each prime power of `n`
+/// without computing a root. Panics if `n` is 0.
+pub fn is_quadratic_residue(a: u64, n: u64) -> bool {
+    assert!(n != 0, "modulus must be nonzero");
+
+    crate::factorization(n).iter().all(|&(p, e)| {
+        let mut u = a % p.pow(e);
+        let mut v = 0;
+
+        if u == 0 {
+            return true;
+        }
+
+        while u.is_multiple_of(p) {
+            u /= p;
+            v += 1;
+        }
+
+        let k = e - v;
+
+        v % 2 == 0
+            && match (p, k) {
+                (2, 1) => true,
+                (2, 2) => u % 4 == 1,
+                (2, _) => u % 8 == 1,
+                _ => mod_pow(u, (p - 1) / 2, p) == 1,
+            }
+    })
+}
+
+// The square roots of a < p^e modulo p^e.
+fn sqrt_mod_prime_power(a: u64, p: u64, e: u32) -> Vec<u64> {
+    let q = p.pow(e);
+
+    if a == 0 {
+        // x^2 ≡ 0 exactly when p^ceil(e / 2) divides x.
+        return (0..q).step_by(p.pow(e.div_ceil(2)) as usize).collect();
+    }
+
+    let mut v = 0;
+    let mut u = a;
+
+    while u.is_multiple_of(p) {
+        u /= p;
+        v += 1;
+    }
+
+    if v % 2 == 1 {
+        return vec![];
+    }
+
+    // x = p^(v / 2) y with y^2 ≡ u (mod p^(e − v)), and y free modulo p^(e − v / 2).
+    let scale = p.pow(v / 2);
+    let inner = p.pow(e - v);
+    let mut roots = vec![];
+
+    for y in sqrt_mod_unit(u, p, e - v) {
+        roots.extend((0..scale).map(|t| scale * (y + t * inner)));
+    }
+
+    roots
+}
+
+// The square roots modulo p^k of u < p^k, which is coprime to p.
+fn sqrt_mod_unit(u: u64, p: u64, k: u32) -> Vec<u64> {
+    let q = p.pow(k);
+
+    if p == 2 {
+        return match k {
+            1 => vec![1],
+            2 if u % 4 == 1 => vec![1, 3],
+            2 => vec![],
+            _ if u % 8 != 1 => vec![],
+            _ => {
+                // Fix one bit at a time; r + 2^(j − 1) changes r^2 by 2^j (mod 2^(j + 1)).
+                let mut r = 1;
+
+                for j in 3..k {
+                    let m = 1 << (j + 1);
+
+                    if mul_mod(r, r, m) != u % m {
+                        r += 1 << (j - 1);
+                    }
+                }
+
+                let half = q / 2;
+
+                vec![r, q - r, (r + half) % q, (q - r + half) % q]
+            }
+        };
+    }
+
+    let Some(mut r) = sqrt_mod_prime(u % p, p) else {
+        return vec![];
+    };
+    let mut pj = p;
+
+    // Hensel's lemma: each Newton step r − (r^2 − u) / 2r gains one more p-adic digit.
+    for _ in 1..k {
+        pj *= p;
+
+        let f = sub_mod(mul_mod(r, r, pj), u % pj, pj);
+        let inverse = mod_inv(add_mod(r, r, pj), pj).unwrap();
+
+        r = sub_mod(r, mul_mod(f, inverse, pj), pj);
+    }
+
+    vec![r, q - r]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mul_add_sub_mod_01() {
+        let m = u64::MAX;
+
+        assert_eq!(mul_mod(m - 1, m - 1, m), 1);
+        assert_eq!(mul_mod(u64::MAX, u64::MAX, 1_000_000_007), 114_944_269);
+        assert_eq!(add_mod(m - 1, m - 1, m), m - 2);
+        assert_eq!(add_mod(u64::MAX, u64::MAX, 10), 0);
+        assert_eq!(add_mod(3, 4, 1), 0);
+        assert_eq!(sub_mod(0, 1, m), m - 1);
+        assert_eq!(sub_mod(5, u64::MAX, 7), 4);
+
+        for m in 1..50u64 {
+            for a in 0..2 * m {
+                for b in 0..2 * m {
+                    assert_eq!(mul_mod(a, b, m), a * b % m);
+                    assert_eq!(add_mod(a, b, m), (a + b) % m);
+                    assert_eq!(sub_mod(a, b, m), (a + 2 * m - b) % m);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn mod_inv_01() {
+        assert_eq!(mod_inv(3, 7), Some(5));
+        assert_eq!(mod_inv(10, 7), Some(5));
+        assert_eq!(mod_inv(0, 1), Some(0));
+        assert_eq!(mod_inv(0, 7), None);
+        assert_eq!(mod_inv(6, 9), None);
+
+        let p = 18_446_744_073_709_551_557;
+
+        for a in [1, 2, 12_345, p - 1, u64::MAX] {
+            let inverse = mod_inv(a, p).unwrap();
+
+            assert_eq!(mul_mod(a, inverse, p), 1);
+            assert_eq!(inverse, mod_pow(a, p - 2, p));
+        }
+
+        for m in 1..200 {
+            for a in 0..m {
+                match mod_inv(a, m) {
+                    Some(inverse) => assert_eq!(mul_mod(a, inverse, m), 1 % m),
+                    None => assert_ne!(gcd(a, m), 1),
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn gcd_lcm_01() {
+        assert_eq!(gcd(0, 0), 0);
+        assert_eq!(gcd(12, 18), 6);
+        assert_eq!(gcd(u64::MAX, 1 << 32 | 1), 1 << 32 | 1);
+        assert_eq!(lcm(4, 6), Some(12));
+        assert_eq!(lcm(0, 6), Some(0));
+        assert_eq!(lcm(1 << 32, 3 << 31), Some(3 << 32));
+        assert_eq!(lcm(1 << 32, (1 << 32) + 1), None);
+
+        assert_eq!(gcd_all(&[]), 0);
+        assert_eq!(gcd_all(&[84, 126, 210]), 42);
+        assert_eq!(lcm_all(&[]), Some(1));
+        assert_eq!(lcm_all(&(1..=20).collect::<Vec<_>>()), Some(232_792_560));
+        assert_eq!(
+            lcm_all(&(1..=46).collect::<Vec<_>>()),
+            Some(9_419_588_158_802_421_600)
+        );
+        assert_eq!(lcm_all(&(1..=47).collect::<Vec<_>>()), None);
+        assert_eq!(lcm_all(&[u64::MAX, 0]), Some(0));
+        assert_eq!(lcm_all(&[u64::MAX, u64::MAX - 1, 0]), Some(0));
+    }
+
+    #[test]
+    fn crt_01() {
+        assert_eq!(crt(&[]), Some((0, 1)));
+        assert_eq!(crt(&[(2, 3), (3, 5), (2, 7)]), Some((23, 105)));
+        assert_eq!(crt(&[(10, 7)]), Some((3, 7)));
+        assert_eq!(crt(&[(1, 4), (3, 6)]), Some((9, 12)));
+        assert_eq!(crt(&[(1, 4), (2, 6)]), None);
+        assert_eq!(crt(&[(0, 1 << 32), (1, (1 << 32) + 1)]), None);
+
+        let p = 18_446_744_073_709_551_557;
+        let (x, l) = crt(&[(5, 4_294_967_291), (7, 4_294_967_279)]).unwrap();
+
+        assert_eq!(l, 4_294_967_291 * 4_294_967_279);
+        assert_eq!((x % 4_294_967_291, x % 4_294_967_279), (5, 7));
+        assert_eq!(crt(&[(p - 1, p), (p - 1, p)]), Some((p - 1, p)));
+
+        for m1 in 1..30 {
+            for m2 in 1..30 {
+                for r1 in 0..m1 {
+                    for r2 in 0..m2 {
+                        let l = lcm(m1, m2).unwrap();
+                        let expected = (0..l).find(|x| x % m1 == r1 && x % m2 == r2);
+
+                        assert_eq!(crt(&[(r1, m1), (r2, m2)]), expected.map(|x| (x, l)));
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn solve_linear_congruence_01() {
+        for m in 1..60u64 {
+            for a in 0..2 * m {
+                for b in 0..m {
+                    let expected: Vec<u64> = (0..m).filter(|&x| (a * x) % m == b).collect();
+
+                    match solve_linear_congruence(a, b, m) {
+                        Some(solutions) => {
+                            assert_eq!(solutions.collect::<Vec<_>>(), expected);
+                        }
+                        None => assert!(expected.is_empty(), "{a} x ≡ {b} mod {m}"),
+                    }
+                }
+            }
+        }
+
+        // 6 x ≡ 4 (mod 10) has the two solutions 4 and 9, and 0 x ≡ 0 has every residue.
+        let m = 1 << 62;
+
+        assert_eq!(
+            solve_linear_congruence(6, 4, 10)
+                .unwrap()
+                .collect::<Vec<_>>(),
+            [4, 9]
+        );
+        assert_eq!(
+            solve_linear_congruence(0, 0, m).unwrap().next_back(),
+            Some(m - 1)
+        );
+        assert!(solve_linear_congruence(0, 1, m).is_none());
+
+        let x = solve_linear_congruence(u64::MAX, 12_345, u64::MAX - 1)
+            .unwrap()
+            .next()
+            .unwrap();
+
+        assert_eq!(mul_mod(u64::MAX, x, u64::MAX - 1), 12_345);
+    }
+
+    #[test]
+    fn garner_01() {
+        assert_eq!(garner_digits(&[]), Some(vec![]));
+        assert_eq!(
+            garner_digits(&[(2, 3), (3, 5), (2, 7)]),
+            Some(vec![2, 2, 1])
+        );
+        assert_eq!(garner(&[(2, 3), (3, 5), (2, 7)], u64::MAX), Some(23));
+        assert_eq!(garner(&[(2, 3), (3, 5), (2, 7)], 10), Some(3));
+        assert_eq!(garner(&[(1, 4), (3, 6)], 100), None);
+        assert_eq!(garner(&[], 7), Some(0));
+        assert_eq!(garner(&[(5, 9)], 1), Some(0));
+
+        // The primes below 200 have a product far past u128.
+        let primes = crate::sieve::sieve_primes(200);
+        let x = 0x1234_5678_9abc_def0_u64;
+        let congruences: Vec<_> = primes.iter().map(|&p| (x % p, p)).collect();
+
+        assert_eq!(garner(&congruences, u64::MAX), Some(x % u64::MAX));
+        assert_eq!(garner(&congruences, 1_000_000_007), Some(x % 1_000_000_007));
+
+        for m1 in 1..20 {
+            for m2 in (1..20).filter(|&m2| gcd(m1, m2) == 1) {
+                for x in 0..m1 * m2 {
+                    let congruences = [(x % m1, m1), (x % m2, m2)];
+
+                    assert_eq!(garner(&congruences, u64::MAX), Some(x));
+                    assert_eq!(crt(&congruences), Some((x, m1 * m2)));
+                }
+            }
+        }
+    }
+
+    #[cfg(feature = "bigint")]
+    #[test]
+    fn garner_biguint_01() {
+        let primes = crate::sieve::sieve_primes(200);
+        let x = num_bigint::BigUint::from(3u32).pow(100) + 1u32;
+        let congruences: Vec<_> = primes
+            .iter()
+            .map(|&p| ((&x % p).try_into().unwrap(), p))
+            .collect();
+
+        assert_eq!(garner_biguint(&congruences), Some(x));
+        assert_eq!(garner_biguint(&[(1, 4), (3, 6)]), None);
+    }
+
+    #[test]
+    fn jacobi_01() {
+        assert_eq!(jacobi(0, 1), 1);
+        assert_eq!(jacobi(2, 7), 1);
+        assert_eq!(jacobi(3, 7), -1);
+        assert_eq!(jacobi(-1, 7), -1);
+        assert_eq!(jacobi(-1, 5), 1);
+        assert_eq!(jacobi(6, 9), 0);
+        assert_eq!(jacobi(2, 15), 1);
+        assert_eq!(jacobi(i64::MIN, u64::MAX), jacobi(-1, u64::MAX));
+
+        // For a prime p the symbol is Euler's criterion, and it is multiplicative in n.
+        for p in [3, 5, 7, 11, 13, 1009] {
+            for a in -50..50i64 {
+                let euler = mod_pow(a.rem_euclid(p as i64) as u64, (p - 1) / 2, p);
+                let expected = match euler {
+                    0 => 0,
+                    1 => 1,
+                    _ => -1,
+                };
+
+                assert_eq!(jacobi(a, p), expected);
+                assert_eq!(jacobi(a, 3 * p), jacobi(a, 3) * jacobi(a, p));
+            }
+        }
+    }
+
+    #[test]
+    fn legendre_01() {
+        assert_eq!(legendre(0, 3), 0);
+        assert_eq!(legendre(4, 7), 1);
+        assert_eq!(legendre(-1, 7), -1);
+        assert_eq!(legendre(-1, 13), 1);
+        assert_eq!(legendre(2, 18_446_744_073_709_551_557), -1);
+
+        for p in crate::primes_upto(200).skip(1) {
+            let squares: Vec<_> = (1..p).map(|x| x * x % p).collect();
+
+            for a in -300..300i64 {
+                let expected = match a.rem_euclid(p as i64) as u64 {
+                    0 => 0,
+                    r if squares.contains(&r) => 1,
+                    _ => -1,
+                };
+
+                assert_eq!(legendre(a, p), expected);
+                assert_eq!(legendre(a, p), jacobi(a, p));
+            }
+        }
+    }
+
+    #[test]
+    fn kronecker_01() {
+        assert_eq!(kronecker(1, 0), 1);
+        assert_eq!(kronecker(-1, 0), 1);
+        assert_eq!(kronecker(2, 0), 0);
+        assert_eq!(kronecker(3, 2), -1);
+        assert_eq!(kronecker(7, 2), 1);
+        assert_eq!(kronecker(4, 2), 0);
+        assert_eq!(kronecker(-5, -1), -1);
+        assert_eq!(kronecker(5, -1), 1);
+        assert_eq!(kronecker(3, i64::MIN), -1);
+        assert_eq!(kronecker(0, 1), 1);
+
+        for a in -100..100 {
+            for n in -100..100i64 {
+                if n % 2 != 0 && n > 0 {
+                    assert_eq!(kronecker(a, n), jacobi(a, n as u64));
+                }
+
+                // Completely multiplicative in n, away from 0.
+                if n != 0 {
+                    for m in [-1, 2, 3, 4, 6] {
+                        assert_eq!(kronecker(a, n * m), kronecker(a, n) * kronecker(a, m));
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn sqrt_mod_prime_01() {
+        assert_eq!(sqrt_mod_prime(0, 7), Some(0));
+        assert_eq!(sqrt_mod_prime(1, 2), Some(1));
+        assert_eq!(sqrt_mod_prime(3, 7), None);
+
+        // p ≡ 1 (mod 2^k) for large k exercises the Tonelli–Shanks loop.
+        for p in [
+            3u64,
+            5,
+            13,
+            17,
+            97,
+            257,
+            65_537,
+            998_244_353,
+            18_446_744_073_709_551_557,
+        ] {
+            for a in (0..200).chain(p.saturating_sub(100)..p) {
+                match sqrt_mod_prime(a, p) {
+                    Some(r) => assert_eq!(mul_mod(r, r, p), a % p),
+                    None => assert_eq!(mod_pow(a, (p - 1) / 2, p), p - 1),
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn sqrt_mod_prime_02() {
+        // 2^64 − 2^32 + 1 and 998244353 have large 2-adic parts, where Auto picks Cipolla.
+        for p in [3, 13, 1009, 998_244_353, 18_446_744_069_414_584_321] {
+            for a in 0..300u64 {
+                let roots = [
+                    sqrt_mod_prime_with(a, p, SqrtMethod::TonelliShanks),
+                    sqrt_mod_prime_with(a, p, SqrtMethod::Cipolla),
+                    sqrt_mod_prime_with(a, p, SqrtMethod::Auto),
+                ];
+
+                for root in roots {
+                    assert_eq!(root.is_some(), mod_pow(a % p, (p - 1) / 2, p) <= 1);
+
+                    if let Some(r) = root {
+                        assert_eq!(mul_mod(r, r, p), a % p);
+                    }
+                }
+
+                // The two methods agree up to sign.
+                if let [Some(x), Some(y), _] = roots {
+                    assert!(x == y || x == (p - y) % p);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn sqrt_mod_01() {
+        assert_eq!(sqrt_mod(0, 1), vec![0]);
+        assert_eq!(sqrt_mod(1, 8), vec![1, 3, 5, 7]);
+        assert_eq!(sqrt_mod(4, 15), vec![2, 7, 8, 13]);
+        assert!(sqrt_mod(2, 15).is_empty());
+        assert_eq!(sqrt_mod(0, 16), vec![0, 4, 8, 12]);
+
+        for n in 1..400u64 {
+            for a in 0..n {
+                let expected: Vec<_> = (0..n).filter(|&x| x * x % n == a).collect();
+
+                assert_eq!(sqrt_mod(a, n), expected, "{a} {n}");
+            }
+        }
+
+        // Prime powers past 2^32, where products need 128 bits.
+        for (a, n) in [
+            (2, 7u64.pow(22)),
+            (17, 1 << 63),
+            (9 * 7, 3u64.pow(39)),
+            (12_345 * 12_345, 18_446_744_073_709_551_557),
+        ] {
+            let roots = sqrt_mod(a, n);
+
+            assert!(!roots.is_empty());
+
+            for r in roots {
+                assert_eq!(mul_mod(r, r, n), a);
+            }
+        }
+    }
+
+    #[test]
+    fn is_quadratic_residue_01() {
+        assert!(is_quadratic_residue(0, 1));
+        assert!(is_quadratic_residue(4, 15));
+        assert!(!is_quadratic_residue(2, 15));
+        assert!(is_quadratic_residue(17, 1 << 63));
+        assert!(!is_quadratic_residue(5, 1 << 63));
+
+        for n in 1..400 {
+            for a in 0..n + 10 {
+                assert_eq!(
+                    is_quadratic_residue(a, n),
+                    !sqrt_mod(a, n).is_empty(),
+                    "{a} {n}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn ext_gcd_01() {
+        assert_eq!(ext_gcd(240, 46), (2, -9, 47));
+        assert_eq!(ext_gcd(0, 0), (0, 1, 0));
+        assert_eq!(ext_gcd(0, 5), (5, 0, 1));
+        assert_eq!(ext_gcd(5, 0), (5, 1, 0));
+
+        let cases = [
+            (u64::MAX, u64::MAX - 1),
+            (u64::MAX, 1 << 63),
+            (18_446_744_073_709_551_557, 4_294_967_291),
+            (1 << 40, 3 << 20),
+        ];
+
+        for (a, b) in cases
+            .into_iter()
+            .chain((0..50).flat_map(|a| (0..50).map(move |b| (a, b))))
+        {
+            let (g, x, y) = ext_gcd(a, b);
+
+            assert_eq!(g, gcd(a, b));
+            assert_eq!(a as i128 * x + b as i128 * y, g as i128, "{a} {b}");
+
+            if let (Some(a), Some(b)) = (a.checked_div(g), b.checked_div(g)) {
+                assert!(x.unsigned_abs() <= (b / 2).max(1) as u128);
+                assert!(y.unsigned_abs() <= (a / 2).max(1) as u128);
+            }
+        }
+    }
+
+    #[test]
+    fn mod_pow_01() {
+        assert_eq!(mod_pow(2, 10, 1000), 24);
+        assert_eq!(mod_pow(0, 0, 7), 1);
+        assert_eq!(mod_pow(5, 0, 1), 0);
+        assert_eq!(mod_pow(3, 1_000_000_006, 1_000_000_007), 1);
+
+        // Fermat's little theorem for the largest prime below 2^64, where products need 128 bits.
+        let p = 18_446_744_073_709_551_557;
+
+        assert_eq!(mod_pow(u64::MAX, p - 1, p), 1);
+        assert_eq!(mod_pow(p - 1, 3, p), p - 1);
+
+        for base in 0..20u64 {
+            for exp in 0..20 {
+                assert_eq!(
+                    mod_pow(base, exp, 1009),
+                    ((base as u128).pow(exp as u32) % 1009) as u64
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn mod_pow_ct_01() {
+        for modulus in [1, 2, 10, 1_000_000_007, 1 << 63, u64::MAX - 58, u64::MAX] {
+            for base in [0, 1, 2, 3, 12_345_678_901, u64::MAX] {
+                for exp in [0, 1, 2, 15, 16, 65_537, 1 << 63, u64::MAX] {
+                    assert_eq!(
+                        mod_pow_ct(base, exp, modulus),
+                        mod_pow(base, exp, modulus),
+                        "{base}^{exp} mod {modulus}"
+                    );
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,248 @@
+use num_bigint::BigUint;
+use num_integer::Integer;
+use num_traits::{One, Zero};
+
+use crate::is_prime_biguint;
+
+/// [`mod_pow`](crate::mod_pow) for `BigUint`s. `0^0` is 1. Panics if `modulus` is 0.
+pub fn mod_pow_biguint(base: &BigUint, exp: &BigUint, modulus: &BigUint) -> BigUint {
+    assert!(!modulus.is_zero(), "modulus must be nonzero");
+
+    base.modpow(exp, modulus)
+}
+
+/// [`mod_inv`](crate::mod_inv) for `BigUint`s: the inverse of `a` modulo `m`, or `None` if
+/// `gcd(a, m) != 1`. Every inverse modulo 1 is 0. Panics if `m` is 0.
+pub fn mod_inv_biguint(a: &BigUint, m: &BigUint) -> Option<BigUint> {
+    assert!(!m.is_zero(), "modulus must be nonzero");
+
+    if m.is_one() {
+        return Some(BigUint::zero());
+    }
+
+    a.modinv(m)
+}
+
+/// [`crt`](crate::crt) for `BigUint`s: `(x, l)` with `l` the lcm of the moduli and `0 <= x < l`,
+/// or `None` if the congruences are inconsistent. The moduli need not be coprime, and nothing
+/// overflows. The empty system gives `(0, 1)`. Panics if a modulus is 0.
+pub fn crt_biguint(congruences: &[(BigUint, BigUint)]) -> Option<(BigUint, BigUint)> {
+    congruences
+        .iter()
+        .try_fold((BigUint::zero(), BigUint::one()), |(r1, m1), (r2, m2)| {
+            assert!(!m2.is_zero(), "modulus must be nonzero");
+
+            // x = r1 + m1 k, with m1 k ≡ r2 − r1 (mod m2), which needs g | r2 − r1.
+            let g = m1.gcd(m2);
+            let diff = (r2 % m2 + m2 - &r1 % m2) % m2;
+
+            if !diff.is_multiple_of(&g) {
+                return None;
+            }
+
+            let step = m2 / &g;
+            let inverse = mod_inv_biguint(&(&m1 / &g), &step)?;
+            let k = diff / &g * inverse % &step;
+
+            Some((r1 + &m1 * k, m1 * step))
+        })
+}
+
+/// [`jacobi`](crate::jacobi) for `BigUint`s: the Jacobi symbol `(a | n)` for odd `n`, which is
+/// 0 when `gcd(a, n) != 1`. Panics if `n` is even.
+pub fn jacobi_biguint(a: &BigUint, n: &BigUint) -> i8 {
+    assert!(n.is_odd(), "the Jacobi symbol needs an odd modulus");
+
+    let mut a = a % n;
+    let mut n = n.clone();
+    let mut result = 1;
+
+    while !a.is_zero() {
+        let twos = a.trailing_zeros().unwrap();
+
+        a >>= twos;
+
+        // (2 | n) = -1 exactly when n ≡ ±3 (mod 8).
+        if twos % 2 == 1 && matches!(low_bits(&n, 8), 3 | 5) {
+            result = -result;
+        }
+
+        if low_bits(&a, 4) == 3 && low_bits(&n, 4) == 3 {
+            result = -result;
+        }
+
+        let rest = &n % &a;
+
+        n = std::mem::replace(&mut a, rest);
+    }
+
+    if n.is_one() {
+        result
+    } else {
+        0
+    }
+}
+
+/// [`sqrt_mod_prime`](crate::sqrt_mod_prime) for `BigUint`s, by Tonelli–Shanks: a square root
+/// of `a` modulo the prime `p`, or `None` if `a` is a quadratic non-residue. The other root is
+/// `p` minus this one. Panics if `p` fails [`is_prime_biguint`].
+pub fn sqrt_mod_prime_biguint(a: &BigUint, p: &BigUint) -> Option<BigUint> {
+    assert!(is_prime_biguint(p), "{p} is not prime");
+
+    let a = a % p;
+
+    if a.is_zero() || p == &BigUint::from(2u32) {
+        return Some(a);
+    }
+
+    if jacobi_biguint(&a, p) != 1 {
+        return None;
+    }
+
+    let one = BigUint::one();
+    let s = (p - 1u32).trailing_zeros().unwrap();
+    let q = (p - 1u32) >> s;
+    let z = (2u32..)
+        .map(BigUint::from)
+        .find(|z| jacobi_biguint(z, p) == -1)
+        .unwrap();
+
+    let mut m = s;
+    let mut c = z.modpow(&q, p);
+    let mut t = a.modpow(&q, p);
+    let mut r = a.modpow(&((&q + 1u32) >> 1), p);
+
+    while t != one {
+        let mut i = 0;
+        let mut t2 = t.clone();
+
+        while t2 != one {
+            t2 = &t2 * &t2 % p;
+            i += 1;
+        }
+
+        let b = c.modpow(&(&one << (m - i - 1)), p);
+
+        m = i;
+        c = &b * &b % p;
+        t = t * &c % p;
+        r = r * b % p;
+    }
+
+    Some(r)
+}
+
+// n mod m for a power of two m up to 2^32.
+fn low_bits(n: &BigUint, m: u32) -> u32 {
+    n.iter_u32_digits().next().unwrap_or(0) & (m - 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{crt, jacobi, mod_inv, mod_pow, sqrt_mod_prime};
+
+    fn big(n: u64) -> BigUint {
+        BigUint::from(n)
+    }
+
+    #[test]
+    fn mod_pow_biguint_01() {
+        for modulus in [1u64, 2, 10, 1_000_000_007, u64::MAX] {
+            for (base, exp) in [(0u64, 0u64), (2, 10), (3, 1 << 40), (u64::MAX, u64::MAX)] {
+                assert_eq!(
+                    mod_pow_biguint(&big(base), &big(exp), &big(modulus)),
+                    big(mod_pow(base, exp, modulus))
+                );
+            }
+        }
+
+        // Fermat's little theorem modulo the Mersenne prime 2^127 − 1.
+        let m127 = (BigUint::one() << 127u32) - 1u32;
+
+        assert!(mod_pow_biguint(&big(3), &(&m127 - 1u32), &m127).is_one());
+    }
+
+    #[test]
+    fn mod_inv_biguint_01() {
+        for m in [1u64, 2, 12, 1_000_000_007] {
+            for a in [0u64, 1, 5, 6, 123_456_789] {
+                assert_eq!(mod_inv_biguint(&big(a), &big(m)), mod_inv(a, m).map(big));
+            }
+        }
+
+        let m127 = (BigUint::one() << 127u32) - 1u32;
+        let a = BigUint::from(u128::MAX / 3);
+        let inverse = mod_inv_biguint(&a, &m127).unwrap();
+
+        assert!((a * inverse % &m127).is_one());
+        assert_eq!(mod_inv_biguint(&big(6), &(&m127 * 3u32)), None);
+    }
+
+    #[test]
+    fn crt_biguint_01() {
+        for congruences in [
+            vec![],
+            vec![(2u64, 3u64), (3, 5), (2, 7)],
+            vec![(3, 4), (5, 6)],
+            vec![(1, 4), (2, 6)],
+            vec![(7, 10), (0, 1)],
+        ] {
+            let big_congruences: Vec<_> =
+                congruences.iter().map(|&(r, m)| (big(r), big(m))).collect();
+
+            assert_eq!(
+                crt_biguint(&big_congruences),
+                crt(&congruences).map(|(x, l)| (big(x), big(l)))
+            );
+        }
+
+        // Moduli whose lcm overflows a u64.
+        let (p, q) = (
+            big(18_446_744_073_709_551_557),
+            big(18_446_744_073_709_551_533),
+        );
+        let (x, l) = crt_biguint(&[(big(5), p.clone()), (big(7), q.clone())]).unwrap();
+
+        assert_eq!(l, &p * &q);
+        assert_eq!((&x % &p, &x % &q), (big(5), big(7)));
+    }
+
+    #[test]
+    fn jacobi_biguint_01() {
+        for n in [1u64, 3, 15, 1_000_000_007, u64::MAX] {
+            for a in [0u64, 1, 2, 5, 15, 1_000_000_006, i64::MAX as u64] {
+                assert_eq!(
+                    jacobi_biguint(&big(a), &big(n)),
+                    jacobi(a as i64, n),
+                    "({a} | {n})"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn sqrt_mod_prime_biguint_01() {
+        // s = 1, 2 and 27 in p − 1 = 2^s q.
+        for p in [1_000_000_007u64, 998_244_353, 2_013_265_921] {
+            for a in [0u64, 1, 2, 3, 10, 123_456] {
+                let root = sqrt_mod_prime_biguint(&big(a), &big(p));
+
+                assert_eq!(root.is_some(), sqrt_mod_prime(a, p).is_some());
+
+                if let Some(root) = root {
+                    assert_eq!(&root * &root % p, big(a % p));
+                }
+            }
+        }
+
+        // 2^255 − 19 ≡ 5 (mod 8).
+        let p = (BigUint::one() << 255u32) - 19u32;
+        let a = BigUint::from(u128::MAX) * 7u32;
+        let square = &a * &a % &p;
+        let root = sqrt_mod_prime_biguint(&square, &p).unwrap();
+
+        assert!(root == a || root == &p - &a);
+        assert_eq!(sqrt_mod_prime_biguint(&big(2), &p), None);
+    }
+}
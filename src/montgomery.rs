@@ -0,0 +1,264 @@
+/// Arithmetic modulo a fixed odd modulus in Montgomery form, where `a` is represented by
+/// `a R mod n` with `R = 2^64`. Multiplication then needs two widening multiplications and no
+/// division, which pays off over repeated operations modulo the same `n`.
+///
+/// Values passed to and returned by [`mul`](Montgomery::mul), [`add`](Montgomery::add),
+/// [`sub`](Montgomery::sub) and [`pow`](Montgomery::pow) are in Montgomery form and below `n`;
+/// convert with [`to_montgomery`](Montgomery::to_montgomery) and
+/// [`from_montgomery`](Montgomery::from_montgomery).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Montgomery {
+    n: u64,
+    // n^-1 mod 2^64.
+    n_inv: u64,
+    // R^2 mod n, for converting into Montgomery form.
+    r2: u64,
+}
+
+impl Montgomery {
+    /// Panics if `n` is even.
+    pub const fn new(n: u64) -> Montgomery {
+        assert!(n % 2 == 1, "Montgomery arithmetic needs an odd modulus");
+
+        // Newton's iteration doubles the number of correct low bits, from 3 since n n ≡ 1 mod 8.
+        let mut n_inv = n;
+        let mut i = 0;
+
+        while i < 5 {
+            n_inv = n_inv.wrapping_mul(2u64.wrapping_sub(n.wrapping_mul(n_inv)));
+            i += 1;
+        }
+
+        let r = ((1u128 << 64) % n as u128) as u64;
+        let r2 = (r as u128 * r as u128 % n as u128) as u64;
+
+        Montgomery { n, n_inv, r2 }
+    }
+
+    pub const fn modulus(&self) -> u64 {
+        self.n
+    }
+
+    // t R^-1 mod n for t < n 2^64. The low words of t and m n agree, so only the high words of
+    // t − m n need subtracting.
+    fn reduce(&self, t: u128) -> u64 {
+        let m = (t as u64).wrapping_mul(self.n_inv);
+        let mn = ((m as u128 * self.n as u128) >> 64) as u64;
+        let high = (t >> 64) as u64;
+
+        if high >= mn {
+            high - mn
+        } else {
+            self.n - (mn - high)
+        }
+    }
+
+    pub fn to_montgomery(&self, a: u64) -> u64 {
+        self.reduce((a % self.n) as u128 * self.r2 as u128)
+    }
+
+    pub fn from_montgomery(&self, a: u64) -> u64 {
+        self.reduce(a as u128)
+    }
+
+    /// The Montgomery form of 1.
+    pub fn one(&self) -> u64 {
+        self.reduce(self.r2 as u128)
+    }
+
+    pub fn mul(&self, a: u64, b: u64) -> u64 {
+        self.reduce(a as u128 * b as u128)
+    }
+
+    pub fn add(&self, a: u64, b: u64) -> u64 {
+        if a >= self.n - b {
+            a - (self.n - b)
+        } else {
+            a + b
+        }
+    }
+
+    pub fn sub(&self, a: u64, b: u64) -> u64 {
+        if a >= b {
+            a - b
+        } else {
+            self.n - (b - a)
+        }
+    }
+
+    /// `a^exp` for `a` in Montgomery form.
+    pub fn pow(&self, a: u64, exp: u64) -> u64 {
+        let mut result = self.one();
+        let mut base = a;
+        let mut exp = exp;
+
+        while exp > 0 {
+            if exp & 1 == 1 {
+                result = self.mul(result, base);
+            }
+
+            base = self.mul(base, base);
+            exp >>= 1;
+        }
+
+        result
+    }
+}
+
+/// Reduction modulo a fixed modulus by Barrett's method, which replaces each division with a
+/// multiplication by a precomputed reciprocal. Unlike [`Montgomery`] it works for any nonzero
+/// modulus, including even ones, and needs no change of representation.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Barrett {
+    m: u64,
+    // floor((2^128 − 1) / m), which is within one of 2^128 / m.
+    mu: u128,
+}
+
+impl Barrett {
+    /// Panics if `m` is 0.
+    pub const fn new(m: u64) -> Barrett {
+        assert!(m != 0, "modulus must be nonzero");
+
+        Barrett {
+            m,
+            mu: u128::MAX / m as u128,
+        }
+    }
+
+    pub const fn modulus(&self) -> u64 {
+        self.m
+    }
+
+    /// `x mod m` for any `x`.
+    pub fn reduce(&self, x: u128) -> u64 {
+        // q underestimates x / m by at most a few, so the remainder needs a couple of
+        // corrections at most.
+        let q = mul_high(x, self.mu);
+        let mut r = x - q * self.m as u128;
+
+        while r >= self.m as u128 {
+            r -= self.m as u128;
+        }
+
+        r as u64
+    }
+
+    /// `a b mod m` for any `a` and `b`.
+    pub fn mul(&self, a: u64, b: u64) -> u64 {
+        self.reduce(a as u128 * b as u128)
+    }
+
+    /// `base^exp mod m`.
+    pub fn pow(&self, base: u64, exp: u64) -> u64 {
+        let mut result = self.reduce(1);
+        let mut base = self.reduce(base as u128);
+        let mut exp = exp;
+
+        while exp > 0 {
+            if exp & 1 == 1 {
+                result = self.mul(result, base);
+            }
+
+            base = self.mul(base, base);
+            exp >>= 1;
+        }
+
+        result
+    }
+}
+
+// The high 128 bits of the 256-bit product a b, from four 64-bit partial products.
+fn mul_high(a: u128, b: u128) -> u128 {
+    const LOW: u128 = u64::MAX as u128;
+
+    let (a1, a0) = (a >> 64, a & LOW);
+    let (b1, b0) = (b >> 64, b & LOW);
+    let low = a0 * b0;
+    let cross1 = a1 * b0;
+    let cross2 = a0 * b1;
+    let middle = (low >> 64) + (cross1 & LOW) + (cross2 & LOW);
+
+    a1 * b1 + (cross1 >> 64) + (cross2 >> 64) + (middle >> 64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::modular::{add_mod, mod_pow, mul_mod, sub_mod};
+
+    #[test]
+    fn barrett_01() {
+        assert_eq!(mul_high(u128::MAX, u128::MAX), u128::MAX - 1);
+        assert_eq!(mul_high(1 << 100, 1 << 100), 1 << 72);
+
+        for m in [
+            1,
+            2,
+            10,
+            1 << 32,
+            1_000_000_007,
+            (1 << 63) + 2,
+            u64::MAX - 1,
+            u64::MAX,
+        ] {
+            let barrett = Barrett::new(m);
+
+            assert_eq!(barrett.modulus(), m);
+
+            for x in [
+                0,
+                1,
+                m as u128 - 1,
+                m as u128,
+                u64::MAX as u128,
+                u128::MAX,
+                1 << 127,
+            ] {
+                assert_eq!(barrett.reduce(x) as u128, x % m as u128, "{x} mod {m}");
+            }
+
+            for (a, b) in [
+                (0, 5),
+                (m - 1, m - 1),
+                (u64::MAX, u64::MAX),
+                (12_345, 678_910),
+            ] {
+                assert_eq!(barrett.mul(a, b), mul_mod(a, b, m));
+                assert_eq!(barrett.pow(a, b), mod_pow(a, b, m));
+            }
+        }
+    }
+
+    #[test]
+    fn montgomery_01() {
+        for n in [1, 3, 7, 1_000_000_007, 18_446_744_073_709_551_557, u64::MAX] {
+            let mont = Montgomery::new(n);
+
+            assert_eq!(mont.modulus(), n);
+            assert_eq!(mont.from_montgomery(mont.one()), 1 % n);
+
+            for (a, b) in [
+                (0, 0),
+                (1, n - 1),
+                (n - 1, n - 1),
+                (12_345, 678_910),
+                (u64::MAX, 2),
+            ] {
+                let (x, y) = (mont.to_montgomery(a), mont.to_montgomery(b));
+
+                assert_eq!(mont.from_montgomery(x), a % n);
+                assert_eq!(mont.from_montgomery(mont.mul(x, y)), mul_mod(a, b, n));
+                assert_eq!(
+                    mont.from_montgomery(mont.add(x, y)),
+                    add_mod(a % n, b % n, n)
+                );
+                assert_eq!(
+                    mont.from_montgomery(mont.sub(x, y)),
+                    sub_mod(a % n, b % n, n)
+                );
+                assert_eq!(mont.from_montgomery(mont.pow(x, b)), mod_pow(a, b, n));
+            }
+        }
+    }
+}
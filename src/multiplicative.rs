@@ -0,0 +1,361 @@
+use std::ops::{Add, Mul};
+
+use crate::sieve::sieve_primes;
+use crate::{factorization, Factorization};
+
+/// An arithmetic function with `f(1) = 1` and `f(mn) = f(m) f(n)` whenever `gcd(m, n) = 1`.
+///
+/// Implementors only define the value on prime powers. Evaluation at arbitrary `n`, over whole
+/// ranges, and Dirichlet convolution all follow from that.
+pub trait MultiplicativeFunction {
+    type Output: Copy + Mul<Output = Self::Output> + From<u8>;
+
+    /// The value at `p^e`, where `p` is prime and `e >= 1`.
+    fn prime_power(&self, p: u64, e: u32) -> Self::Output;
+
+    fn evaluate_factorization(&self, factorization: &Factorization) -> Self::Output {
+        factorization
+            .iter()
+            .fold(Self::Output::from(1), |acc, &(p, e)| {
+                acc * self.prime_power(p, e)
+            })
+    }
+
+    /// The value at `n`, which must be nonzero.
+    fn evaluate(&self, n: u64) -> Self::Output {
+        assert!(n != 0, "multiplicative functions are not defined at 0");
+
+        self.evaluate_factorization(&factorization(n))
+    }
+
+    /// The values at every `n` in `lo..hi`, found by sieving the window rather than factoring
+    /// each element. `lo` must be nonzero.
+    fn evaluate_range(&self, lo: u64, hi: u64) -> Vec<Self::Output> {
+        assert!(lo != 0, "multiplicative functions are not defined at 0");
+
+        if hi <= lo {
+            return vec![];
+        }
+
+        let len = (hi - lo) as usize;
+        let mut remaining: Vec<u64> = (lo..hi).collect();
+        let mut values = vec![Self::Output::from(1); len];
+
+        for p in sieve_primes((hi - 1).isqrt()) {
+            let mut i = (lo.div_ceil(p) * p - lo) as usize;
+
+            while i < len {
+                let mut e = 0;
+
+                while remaining[i].is_multiple_of(p) {
+                    remaining[i] /= p;
+                    e += 1;
+                }
+
+                values[i] = values[i] * self.prime_power(p, e);
+                i += p as usize;
+            }
+        }
+
+        for (value, &rest) in values.iter_mut().zip(&remaining) {
+            if rest > 1 {
+                *value = *value * self.prime_power(rest, 1);
+            }
+        }
+
+        values
+    }
+}
+
+/// Euler's totient function φ.
+#[derive(Clone, Copy, Debug)]
+pub struct Totient;
+
+impl MultiplicativeFunction for Totient {
+    type Output = u64;
+
+    fn prime_power(&self, p: u64, e: u32) -> u64 {
+        (p - 1) * p.pow(e - 1)
+    }
+}
+
+/// The Möbius function μ.
+#[derive(Clone, Copy, Debug)]
+pub struct Mobius;
+
+impl MultiplicativeFunction for Mobius {
+    type Output = i64;
+
+    fn prime_power(&self, _p: u64, e: u32) -> i64 {
+        if e == 1 {
+            -1
+        } else {
+            0
+        }
+    }
+}
+
+/// Liouville's function λ.
+#[derive(Clone, Copy, Debug)]
+pub struct Liouville;
+
+impl MultiplicativeFunction for Liouville {
+    type Output = i64;
+
+    fn prime_power(&self, _p: u64, e: u32) -> i64 {
+        if e % 2 == 1 {
+            -1
+        } else {
+            1
+        }
+    }
+}
+
+/// The divisor function σ_k, the sum of the `k`-th powers of the divisors.
+#[derive(Clone, Copy, Debug)]
+pub struct DivisorSigma(pub u32);
+
+impl MultiplicativeFunction for DivisorSigma {
+    type Output = u64;
+
+    fn prime_power(&self, p: u64, e: u32) -> u64 {
+        let q = p.pow(self.0);
+
+        (0..=e).fold(0, |acc, _| acc * q + 1)
+    }
+}
+
+/// The number of divisors d(n), equal to σ_0.
+#[derive(Clone, Copy, Debug)]
+pub struct DivisorCount;
+
+impl MultiplicativeFunction for DivisorCount {
+    type Output = u64;
+
+    fn prime_power(&self, _p: u64, e: u32) -> u64 {
+        e as u64 + 1
+    }
+}
+
+/// The identity function `n -> n`.
+#[derive(Clone, Copy, Debug)]
+pub struct Identity;
+
+impl MultiplicativeFunction for Identity {
+    type Output = u64;
+
+    fn prime_power(&self, p: u64, e: u32) -> u64 {
+        p.pow(e)
+    }
+}
+
+/// The constant function `n -> 1`.
+#[derive(Clone, Copy, Debug)]
+pub struct One;
+
+impl MultiplicativeFunction for One {
+    type Output = u64;
+
+    fn prime_power(&self, _p: u64, _e: u32) -> u64 {
+        1
+    }
+}
+
+/// The Dirichlet convolution `(f * g)(n) = Σ_(d | n) f(d) g(n / d)`, itself multiplicative.
+#[derive(Clone, Copy, Debug)]
+pub struct DirichletConvolution<F, G> {
+    f: F,
+    g: G,
+}
+
+impl<F, G> MultiplicativeFunction for DirichletConvolution<F, G>
+where
+    F: MultiplicativeFunction,
+    F::Output: Add<Output = F::Output>,
+    G: MultiplicativeFunction<Output = F::Output>,
+{
+    type Output = F::Output;
+
+    fn prime_power(&self, p: u64, e: u32) -> F::Output {
+        let one = F::Output::from(1);
+        let f = |i| {
+            if i == 0 {
+                one
+            } else {
+                self.f.prime_power(p, i)
+            }
+        };
+        let g = |i| {
+            if i == 0 {
+                one
+            } else {
+                self.g.prime_power(p, i)
+            }
+        };
+
+        (0..=e)
+            .map(|i| f(i) * g(e - i))
+            .reduce(|a, b| a + b)
+            .unwrap()
+    }
+}
+
+pub fn dirichlet_convolution<F, G>(f: F, g: G) -> DirichletConvolution<F, G> {
+    DirichletConvolution { f, g }
+}
+
+/// σ_k(n) for every `n` in `lo..hi`, from one sieving pass over the window. `lo` must be nonzero.
+pub fn sigma_range(lo: u64, hi: u64, k: u32) -> Vec<u64> {
+    DivisorSigma(k).evaluate_range(lo, hi)
+}
+
+/// The number of divisors d(n) of every `n` in `lo..hi`, sieved over the window one prime power
+/// at a time. `lo` must be nonzero.
+pub fn divisor_count_range(lo: u64, hi: u64) -> Vec<u64> {
+    assert!(lo != 0, "multiplicative functions are not defined at 0");
+
+    if hi <= lo {
+        return vec![];
+    }
+
+    let len = (hi - lo) as usize;
+    let mut counts = vec![1u64; len];
+    // The part of each n made of the prime powers sieved so far.
+    let mut found = vec![1u64; len];
+
+    for p in sieve_primes((hi - 1).isqrt()) {
+        let mut q = p;
+        let mut k = 1;
+
+        // At the multiples of p^k, the exponent of p is at least k, so the factor k of d(n)
+        // contributed by p^(k - 1) becomes k + 1.
+        loop {
+            let mut i = (lo.div_ceil(q) * q - lo) as usize;
+
+            while i < len {
+                counts[i] = counts[i] / k * (k + 1);
+                found[i] *= p;
+                i += q as usize;
+            }
+
+            match q.checked_mul(p) {
+                Some(next) if next < hi => q = next,
+                _ => break,
+            }
+
+            k += 1;
+        }
+    }
+
+    // Whatever is left of each n is 1 or a single prime above sqrt(hi).
+    for ((count, &f), n) in counts.iter_mut().zip(&found).zip(lo..) {
+        if f != n {
+            *count *= 2;
+        }
+    }
+
+    counts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn divisors(n: u64) -> Vec<u64> {
+        (1..=n).filter(|&d| n.is_multiple_of(d)).collect()
+    }
+
+    fn gcd(a: u64, b: u64) -> u64 {
+        if b == 0 {
+            a
+        } else {
+            gcd(b, a % b)
+        }
+    }
+
+    #[test]
+    fn multiplicative_01() {
+        for n in 1..200u64 {
+            let ds = divisors(n);
+            let mobius_sum: i64 = ds.iter().map(|&d| Mobius.evaluate(d)).sum();
+
+            assert_eq!(
+                Totient.evaluate(n),
+                (1..=n).filter(|&k| gcd(n, k) == 1).count() as u64
+            );
+            assert_eq!(DivisorCount.evaluate(n), ds.len() as u64);
+            assert_eq!(
+                DivisorSigma(2).evaluate(n),
+                ds.iter().map(|d| d * d).sum::<u64>()
+            );
+            assert_eq!(mobius_sum, if n == 1 { 1 } else { 0 });
+        }
+
+        assert_eq!(Mobius.evaluate(30), -1);
+        assert_eq!(Mobius.evaluate(12), 0);
+        assert_eq!(Liouville.evaluate(12), -1);
+    }
+
+    #[test]
+    fn multiplicative_02() {
+        // Σ_(d | n) φ(d) = n, 1 * 1 = d and Id * 1 = σ.
+        let phi_one = dirichlet_convolution(Totient, One);
+        let one_one = dirichlet_convolution(One, One);
+        let id_one = dirichlet_convolution(Identity, One);
+
+        for n in 1..500 {
+            assert_eq!(phi_one.evaluate(n), n);
+            assert_eq!(one_one.evaluate(n), DivisorCount.evaluate(n));
+            assert_eq!(id_one.evaluate(n), DivisorSigma(1).evaluate(n));
+        }
+    }
+
+    #[test]
+    fn multiplicative_range_01() {
+        let lo = 100_000;
+        let hi = 101_000;
+        let phis = Totient.evaluate_range(lo, hi);
+        let mus = Mobius.evaluate_range(1, 1000);
+
+        for (n, &phi) in (lo..hi).zip(&phis) {
+            assert_eq!(phi, Totient.evaluate(n));
+        }
+
+        for (n, &mu) in (1..1000).zip(&mus) {
+            assert_eq!(mu, Mobius.evaluate(n));
+        }
+
+        assert!(Totient.evaluate_range(10, 10).is_empty());
+    }
+
+    #[test]
+    fn sigma_range_01() {
+        let lo = 1_000_000;
+        let hi = 1_001_000;
+
+        for k in 0..3 {
+            for (n, &s) in (lo..hi).zip(&sigma_range(lo, hi, k)) {
+                assert_eq!(s, DivisorSigma(k).evaluate(n));
+            }
+        }
+
+        assert_eq!(sigma_range(1, 7, 1), vec![1, 3, 4, 7, 6, 12]);
+        assert_eq!(sigma_range(1, 2000, 0), divisor_count_range(1, 2000));
+    }
+
+    #[test]
+    fn divisor_count_range_01() {
+        for (lo, hi) in [(1, 5000), (1 << 40, (1 << 40) + 2000)] {
+            let counts = divisor_count_range(lo, hi);
+
+            assert_eq!(counts.len(), (hi - lo) as usize);
+
+            for (n, &d) in (lo..hi).zip(&counts) {
+                assert_eq!(d, DivisorCount.evaluate(n), "{n}");
+            }
+        }
+
+        assert_eq!(divisor_count_range(720_720, 720_721), vec![240]);
+        assert!(divisor_count_range(10, 10).is_empty());
+    }
+}
@@ -0,0 +1,236 @@
+use crate::modular::{add_mod_u128, jacobi_u128, mod_pow_u128, mul_mod_u128, sub_mod_u128};
+use crate::Montgomery;
+
+// These seven bases are sufficient for a deterministic test over all of u64 (Sinclair, 2011).
+const MILLER_RABIN_BASES: [u64; 7] = [2, 325, 9375, 28178, 450775, 9780504, 1795265022];
+
+const SMALL_PRIMES: [u64; 12] = [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37];
+
+// The strong probable prime test to the given base, for odd n in the Montgomery context mont.
+fn is_strong_probable_prime(mont: &Montgomery, base: u64) -> bool {
+    let n = mont.modulus();
+    let base = base % n;
+
+    if base == 0 {
+        return true;
+    }
+
+    let one = mont.one();
+    let minus_one = mont.sub(0, one);
+    let s = (n - 1).trailing_zeros();
+    let d = (n - 1) >> s;
+    let mut x = mont.pow(mont.to_montgomery(base), d);
+
+    if x == one || x == minus_one {
+        return true;
+    }
+
+    for _ in 1..s {
+        x = mont.mul(x, x);
+
+        if x == minus_one {
+            return true;
+        }
+    }
+
+    false
+}
+
+/// Deterministic Miller–Rabin primality test, valid for every `u64`.
+pub(crate) fn miller_rabin(n: u64) -> bool {
+    if n < 2 {
+        return false;
+    }
+
+    for &p in &SMALL_PRIMES {
+        if n.is_multiple_of(p) {
+            return n == p;
+        }
+    }
+
+    if n < 41 * 41 {
+        return true;
+    }
+
+    let mont = Montgomery::new(n);
+
+    MILLER_RABIN_BASES
+        .iter()
+        .all(|&base| is_strong_probable_prime(&mont, base))
+}
+
+fn is_strong_probable_prime_u128(n: u128, base: u128) -> bool {
+    let s = (n - 1).trailing_zeros();
+    let d = (n - 1) >> s;
+    let mut x = mod_pow_u128(base, d, n);
+
+    if x == 1 || x == n - 1 {
+        return true;
+    }
+
+    for _ in 1..s {
+        x = mul_mod_u128(x, x, n);
+
+        if x == n - 1 {
+            return true;
+        }
+    }
+
+    false
+}
+
+// The strong Lucas probable prime test with Selfridge's parameters: the first D in 5, −7, 9,
+// −11, ... with (D | n) = −1, P = 1 and Q = (1 − D) / 4. n must be odd and not a square.
+fn is_strong_lucas_probable_prime(n: u128) -> bool {
+    // Reduces a small signed value modulo n, which may not fit in an i128.
+    let modular = |x: i128| {
+        if x >= 0 {
+            x as u128 % n
+        } else {
+            sub_mod_u128(0, x.unsigned_abs() % n, n)
+        }
+    };
+
+    let mut d: i128 = 5;
+
+    loop {
+        match jacobi_u128(modular(d), n) {
+            -1 => break,
+            0 if d.unsigned_abs() != n => return false,
+            _ => d = if d > 0 { -d - 2 } else { -d + 2 },
+        }
+    }
+
+    let half = |x: u128| {
+        if x.is_multiple_of(2) {
+            x / 2
+        } else {
+            x / 2 + n / 2 + 1
+        }
+    };
+
+    let d_mod = modular(d);
+    let q = modular((1 - d) / 4);
+    let s = (n + 1).trailing_zeros();
+    let k = (n + 1) >> s;
+
+    // U_k, V_k and Q^k, by a left-to-right ladder over the bits of k with P = 1.
+    let mut u = 0;
+    let mut v = 2;
+    let mut q_k = 1;
+
+    for bit in (0..128 - k.leading_zeros()).rev() {
+        u = mul_mod_u128(u, v, n);
+        v = sub_mod_u128(mul_mod_u128(v, v, n), add_mod_u128(q_k, q_k, n), n);
+        q_k = mul_mod_u128(q_k, q_k, n);
+
+        if k >> bit & 1 == 1 {
+            let next_u = half(add_mod_u128(u, v, n));
+
+            v = half(add_mod_u128(mul_mod_u128(d_mod, u, n), v, n));
+            u = next_u;
+            q_k = mul_mod_u128(q_k, q, n);
+        }
+    }
+
+    if u == 0 || v == 0 {
+        return true;
+    }
+
+    for _ in 1..s {
+        v = sub_mod_u128(mul_mod_u128(v, v, n), add_mod_u128(q_k, q_k, n), n);
+        q_k = mul_mod_u128(q_k, q_k, n);
+
+        if v == 0 {
+            return true;
+        }
+    }
+
+    false
+}
+
+/// The Baillie–PSW test for `u128`: Miller–Rabin to base 2 and a strong Lucas test. There is no
+/// known composite that passes both, and none exists below 2^64, where the deterministic test is
+/// used instead.
+pub(crate) fn baillie_psw(n: u128) -> bool {
+    if n <= u64::MAX as u128 {
+        return miller_rabin(n as u64);
+    }
+
+    if SMALL_PRIMES.iter().any(|&p| n.is_multiple_of(p as u128)) {
+        return false;
+    }
+
+    let root = n.isqrt();
+
+    if root * root == n {
+        return false;
+    }
+
+    is_strong_probable_prime_u128(n, 2) && is_strong_lucas_probable_prime(n)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::primes_upto;
+
+    #[test]
+    fn miller_rabin_01() {
+        let ps: Vec<_> = primes_upto(10_000).collect();
+
+        for n in 0..10_000 {
+            assert_eq!(miller_rabin(n), ps.binary_search(&n).is_ok());
+        }
+    }
+
+    #[test]
+    fn miller_rabin_02() {
+        // Carmichael numbers, strong pseudoprimes to several small bases, and primes near
+        // u64::MAX.
+        assert!(!miller_rabin(561));
+        assert!(!miller_rabin(3_215_031_751));
+        assert!(!miller_rabin(3_825_123_056_546_413_051));
+        assert!(!miller_rabin(1_000_000_007 * 998_244_353));
+        assert!(miller_rabin(1_000_000_007));
+        assert!(miller_rabin(18_446_744_073_709_551_557));
+        assert!(!miller_rabin(18_446_744_073_709_551_559));
+    }
+
+    #[test]
+    fn baillie_psw_01() {
+        // Strong pseudoprimes to base 2 and strong Lucas pseudoprimes, checked below 2^64 with
+        // the Lucas test alone.
+        for n in [2047u128, 3277, 4033, 4681, 8321, 3_215_031_751] {
+            assert!(!baillie_psw(n));
+            assert!(is_strong_probable_prime_u128(n, 2));
+        }
+
+        for n in [5459u128, 5777, 10_877, 16_109, 18_971, 22_499] {
+            assert!(is_strong_lucas_probable_prime(n));
+            assert!(!baillie_psw(n));
+        }
+
+        for n in (3..10_000u128).step_by(2) {
+            let root = n.isqrt();
+
+            if root * root != n {
+                assert_eq!(
+                    is_strong_lucas_probable_prime(n) && is_strong_probable_prime_u128(n, 2),
+                    miller_rabin(n as u64),
+                    "{n}"
+                );
+            }
+        }
+
+        // 2^127 − 1, 2^89 − 1, the largest u128 prime 2^128 − 159, and products of large primes.
+        assert!(baillie_psw((1 << 127) - 1));
+        assert!(baillie_psw((1 << 89) - 1));
+        assert!(baillie_psw(u128::MAX - 158));
+        assert!(!baillie_psw(u128::MAX));
+        assert!(!baillie_psw(
+            18_446_744_073_709_551_557 * 18_446_744_073_709_551_533
+        ));
+        assert!(!baillie_psw((1 << 127) + 1));
+    }
+}
@@ -0,0 +1,46 @@
+use crate::{primes_upto, Factorization};
+
+/// The product of all primes `<= n`, or `None` if it does not fit in a `u64`.
+pub fn primorial(n: u64) -> Option<u64> {
+    primes_upto(n).try_fold(1u64, |acc, p| acc.checked_mul(p))
+}
+
+/// The product of all primes `<= n`, or `None` if it does not fit in a `u128`.
+pub fn primorial_u128(n: u64) -> Option<u128> {
+    primes_upto(n).try_fold(1u128, |acc, p| acc.checked_mul(p as u128))
+}
+
+/// The primorial of `n` in factored form, which never overflows.
+pub fn primorial_factorization(n: u64) -> Factorization {
+    primes_upto(n).map(|p| (p, 1)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn primorial_01() {
+        assert_eq!(primorial(0), Some(1));
+        assert_eq!(primorial(1), Some(1));
+        assert_eq!(primorial(2), Some(2));
+        assert_eq!(primorial(10), Some(210));
+        assert_eq!(primorial(52), Some(614889782588491410));
+        assert_eq!(primorial(53), None);
+    }
+
+    #[test]
+    fn primorial_02() {
+        assert_eq!(primorial_u128(53), Some(32589158477190044730));
+        assert_eq!(
+            primorial_u128(102),
+            Some(232862364358497360900063316880507363070)
+        );
+        assert_eq!(primorial_u128(103), None);
+
+        let f = primorial_factorization(1000);
+
+        assert_eq!(f.len(), 168);
+        assert!(f.iter().all(|&(_, e)| e == 1));
+    }
+}
@@ -0,0 +1,1686 @@
+#[cfg(feature = "bigint")]
+use std::ops::Deref;
+use std::ops::{Bound, RangeBounds};
+
+#[cfg(feature = "bigint")]
+use num_bigint::BigUint;
+#[cfg(feature = "bigint")]
+use num_integer::Integer;
+#[cfg(feature = "bigint")]
+use num_traits::{One, ToPrimitive, Zero};
+use rand::{CryptoRng, Rng, RngCore, SeedableRng};
+use rand_chacha::ChaCha20Rng;
+
+#[cfg(feature = "bigint")]
+use crate::factor::is_strong_probable_prime_biguint;
+use crate::factor::trial_primes;
+#[cfg(all(feature = "bigint", feature = "zeroize"))]
+use crate::factor::wipe_biguint;
+use crate::modular::{gcd, mod_pow};
+use crate::{is_prime, multiplicative_order};
+#[cfg(feature = "bigint")]
+use crate::{is_prime_biguint, PrimeCertificate};
+
+// Below this width, ranges are scanned outright, since they may hold few primes or none. Every
+// wider range below 2^64 holds a prime, as no gap between consecutive primes there reaches 1550.
+const SCAN_WIDTH: u64 = 1 << 16;
+
+// Safe primes of at most this many bits are listed outright, since sieving would reject the
+// small primes among them.
+const LISTED_SAFE_PRIME_BITS: u32 = 16;
+
+// Gordon's primes s and t have about half the bits of p less this many, leaving room for the
+// multiplier that brings p up to size.
+const GORDON_SLACK_BITS: u64 = 8;
+
+/// A target probability of accepting a composite, `2^-k` for `SecurityLevel(k)`, from which
+/// [`miller_rabin_rounds`](SecurityLevel::miller_rabin_rounds) sets how many rounds of
+/// Miller–Rabin to random bases a random candidate needs.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct SecurityLevel(pub u32);
+
+impl SecurityLevel {
+    pub const BITS_80: SecurityLevel = SecurityLevel(80);
+    pub const BITS_112: SecurityLevel = SecurityLevel(112);
+    pub const BITS_128: SecurityLevel = SecurityLevel(128);
+
+    /// The fewest rounds of Miller–Rabin to independent random bases after which a random odd
+    /// `bits`-bit candidate that passes every round is composite with probability at most
+    /// `2^-k`, by the bounds of Damgård, Landrock and Pomerance (1993), or Rabin's `4^-t` where
+    /// those give less. Larger candidates need far fewer rounds: 6 for 512 bits at `2^-80`, and
+    /// 27 for 100 bits.
+    pub fn miller_rabin_rounds(self, bits: u64) -> usize {
+        (1..)
+            .find(|&t| log2_error_bound(bits as f64, t as f64) <= -(self.0 as f64))
+            .unwrap()
+    }
+}
+
+impl Default for SecurityLevel {
+    fn default() -> SecurityLevel {
+        SecurityLevel::BITS_128
+    }
+}
+
+// log2 of the least upper bound on p_{k,t}, the probability that a random odd k-bit number
+// passing t rounds is composite, from Rabin's bound and Damgård, Landrock and Pomerance's.
+fn log2_error_bound(k: f64, t: f64) -> f64 {
+    let log_k = k.log2();
+    let mut bound = -2.0 * t;
+
+    if t == 1.0 && k >= 2.0 {
+        bound = bound.min(2.0 * log_k + 2.0 * (2.0 - k.sqrt()));
+    }
+
+    if k >= 21.0 {
+        if t >= 3.0 && t <= k / 9.0 {
+            bound = bound.min(1.5 * log_k + t - 0.5 * t.log2() + 2.0 * (2.0 - (t * k).sqrt()));
+        }
+
+        if t >= k / 9.0 {
+            let terms = [
+                (7.0 / 20.0 * k).log2() - 5.0 * t,
+                (1.0f64 / 7.0).log2() + 3.75 * log_k - k / 2.0 - 2.0 * t,
+                (12.0 * k).log2() - k / 4.0 - 3.0 * t,
+            ];
+            let max = terms.iter().cloned().fold(f64::MIN, f64::max);
+
+            bound = bound.min(max + terms.iter().map(|x| (x - max).exp2()).sum::<f64>().log2());
+        }
+
+        if t >= k / 4.0 {
+            bound = bound.min((1.0f64 / 7.0).log2() + 3.75 * log_k - k / 2.0 - 2.0 * t);
+        }
+    }
+
+    bound
+}
+
+/// A deterministic random bit generator, ChaCha20 keyed by a seed, for reproducible primes.
+/// Every generator in this crate draws from its `Rng` with fixed-width operations only, so a
+/// given seed yields the same primes on every run and platform, for test fixtures and
+/// benchmarks. With the `zeroize` feature, its state is wiped when it is dropped.
+#[derive(Clone, Debug)]
+pub struct Drbg(ChaCha20Rng);
+
+impl Drbg {
+    pub fn new(seed: [u8; 32]) -> Drbg {
+        Drbg(ChaCha20Rng::from_seed(seed))
+    }
+
+    /// Keyed by the little-endian bytes of `seed` followed by zeros.
+    pub fn from_u64(seed: u64) -> Drbg {
+        let mut key = [0; 32];
+
+        key[..8].copy_from_slice(&seed.to_le_bytes());
+        Drbg::new(key)
+    }
+}
+
+impl RngCore for Drbg {
+    fn next_u32(&mut self) -> u32 {
+        self.0.next_u32()
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.0.next_u64()
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.0.fill_bytes(dest);
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+        self.0.try_fill_bytes(dest)
+    }
+}
+
+impl CryptoRng for Drbg {}
+
+#[cfg(feature = "zeroize")]
+impl zeroize::Zeroize for Drbg {
+    fn zeroize(&mut self) {
+        // ChaCha20Rng has no way to clear its key and buffered output in place, so the whole
+        // state is overwritten by a volatile write, which the compiler cannot drop as dead.
+        //
+        // SAFETY: the pointer comes from a reference, so it is valid and aligned, and the
+        // overwritten ChaCha20Rng owns no heap memory that skipping its drop would leak.
+        unsafe { std::ptr::write_volatile(&mut self.0, ChaCha20Rng::from_seed([0; 32])) };
+        std::sync::atomic::compiler_fence(std::sync::atomic::Ordering::SeqCst);
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl Drop for Drbg {
+    fn drop(&mut self) {
+        zeroize::Zeroize::zeroize(self);
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl zeroize::ZeroizeOnDrop for Drbg {}
+
+// A BigUint candidate or intermediate that could reveal a secret prime, wiped when it is
+// dropped with the zeroize feature. into_inner takes out a value to keep. Temporaries inside
+// num-bigint's own arithmetic and the primality tests are freed without wiping.
+#[cfg(feature = "bigint")]
+pub(crate) struct Wiped(pub(crate) BigUint);
+
+#[cfg(feature = "bigint")]
+impl Wiped {
+    pub(crate) fn into_inner(mut self) -> BigUint {
+        std::mem::take(&mut self.0)
+    }
+}
+
+#[cfg(feature = "bigint")]
+impl Deref for Wiped {
+    type Target = BigUint;
+
+    fn deref(&self) -> &BigUint {
+        &self.0
+    }
+}
+
+#[cfg(all(feature = "bigint", feature = "zeroize"))]
+impl Drop for Wiped {
+    fn drop(&mut self) {
+        wipe_biguint(&mut self.0);
+    }
+}
+
+// A uniform index below len, drawn as a u64 so it does not depend on the width of usize.
+fn index<R: Rng + ?Sized>(len: usize, rng: &mut R) -> usize {
+    rng.gen_range(0..len as u64) as usize
+}
+
+/// A prime drawn uniformly from the primes in `range`, or `None` if it holds none. Narrow ranges
+/// are scanned and one of their primes chosen; wider ones are sampled uniformly until a candidate
+/// passes the deterministic primality test, which takes about `ln n` draws near `n`.
+pub fn random_prime_in<R: Rng + ?Sized>(range: impl RangeBounds<u64>, rng: &mut R) -> Option<u64> {
+    let low = match range.start_bound() {
+        Bound::Included(&low) => low,
+        Bound::Excluded(&low) => low.checked_add(1)?,
+        Bound::Unbounded => 0,
+    };
+
+    let high = match range.end_bound() {
+        Bound::Included(&high) => high,
+        Bound::Excluded(&high) => high.checked_sub(1)?,
+        Bound::Unbounded => u64::MAX,
+    };
+
+    if low > high {
+        return None;
+    }
+
+    if high - low < SCAN_WIDTH {
+        let candidates: Vec<u64> = (low..=high).filter(|&n| is_prime(n)).collect();
+
+        return (!candidates.is_empty()).then(|| candidates[index(candidates.len(), rng)]);
+    }
+
+    loop {
+        let n = rng.gen_range(low..=high);
+
+        if is_prime(n) {
+            return Some(n);
+        }
+    }
+}
+
+/// A prime drawn uniformly from those with exactly `bits` bits, so its top bit is set. Panics
+/// unless `2 <= bits <= 64`.
+pub fn random_prime_bits<R: Rng + ?Sized>(bits: u32, rng: &mut R) -> u64 {
+    assert!(
+        (2..=64).contains(&bits),
+        "no {bits}-bit prime fits in a u64"
+    );
+
+    random_prime_in(1 << (bits - 1)..=u64::MAX >> (64 - bits), rng).unwrap()
+}
+
+/// A random prime with exactly `bits` bits, for any `bits >= 2`. Above 64 bits, candidates are
+/// odd with the top bit forced, and must survive trial division, the Baillie–PSW test and
+/// Miller–Rabin to as many random bases as the default [`SecurityLevel`] needs. Panics if
+/// `bits < 2`.
+#[cfg(feature = "bigint")]
+pub fn random_prime_bits_biguint<R: Rng + ?Sized>(bits: u64, rng: &mut R) -> BigUint {
+    assert!(bits >= 2, "no prime has fewer than 2 bits");
+
+    if bits <= 64 {
+        return BigUint::from(random_prime_bits(bits as u32, rng));
+    }
+
+    loop {
+        let candidate =
+            Wiped(random_biguint(bits, rng) | BigUint::one() << (bits - 1) | BigUint::one());
+
+        if passes_prime_tests(&candidate, rng) {
+            return candidate.into_inner();
+        }
+    }
+}
+
+/// A random safe prime `p` with exactly `bits` bits, one where `(p − 1) / 2` is also prime.
+/// Candidates `q ≡ 5 (mod 6)` for `(p − 1) / 2` are stepped through from a random start, and
+/// sieved at once for small factors of both `q` and `2 q + 1`, so only pairs where neither has
+/// one are tested. Panics unless `3 <= bits <= 64`.
+pub fn random_safe_prime<R: Rng + ?Sized>(bits: u32, rng: &mut R) -> u64 {
+    assert!(
+        (3..=64).contains(&bits),
+        "no {bits}-bit safe prime fits in a u64"
+    );
+
+    let low = 1 << (bits - 1);
+    let high = u64::MAX >> (64 - bits);
+
+    if bits <= LISTED_SAFE_PRIME_BITS {
+        let safe: Vec<u64> = (low..=high)
+            .filter(|&p| is_prime(p) && is_prime(p / 2))
+            .collect();
+
+        return safe[index(safe.len(), rng)];
+    }
+
+    loop {
+        let start = rng.gen_range(low / 2..=high / 2);
+        let start = start - start % 6 + 5;
+        let residues = sieve_residues(|r| start % r);
+        let mut offset = 0;
+
+        while let Some(q) = start.checked_add(offset).filter(|&q| q <= high / 2) {
+            if survives_safe_sieve(&residues, offset) && is_prime(q) && is_prime(2 * q + 1) {
+                return 2 * q + 1;
+            }
+
+            offset += 6;
+        }
+    }
+}
+
+/// A random safe prime with exactly `bits` bits, for any `bits >= 3`, sieved as in
+/// [`random_safe_prime`]. Both `(p − 1) / 2` and `p` must pass the tests of
+/// [`random_prime_bits_biguint`]. Panics if `bits < 3`.
+#[cfg(feature = "bigint")]
+pub fn random_safe_prime_biguint<R: Rng + ?Sized>(bits: u64, rng: &mut R) -> BigUint {
+    assert!(bits >= 3, "no safe prime has fewer than 3 bits");
+
+    if bits <= 64 {
+        return BigUint::from(random_safe_prime(bits as u32, rng));
+    }
+
+    let two = BigUint::from(2u32);
+
+    loop {
+        let start = Wiped(random_biguint(bits - 1, rng) | BigUint::one() << (bits - 2));
+        let start = Wiped(&*start - (&*start % 6u32) + 5u32);
+        let residues = sieve_residues(|r| (&*start % r).to_u64().unwrap());
+        let mut offset = 0u64;
+
+        loop {
+            let q = Wiped(&*start + offset);
+
+            if q.bits() >= bits {
+                break;
+            }
+
+            if survives_safe_sieve(&residues, offset) {
+                let p = Wiped(&*q * 2u32 + 1u32);
+
+                // A base-2 test of both first, since most pairs fail one.
+                if is_strong_probable_prime_biguint(&q, &two)
+                    && is_strong_probable_prime_biguint(&p, &two)
+                    && passes_prime_tests(&q, rng)
+                    && passes_prime_tests(&p, rng)
+                {
+                    return p.into_inner();
+                }
+            }
+
+            offset += 6;
+        }
+    }
+}
+
+/// A prime drawn uniformly from the Blum primes with exactly `bits` bits, those `≡ 3 (mod 4)`,
+/// as Blum–Blum–Shub and the Rabin cryptosystem need. Panics unless `2 <= bits <= 64`.
+pub fn random_blum_prime<R: Rng + ?Sized>(bits: u32, rng: &mut R) -> u64 {
+    assert!(
+        (2..=64).contains(&bits),
+        "no {bits}-bit prime fits in a u64"
+    );
+
+    let low = 1u64 << (bits - 1);
+    let high = u64::MAX >> (64 - bits);
+
+    loop {
+        let p = rng.gen_range(low / 4..=high / 4) * 4 + 3;
+
+        if is_prime(p) {
+            return p;
+        }
+    }
+}
+
+/// A random Blum prime with exactly `bits` bits, for any `bits >= 2`, passing the tests of
+/// [`random_prime_bits_biguint`]. Panics if `bits < 2`.
+#[cfg(feature = "bigint")]
+pub fn random_blum_prime_biguint<R: Rng + ?Sized>(bits: u64, rng: &mut R) -> BigUint {
+    assert!(bits >= 2, "no prime has fewer than 2 bits");
+
+    if bits <= 64 {
+        return BigUint::from(random_blum_prime(bits as u32, rng));
+    }
+
+    loop {
+        let candidate =
+            Wiped(random_biguint(bits, rng) | BigUint::one() << (bits - 1) | BigUint::from(3u32));
+
+        if passes_prime_tests(&candidate, rng) {
+            return candidate.into_inner();
+        }
+    }
+}
+
+/// A prime drawn uniformly from those `≡ a (mod m)` with exactly `bits` bits, or `None` if
+/// there is none. Candidates are drawn from the progression `a mod m, a mod m + m, …` itself,
+/// never by rejecting primes in the wrong class, so the choice stays uniform. When `a` and `m`
+/// share a factor `g`, the only prime in the class can be `g` itself. Panics if `m` is 0 or
+/// unless `2 <= bits <= 64`.
+pub fn random_prime_congruent<R: Rng + ?Sized>(
+    a: u64,
+    m: u64,
+    bits: u32,
+    rng: &mut R,
+) -> Option<u64> {
+    assert!(m != 0, "modulus must be nonzero");
+    assert!(
+        (2..=64).contains(&bits),
+        "no {bits}-bit prime fits in a u64"
+    );
+
+    let a = a % m;
+    let low = 1u64 << (bits - 1);
+    let high = u64::MAX >> (64 - bits);
+    let g = gcd(a, m);
+
+    if g != 1 {
+        return (g % m == a && (low..=high).contains(&g) && is_prime(g)).then_some(g);
+    }
+
+    // The terms a + k m in [low, high].
+    let k_low = low.saturating_sub(a).div_ceil(m);
+    let k_high = high.checked_sub(a)? / m;
+
+    if k_low > k_high {
+        return None;
+    }
+
+    if k_high - k_low < SCAN_WIDTH {
+        let candidates: Vec<u64> = (k_low..=k_high)
+            .map(|k| a + k * m)
+            .filter(|&n| is_prime(n))
+            .collect();
+
+        return (!candidates.is_empty()).then(|| candidates[index(candidates.len(), rng)]);
+    }
+
+    loop {
+        let n = a + rng.gen_range(k_low..=k_high) * m;
+
+        if is_prime(n) {
+            return Some(n);
+        }
+    }
+}
+
+/// A random prime `≡ a (mod m)` with exactly `bits` bits, for any `bits >= 2`, passing the
+/// tests of [`random_prime_bits_biguint`], or `None` if there is none. Panics if `m` is 0 or
+/// `bits < 2`.
+#[cfg(feature = "bigint")]
+pub fn random_prime_congruent_biguint<R: Rng + ?Sized>(
+    a: &BigUint,
+    m: &BigUint,
+    bits: u64,
+    rng: &mut R,
+) -> Option<BigUint> {
+    assert!(!m.is_zero(), "modulus must be nonzero");
+    assert!(bits >= 2, "no prime has fewer than 2 bits");
+
+    let a = a % m;
+
+    if bits <= 64 {
+        if let (Some(a), Some(m)) = (a.to_u64(), m.to_u64()) {
+            return random_prime_congruent(a, m, bits as u32, rng).map(BigUint::from);
+        }
+    }
+
+    let low = BigUint::one() << (bits - 1);
+    let high = (BigUint::one() << bits) - 1u32;
+    let g = a.gcd(m);
+
+    if !g.is_one() {
+        return (g == a && low <= g && g <= high && passes_prime_tests(&g, rng)).then_some(g);
+    }
+
+    if a > high {
+        return None;
+    }
+
+    let k_low = if low > a {
+        (&low - &a).div_ceil(m)
+    } else {
+        BigUint::zero()
+    };
+    let k_high = (&high - &a) / m;
+
+    if k_low > k_high {
+        return None;
+    }
+
+    let count = &k_high - &k_low + 1u32;
+
+    if count < BigUint::from(SCAN_WIDTH) {
+        let mut candidates: Vec<Wiped> = (0..count.to_u64().unwrap())
+            .map(|k| Wiped(&a + (&k_low + k) * m))
+            .filter(|n| passes_prime_tests(n, rng))
+            .collect();
+
+        return (!candidates.is_empty()).then(|| {
+            candidates
+                .swap_remove(index(candidates.len(), rng))
+                .into_inner()
+        });
+    }
+
+    loop {
+        let k = Wiped(&k_low + random_biguint(count.bits() + 64, rng) % &count);
+        let candidate = Wiped(&a + &*k * m);
+
+        if passes_prime_tests(&candidate, rng) {
+            return Some(candidate.into_inner());
+        }
+    }
+}
+
+/// Diffie–Hellman group parameters: a safe prime `p = 2 q + 1` and a generator `g` of the
+/// subgroup of order `q`, the quadratic residues modulo `p`. With the `zeroize` feature they
+/// implement `Zeroize`, and `zeroize::Zeroizing` wipes them when dropped.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DhParams<T> {
+    pub p: T,
+    pub q: T,
+    pub g: T,
+}
+
+#[cfg(feature = "zeroize")]
+impl zeroize::Zeroize for DhParams<u64> {
+    fn zeroize(&mut self) {
+        self.p.zeroize();
+        self.q.zeroize();
+        self.g.zeroize();
+    }
+}
+
+#[cfg(all(feature = "bigint", feature = "zeroize"))]
+impl zeroize::Zeroize for DhParams<BigUint> {
+    fn zeroize(&mut self) {
+        wipe_biguint(&mut self.p);
+        wipe_biguint(&mut self.q);
+        wipe_biguint(&mut self.g);
+    }
+}
+
+/// Diffie–Hellman parameters with a random safe prime of exactly `bits` bits. The generator is
+/// 2 when it is a quadratic residue, for `p ≡ 7 (mod 8)`, and 4 otherwise, and its order is
+/// checked to be `q`. Panics unless `3 <= bits <= 64`.
+pub fn generate_dh_params<R: Rng + ?Sized>(bits: u32, rng: &mut R) -> DhParams<u64> {
+    let p = random_safe_prime(bits, rng);
+    let q = p / 2;
+    let g = if p % 8 == 7 { 2 } else { 4 };
+
+    assert_eq!(multiplicative_order(g, p), Some(q));
+
+    DhParams { p, q, g }
+}
+
+/// Diffie–Hellman parameters with a random safe prime of exactly `bits` bits, for any
+/// `bits >= 3`, with the generator chosen as in [`generate_dh_params`] and checked by
+/// `g^q ≡ 1`, which with `g ≠ 1` and `q` prime makes its order `q`. Panics if `bits < 3`.
+#[cfg(feature = "bigint")]
+pub fn generate_dh_params_biguint<R: Rng + ?Sized>(bits: u64, rng: &mut R) -> DhParams<BigUint> {
+    let p = random_safe_prime_biguint(bits, rng);
+    let q = &p >> 1;
+    let g = BigUint::from(if (&p % 8u32) == BigUint::from(7u32) {
+        2u32
+    } else {
+        4
+    });
+
+    assert!(g.modpow(&q, &p).is_one() && (&g % &p) != BigUint::one());
+
+    DhParams { p, q, g }
+}
+
+/// The two primes `p > q` of an RSA modulus, which together are the private key. With the
+/// `zeroize` feature they implement `Zeroize`, and `zeroize::Zeroizing` wipes them when dropped.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RsaPrimes<T> {
+    pub p: T,
+    pub q: T,
+}
+
+#[cfg(feature = "zeroize")]
+impl zeroize::Zeroize for RsaPrimes<u64> {
+    fn zeroize(&mut self) {
+        self.p.zeroize();
+        self.q.zeroize();
+    }
+}
+
+#[cfg(all(feature = "bigint", feature = "zeroize"))]
+impl zeroize::Zeroize for RsaPrimes<BigUint> {
+    fn zeroize(&mut self) {
+        wipe_biguint(&mut self.p);
+        wipe_biguint(&mut self.q);
+    }
+}
+
+// Serialized as an object with `p`, `q` and `g`. Deserializing checks that `p = 2 q + 1` with
+// both prime, and that `g` has order `q`.
+#[cfg(feature = "serde")]
+impl<T: KeyInt> serde::Serialize for DhParams<T> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeStruct;
+
+        let mut state = serializer.serialize_struct("DhParams", 3)?;
+
+        state.serialize_field("p", &Key(&self.p))?;
+        state.serialize_field("q", &Key(&self.q))?;
+        state.serialize_field("g", &Key(&self.g))?;
+        state.end()
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, T: KeyInt> serde::Deserialize<'de> for DhParams<T> {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<DhParams<T>, D::Error> {
+        const FIELDS: &[&str] = &["p", "q", "g"];
+
+        let mut values = deserializer
+            .deserialize_struct("DhParams", FIELDS, KeyFieldsVisitor::<T>::new(FIELDS))?
+            .into_iter();
+        let (p, q, g) = (
+            values.next().unwrap(),
+            values.next().unwrap(),
+            values.next().unwrap(),
+        );
+
+        if !T::is_dh_group(&p, &q, &g) || !q.is_prime_key() || !p.is_prime_key() {
+            return Err(serde::de::Error::custom(
+                "not a safe prime with a generator of order q",
+            ));
+        }
+
+        Ok(DhParams { p, q, g })
+    }
+}
+
+// Serialized as an object with `p` and `q`. Deserializing checks that `p > q` and both are
+// prime.
+#[cfg(feature = "serde")]
+impl<T: KeyInt> serde::Serialize for RsaPrimes<T> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeStruct;
+
+        let mut state = serializer.serialize_struct("RsaPrimes", 2)?;
+
+        state.serialize_field("p", &Key(&self.p))?;
+        state.serialize_field("q", &Key(&self.q))?;
+        state.end()
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, T: KeyInt> serde::Deserialize<'de> for RsaPrimes<T> {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<RsaPrimes<T>, D::Error> {
+        const FIELDS: &[&str] = &["p", "q"];
+
+        let mut values = deserializer
+            .deserialize_struct("RsaPrimes", FIELDS, KeyFieldsVisitor::<T>::new(FIELDS))?
+            .into_iter();
+        let (p, q) = (values.next().unwrap(), values.next().unwrap());
+
+        if p <= q || !p.is_prime_key() || !q.is_prime_key() {
+            return Err(serde::de::Error::custom("not two primes p > q"));
+        }
+
+        Ok(RsaPrimes { p, q })
+    }
+}
+
+// The integers that parameters and keys are made of, for serde: numbers for u64, and decimal
+// strings for BigUint as in certificates, with the checks deserializing applies to them.
+#[cfg(feature = "serde")]
+trait KeyInt: Sized + Ord {
+    fn serialize_key<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error>;
+
+    fn deserialize_key<'de, D: serde::Deserializer<'de>>(deserializer: D)
+        -> Result<Self, D::Error>;
+
+    fn is_prime_key(&self) -> bool;
+
+    // Whether p = 2 q + 1 and g^q ≡ 1 (mod p) with g ≢ 0, 1, so that g has order q for q prime.
+    fn is_dh_group(p: &Self, q: &Self, g: &Self) -> bool;
+}
+
+#[cfg(feature = "serde")]
+impl KeyInt for u64 {
+    fn serialize_key<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u64(*self)
+    }
+
+    fn deserialize_key<'de, D: serde::Deserializer<'de>>(deserializer: D) -> Result<u64, D::Error> {
+        serde::Deserialize::deserialize(deserializer)
+    }
+
+    fn is_prime_key(&self) -> bool {
+        is_prime(*self)
+    }
+
+    fn is_dh_group(&p: &u64, &q: &u64, &g: &u64) -> bool {
+        q.checked_mul(2).and_then(|q2| q2.checked_add(1)) == Some(p)
+            && g % p > 1
+            && mod_pow(g, q, p) == 1
+    }
+}
+
+#[cfg(all(feature = "bigint", feature = "serde"))]
+impl KeyInt for BigUint {
+    fn serialize_key<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+
+    fn deserialize_key<'de, D: serde::Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<BigUint, D::Error> {
+        let s = <String as serde::Deserialize>::deserialize(deserializer)?;
+
+        s.parse()
+            .map_err(|_| serde::de::Error::custom(format!("{s} is not a decimal integer")))
+    }
+
+    fn is_prime_key(&self) -> bool {
+        is_prime_biguint(self)
+    }
+
+    fn is_dh_group(p: &BigUint, q: &BigUint, g: &BigUint) -> bool {
+        *p == q * 2u32 + 1u32 && g % p > BigUint::one() && g.modpow(q, p).is_one()
+    }
+}
+
+#[cfg(feature = "serde")]
+struct Key<'a, T>(&'a T);
+
+#[cfg(feature = "serde")]
+impl<T: KeyInt> serde::Serialize for Key<'_, T> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.0.serialize_key(serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+struct OwnedKey<T>(T);
+
+#[cfg(feature = "serde")]
+impl<'de, T: KeyInt> serde::Deserialize<'de> for OwnedKey<T> {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<OwnedKey<T>, D::Error> {
+        T::deserialize_key(deserializer).map(OwnedKey)
+    }
+}
+
+// Reads an object whose fields are exactly those given, each a T, into their values in order.
+#[cfg(feature = "serde")]
+struct KeyFieldsVisitor<T> {
+    fields: &'static [&'static str],
+    marker: std::marker::PhantomData<T>,
+}
+
+#[cfg(feature = "serde")]
+impl<T> KeyFieldsVisitor<T> {
+    fn new(fields: &'static [&'static str]) -> KeyFieldsVisitor<T> {
+        KeyFieldsVisitor {
+            fields,
+            marker: std::marker::PhantomData,
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, T: KeyInt> serde::de::Visitor<'de> for KeyFieldsVisitor<T> {
+    type Value = Vec<T>;
+
+    fn expecting(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "an object with fields {}", self.fields.join(", "))
+    }
+
+    fn visit_map<A: serde::de::MapAccess<'de>>(self, mut map: A) -> Result<Vec<T>, A::Error> {
+        use serde::de::Error;
+
+        let mut values: Vec<Option<T>> = self.fields.iter().map(|_| None).collect();
+
+        while let Some(key) = map.next_key::<String>()? {
+            let i = self
+                .fields
+                .iter()
+                .position(|&field| field == key)
+                .ok_or_else(|| A::Error::unknown_field(&key, self.fields))?;
+
+            values[i] = Some(map.next_value::<OwnedKey<T>>()?.0);
+        }
+
+        values
+            .into_iter()
+            .zip(self.fields)
+            .map(|(value, field)| value.ok_or_else(|| A::Error::missing_field(field)))
+            .collect()
+    }
+}
+
+/// Constraints on the primes of an RSA modulus.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RsaConfig {
+    /// The public exponent `e`, which must be coprime to `p − 1` and `q − 1`.
+    pub public_exponent: u64,
+    /// Require `|p − q| >= 2^min_distance_bits`, so Fermat's method cannot split `p q` quickly.
+    /// 0 requires only that the primes differ.
+    pub min_distance_bits: u64,
+}
+
+impl Default for RsaConfig {
+    fn default() -> RsaConfig {
+        RsaConfig {
+            public_exponent: 65_537,
+            min_distance_bits: 0,
+        }
+    }
+}
+
+/// Two distinct random primes `p > q` whose product has exactly `bits` bits, each with the top
+/// two bits set, satisfying the default [`RsaConfig`]. Panics unless `16 <= bits <= 128`.
+pub fn generate_rsa_primes<R: Rng + ?Sized>(bits: u32, rng: &mut R) -> RsaPrimes<u64> {
+    generate_rsa_primes_with(bits, &RsaConfig::default(), rng)
+}
+
+/// Two distinct random primes `p > q` whose product has exactly `bits` bits, satisfying
+/// `config`. Panics unless `16 <= bits <= 128`, if the public exponent is even or below 3, or
+/// if `min_distance_bits` leaves too few suitable pairs, above `bits / 2 − 4`.
+pub fn generate_rsa_primes_with<R: Rng + ?Sized>(
+    bits: u32,
+    config: &RsaConfig,
+    rng: &mut R,
+) -> RsaPrimes<u64> {
+    assert!(
+        (16..=128).contains(&bits),
+        "no {bits}-bit RSA modulus has u64 primes"
+    );
+    check_rsa_config(bits as u64, config);
+
+    let e = config.public_exponent;
+
+    // Primes in [3 2^(k − 2), 2^k) have a product of exactly the sum of their bits.
+    let prime = |k: u32, rng: &mut R| loop {
+        let p = random_prime_in(3 << (k - 2)..=u64::MAX >> (64 - k), rng).unwrap();
+
+        if gcd(e, p - 1) == 1 {
+            break p;
+        }
+    };
+
+    let p = prime(bits - bits / 2, rng);
+
+    loop {
+        let q = prime(bits / 2, rng);
+
+        if p.abs_diff(q) >> config.min_distance_bits != 0 {
+            return RsaPrimes {
+                p: p.max(q),
+                q: p.min(q),
+            };
+        }
+    }
+}
+
+/// Two distinct random primes `p > q` whose product has exactly `bits` bits, for any
+/// `bits >= 16`, satisfying `config` as in [`generate_rsa_primes_with`], with each prime passing
+/// the tests of [`random_prime_bits_biguint`]. Panics if `bits < 16`, or `config` is unusable.
+#[cfg(feature = "bigint")]
+pub fn generate_rsa_primes_biguint<R: Rng + ?Sized>(
+    bits: u64,
+    config: &RsaConfig,
+    rng: &mut R,
+) -> RsaPrimes<BigUint> {
+    assert!(bits >= 16, "RSA moduli need at least 16 bits");
+
+    if bits <= 128 {
+        let RsaPrimes { p, q } = generate_rsa_primes_with(bits as u32, config, rng);
+
+        return RsaPrimes {
+            p: BigUint::from(p),
+            q: BigUint::from(q),
+        };
+    }
+
+    check_rsa_config(bits, config);
+
+    let e = BigUint::from(config.public_exponent);
+
+    let prime = |k: u64, rng: &mut R| loop {
+        let p = Wiped(random_biguint(k, rng) | BigUint::from(3u32) << (k - 2) | BigUint::one());
+
+        if Wiped(&*p - 1u32).gcd(&e).is_one() && passes_prime_tests(&p, rng) {
+            break p;
+        }
+    };
+
+    let p = prime(bits - bits / 2, rng);
+
+    loop {
+        let q = prime(bits / 2, rng);
+        let distance = Wiped(if *p > *q { &*p - &*q } else { &*q - &*p });
+
+        if distance.bits() > config.min_distance_bits {
+            let (p, q) = if *p > *q { (p, q) } else { (q, p) };
+
+            return RsaPrimes {
+                p: p.into_inner(),
+                q: q.into_inner(),
+            };
+        }
+    }
+}
+
+fn check_rsa_config(bits: u64, config: &RsaConfig) {
+    let e = config.public_exponent;
+
+    assert!(
+        e >= 3 && e % 2 == 1,
+        "the public exponent must be odd and at least 3"
+    );
+    assert!(
+        config.min_distance_bits + 4 <= bits / 2,
+        "primes of {bits} / 2 bits cannot differ by 2^{}",
+        config.min_distance_bits
+    );
+}
+
+/// A random strong prime with exactly `bits` bits, by Gordon's algorithm: `p − 1` has a large
+/// prime factor `r`, `p + 1` has a large prime factor `s`, and `r − 1` has a large prime factor
+/// `t`. Here `s` has 8 bits fewer than half of `p`, and `t` 8 fewer again. Such primes resist
+/// Pollard's p − 1 and Williams' p + 1 methods, and some legacy RSA standards require them.
+/// Panics unless `40 <= bits <= 64`.
+pub fn random_strong_prime<R: Rng + ?Sized>(bits: u32, rng: &mut R) -> u64 {
+    gordon(bits, rng).0
+}
+
+// Gordon's algorithm, returning (p, r, s, t).
+fn gordon<R: Rng + ?Sized>(bits: u32, rng: &mut R) -> (u64, u64, u64, u64) {
+    assert!(
+        (40..=64).contains(&bits),
+        "no {bits}-bit strong prime fits in a u64"
+    );
+
+    let s_bits = bits / 2 - GORDON_SLACK_BITS as u32;
+    let s = random_prime_bits(s_bits, rng);
+    let t = random_prime_bits(s_bits - GORDON_SLACK_BITS as u32, rng);
+
+    // r = 2 i t + 1, the first prime from a small random i.
+    let mut i = rng.gen_range(1..=16);
+
+    while !is_prime(2 * i * t + 1) {
+        i += 1;
+    }
+
+    let r = 2 * i * t + 1;
+
+    // p0 ≡ 1 (mod r) and p0 ≡ −1 (mod s), and so is every p0 + 2 j r s.
+    let rs = r as u128 * s as u128;
+    let p0 = 2 * mod_pow(s, r - 2, r) as u128 * s as u128 - 1;
+    let low = 1u128 << (bits - 1);
+    let high = (1u128 << bits) - 1;
+
+    loop {
+        let mut j = rng.gen_range(low.saturating_sub(p0).div_ceil(2 * rs)..=(high - p0) / (2 * rs));
+
+        while p0 + 2 * j * rs <= high {
+            let p = (p0 + 2 * j * rs) as u64;
+
+            if is_prime(p) {
+                return (p, r, s, t);
+            }
+
+            j += 1;
+        }
+    }
+}
+
+/// A random strong prime with exactly `bits` bits, for any `bits >= 40`, by Gordon's algorithm
+/// as in [`random_strong_prime`], with every prime passing the tests of
+/// [`random_prime_bits_biguint`]. Panics if `bits < 40`.
+#[cfg(feature = "bigint")]
+pub fn random_strong_prime_biguint<R: Rng + ?Sized>(bits: u64, rng: &mut R) -> BigUint {
+    assert!(bits >= 40, "strong primes need at least 40 bits");
+
+    if bits <= 64 {
+        return BigUint::from(random_strong_prime(bits as u32, rng));
+    }
+
+    let s_bits = bits / 2 - GORDON_SLACK_BITS;
+    let s = Wiped(random_prime_bits_biguint(s_bits, rng));
+    let t = Wiped(random_prime_bits_biguint(s_bits - GORDON_SLACK_BITS, rng));
+    let two_t = Wiped(&*t * 2u32);
+    let mut r = Wiped(&*two_t * rng.gen_range(1..=16u32) + 1u32);
+
+    while !passes_prime_tests(&r, rng) {
+        r.0 += &*two_t;
+    }
+
+    let two_rs = Wiped(&*r * &*s * 2u32);
+    let p0 = Wiped(s.modpow(&Wiped(&*r - 2u32), &r) * &*s * 2u32 - 1u32);
+    let low = BigUint::one() << (bits - 1);
+    let high = (BigUint::one() << bits) - 1u32;
+
+    loop {
+        // A random multiple of 2 r s, from those that keep p0 plus it within bits bits.
+        let j_low = Wiped(if low > *p0 {
+            (&low - &*p0).div_ceil(&two_rs)
+        } else {
+            BigUint::zero()
+        });
+        let j_count = Wiped((&high - &*p0) / &*two_rs - &*j_low + 1u32);
+        let j = Wiped(&*j_low + random_biguint(j_count.bits() + 64, rng) % &*j_count);
+        let mut p = Wiped(&*p0 + &*j * &*two_rs);
+
+        while *p <= high {
+            if passes_prime_tests(&p, rng) {
+                return p.into_inner();
+            }
+
+            p.0 += &*two_rs;
+        }
+    }
+}
+
+/// A random prime with exactly `bits` bits and a certificate proving it, by Maurer's method.
+/// A certified prime `q` of just over half the bits is built first, recursively down to 64 bits
+/// where the deterministic test is a proof, and then `n = 2 R q + 1` for random `R` until a
+/// random witness satisfies Pocklington's criterion with `F = q`. No probabilistic test is
+/// trusted. Panics if `bits < 2`.
+#[cfg(feature = "bigint")]
+pub fn random_provable_prime<R: Rng + ?Sized>(
+    bits: u64,
+    rng: &mut R,
+) -> (BigUint, PrimeCertificate) {
+    let certificate = maurer(bits, rng);
+
+    (certificate.n(), certificate)
+}
+
+#[cfg(feature = "bigint")]
+fn maurer<R: Rng + ?Sized>(bits: u64, rng: &mut R) -> PrimeCertificate {
+    assert!(bits >= 2, "no prime has fewer than 2 bits");
+
+    if bits <= 64 {
+        return PrimeCertificate::Small(random_prime_bits(bits as u32, rng));
+    }
+
+    // q >= 2^ceil(bits / 2) makes q^2 exceed every n of bits bits.
+    let q_certificate = maurer(bits.div_ceil(2) + 1, rng);
+    let two_q = Wiped(q_certificate.n() * 2u32);
+
+    // n = 2 R q + 1 in [2^(bits − 1), 2^bits).
+    let r_low = Wiped(((BigUint::one() << (bits - 1)) - 1u32).div_ceil(&two_q));
+    let r_count = Wiped(((BigUint::one() << bits) - 2u32) / &*two_q - &*r_low + 1u32);
+
+    loop {
+        let r = Wiped(&*r_low + random_biguint(r_count.bits() + 64, rng) % &*r_count);
+        let n = Wiped(&*two_q * &*r + 1u32);
+
+        if trial_primes().iter().any(|&p| (&*n % p).is_zero()) {
+            continue;
+        }
+
+        let a = Wiped(random_biguint(bits + 64, rng) % &*Wiped(&*n - 3u32) + 2u32);
+        let fermat = Wiped(a.modpow(&Wiped(&*n - 1u32), &n));
+
+        if fermat.is_one()
+            && Wiped(a.modpow(&Wiped(&*r * 2u32), &n) - 1u32)
+                .gcd(&n)
+                .is_one()
+        {
+            return PrimeCertificate::Pocklington {
+                n: n.into_inner(),
+                witness: a.into_inner(),
+                factors: vec![q_certificate],
+            };
+        }
+    }
+}
+
+// The residue of a sieving start modulo each trial prime from 5 up, given a way to reduce it.
+fn sieve_residues(residue: impl Fn(u64) -> u64) -> Vec<u64> {
+    trial_primes()[2..].iter().map(|&r| residue(r)).collect()
+}
+
+// Whether start + offset, with the given residues of start, leaves neither q nor 2 q + 1
+// divisible by a trial prime, so q avoids both 0 and (r − 1) / 2 modulo each prime r.
+fn survives_safe_sieve(residues: &[u64], offset: u64) -> bool {
+    trial_primes()[2..]
+        .iter()
+        .zip(residues)
+        .all(|(&r, &residue)| {
+            let q = (residue + offset % r) % r;
+
+            q != 0 && q != (r - 1) / 2
+        })
+}
+
+// A uniformly random integer below 2^bits.
+#[cfg(feature = "bigint")]
+pub(crate) fn random_biguint<R: Rng + ?Sized>(bits: u64, rng: &mut R) -> BigUint {
+    let mut bytes = vec![0; bits.div_ceil(8) as usize];
+
+    rng.fill_bytes(&mut bytes);
+
+    let n = &*Wiped(BigUint::from_bytes_le(&bytes)) >> (8 * bytes.len() as u64 - bits);
+
+    #[cfg(feature = "zeroize")]
+    zeroize::Zeroize::zeroize(&mut bytes);
+
+    n
+}
+
+/// Whether `n` is probably prime, with a composite passing with probability at most that of
+/// `level` when `n` is a random candidate, by trial division and then the number of rounds of
+/// Miller–Rabin to random bases that [`SecurityLevel::miller_rabin_rounds`] gives for its size.
+/// Below 2^64 the answer is exact.
+#[cfg(feature = "bigint")]
+pub fn is_probable_prime_biguint<R: Rng + ?Sized>(
+    n: &BigUint,
+    level: SecurityLevel,
+    rng: &mut R,
+) -> bool {
+    if let Some(n) = n.to_u64() {
+        return is_prime(n);
+    }
+
+    if trial_primes().iter().any(|&p| (n % p).is_zero()) {
+        return false;
+    }
+
+    let bases = Wiped(n - 3u32);
+
+    (0..level.miller_rabin_rounds(n.bits())).all(|_| {
+        let base = Wiped(random_biguint(n.bits() + 64, rng) % &*bases + 2u32);
+
+        is_strong_probable_prime_biguint(n, &base)
+    })
+}
+
+// The tests every generated bigint prime passes: those of is_probable_prime_biguint at the
+// default level, and the Baillie–PSW test.
+#[cfg(feature = "bigint")]
+fn passes_prime_tests<R: Rng + ?Sized>(n: &BigUint, rng: &mut R) -> bool {
+    is_probable_prime_biguint(n, SecurityLevel::default(), rng) && is_prime_biguint(n)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    #[test]
+    fn random_prime_in_01() {
+        let mut rng = StdRng::seed_from_u64(1);
+
+        assert_eq!(random_prime_in(24..=28, &mut rng), None);
+        assert_eq!(random_prime_in(10..10, &mut rng), None);
+        assert_eq!(random_prime_in(0..2, &mut rng), None);
+        assert_eq!(random_prime_in(2..3, &mut rng), Some(2));
+        assert_eq!(
+            random_prime_in(u64::MAX - 58.., &mut rng),
+            Some(u64::MAX - 58)
+        );
+
+        // Each of the 25 primes below 100 turns up, at about the expected rate.
+        let mut counts = [0; 100];
+
+        for _ in 0..10_000 {
+            counts[random_prime_in(..100, &mut rng).unwrap() as usize] += 1;
+        }
+
+        for (n, &count) in counts.iter().enumerate() {
+            if is_prime(n as u64) {
+                assert!((300..500).contains(&count), "{n} drawn {count} times");
+            } else {
+                assert_eq!(count, 0);
+            }
+        }
+
+        for range in [1 << 20..1 << 21, 1 << 40..1 << 41, 1 << 63..u64::MAX] {
+            for _ in 0..100 {
+                let p = random_prime_in(range.clone(), &mut rng).unwrap();
+
+                assert!(range.contains(&p) && is_prime(p));
+            }
+        }
+
+        assert!(is_prime(random_prime_in(.., &mut rng).unwrap()));
+    }
+
+    #[test]
+    fn random_prime_bits_01() {
+        let mut rng = StdRng::seed_from_u64(2);
+
+        for bits in 2..=64 {
+            for _ in 0..20 {
+                let p = random_prime_bits(bits, &mut rng);
+
+                assert!(is_prime(p));
+                assert_eq!(64 - p.leading_zeros(), bits);
+            }
+        }
+    }
+
+    #[cfg(feature = "bigint")]
+    #[test]
+    fn random_prime_bits_biguint_01() {
+        let mut rng = StdRng::seed_from_u64(3);
+
+        for bits in [2, 20, 64, 65, 100, 128, 256] {
+            let p = random_prime_bits_biguint(bits, &mut rng);
+
+            assert!(is_prime_biguint(&p));
+            assert_eq!(p.bits(), bits);
+        }
+    }
+
+    #[test]
+    fn random_safe_prime_01() {
+        let mut rng = StdRng::seed_from_u64(4);
+
+        for bits in 3..=64 {
+            for _ in 0..5 {
+                let p = random_safe_prime(bits, &mut rng);
+
+                assert!(is_prime(p) && is_prime(p / 2), "{p}");
+                assert_eq!(64 - p.leading_zeros(), bits);
+            }
+        }
+    }
+
+    #[cfg(feature = "bigint")]
+    #[test]
+    fn random_safe_prime_biguint_01() {
+        let mut rng = StdRng::seed_from_u64(5);
+
+        for bits in [3, 64, 65, 128] {
+            let p = random_safe_prime_biguint(bits, &mut rng);
+
+            assert!(is_prime_biguint(&p) && is_prime_biguint(&(&p >> 1)));
+            assert_eq!(p.bits(), bits);
+        }
+    }
+
+    #[test]
+    fn random_strong_prime_01() {
+        let mut rng = StdRng::seed_from_u64(6);
+
+        for bits in 40..=64 {
+            for _ in 0..5 {
+                let (p, r, s, t) = gordon(bits, &mut rng);
+
+                assert!(is_prime(p) && is_prime(r) && is_prime(s) && is_prime(t));
+                assert_eq!(64 - p.leading_zeros(), bits);
+                assert_eq!(((p - 1) % r, (p + 1) % s, (r - 1) % t), (0, 0, 0));
+                assert!(s >= 1 << (bits / 2 - 9) && r > 2 * t && t >= 1 << (bits / 2 - 17));
+            }
+        }
+
+        assert!(is_prime(random_strong_prime(64, &mut rng)));
+    }
+
+    #[cfg(feature = "bigint")]
+    #[test]
+    fn random_strong_prime_biguint_01() {
+        let mut rng = StdRng::seed_from_u64(7);
+
+        for bits in [40, 64, 100, 256] {
+            let p = random_strong_prime_biguint(bits, &mut rng);
+
+            assert!(is_prime_biguint(&p));
+            assert_eq!(p.bits(), bits);
+        }
+    }
+
+    #[test]
+    fn random_blum_prime_01() {
+        let mut rng = StdRng::seed_from_u64(8);
+
+        for bits in 2..=64 {
+            for _ in 0..10 {
+                let p = random_blum_prime(bits, &mut rng);
+
+                assert!(is_prime(p) && p % 4 == 3);
+                assert_eq!(64 - p.leading_zeros(), bits);
+            }
+        }
+    }
+
+    #[cfg(feature = "bigint")]
+    #[test]
+    fn random_blum_prime_biguint_01() {
+        let mut rng = StdRng::seed_from_u64(9);
+
+        for bits in [2, 64, 65, 128, 256] {
+            let p = random_blum_prime_biguint(bits, &mut rng);
+
+            assert!(is_prime_biguint(&p));
+            assert_eq!(p.bits(), bits);
+            assert_eq!(p % 4u32, BigUint::from(3u32));
+        }
+    }
+
+    #[test]
+    fn random_prime_congruent_01() {
+        let mut rng = StdRng::seed_from_u64(17);
+
+        for (a, m) in [
+            (1u64, 4u64),
+            (3, 4),
+            (5, 6),
+            (1, 1),
+            (1, 1_000_003),
+            (12, 35),
+            (u64::MAX, 257),
+        ] {
+            for bits in [2, 3, 8, 17, 33, 64] {
+                let expected = (1u64 << (bits - 1)..=u64::MAX >> (64 - bits))
+                    .take(1 << 12)
+                    .any(|n| n % m == a % m && is_prime(n));
+
+                match random_prime_congruent(a, m, bits, &mut rng) {
+                    Some(p) => {
+                        assert!(is_prime(p) && p % m == a % m);
+                        assert_eq!(64 - p.leading_zeros(), bits);
+                    }
+                    None => assert!(!expected, "{a} mod {m}, {bits} bits"),
+                }
+            }
+        }
+
+        // Every 8-bit prime ≡ 1 mod 10 turns up, and a shared factor leaves only itself.
+        let mut seen: Vec<u64> = (0..200)
+            .map(|_| random_prime_congruent(1, 10, 8, &mut rng).unwrap())
+            .collect();
+
+        seen.sort_unstable();
+        seen.dedup();
+
+        assert_eq!(seen, [131, 151, 181, 191, 211, 241, 251]);
+        assert_eq!(random_prime_congruent(7, 21, 3, &mut rng), Some(7));
+        assert_eq!(random_prime_congruent(7, 21, 4, &mut rng), None);
+        assert_eq!(random_prime_congruent(6, 12, 8, &mut rng), None);
+        assert_eq!(random_prime_congruent(100, 1 << 40, 5, &mut rng), None);
+    }
+
+    #[cfg(feature = "bigint")]
+    #[test]
+    fn random_prime_congruent_biguint_01() {
+        let mut rng = StdRng::seed_from_u64(18);
+        let m = (BigUint::one() << 100) + 277u32;
+
+        for bits in [8, 64, 65, 128, 256] {
+            for a in [BigUint::one(), BigUint::from(1_000_000_007u32), &m - 1u32] {
+                if let Some(p) = random_prime_congruent_biguint(&a, &m, bits, &mut rng) {
+                    assert!(is_prime_biguint(&p));
+                    assert_eq!(p.bits(), bits);
+                    assert_eq!(&p % &m, &a % &m);
+                } else {
+                    assert!(bits <= 101);
+                }
+            }
+        }
+
+        let p = random_prime_congruent_biguint(
+            &BigUint::from(3u32),
+            &BigUint::from(4u32),
+            200,
+            &mut rng,
+        );
+
+        assert_eq!(p.unwrap() % 4u32, BigUint::from(3u32));
+        assert_eq!(
+            random_prime_congruent_biguint(
+                &BigUint::from(6u32),
+                &(BigUint::from(12u32) << 200),
+                300,
+                &mut rng
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn generate_dh_params_01() {
+        let mut rng = StdRng::seed_from_u64(10);
+
+        for bits in 3..=64 {
+            let DhParams { p, q, g } = generate_dh_params(bits, &mut rng);
+
+            assert!(is_prime(p) && is_prime(q) && p == 2 * q + 1);
+            assert_eq!(64 - p.leading_zeros(), bits);
+            assert_eq!(mod_pow(g, q, p), 1);
+            assert_ne!(g % p, 1);
+        }
+    }
+
+    #[cfg(feature = "bigint")]
+    #[test]
+    fn generate_dh_params_biguint_01() {
+        let mut rng = StdRng::seed_from_u64(11);
+
+        for bits in [3, 64, 128] {
+            let DhParams { p, q, g } = generate_dh_params_biguint(bits, &mut rng);
+
+            assert_eq!(p.bits(), bits);
+            assert_eq!(p, &q * 2u32 + 1u32);
+            assert!(is_prime_biguint(&q) && g.modpow(&q, &p).is_one());
+        }
+    }
+
+    #[test]
+    fn generate_rsa_primes_01() {
+        let mut rng = StdRng::seed_from_u64(12);
+
+        for bits in 16..=128 {
+            let RsaPrimes { p, q } = generate_rsa_primes(bits, &mut rng);
+            let n = p as u128 * q as u128;
+
+            assert!(is_prime(p) && is_prime(q) && p > q);
+            assert_eq!(128 - n.leading_zeros(), bits);
+            assert!(gcd(65_537, p - 1) == 1 && gcd(65_537, q - 1) == 1);
+        }
+
+        // e = 3 needs p ≡ q ≡ 2 (mod 3), and the primes may differ by at most about 2^28.
+        let config = RsaConfig {
+            public_exponent: 3,
+            min_distance_bits: 28,
+        };
+
+        for _ in 0..20 {
+            let RsaPrimes { p, q } = generate_rsa_primes_with(64, &config, &mut rng);
+
+            assert_eq!((p % 3, q % 3), (2, 2));
+            assert!(p - q >= 1 << 28);
+        }
+    }
+
+    #[cfg(feature = "bigint")]
+    #[test]
+    fn generate_rsa_primes_biguint_01() {
+        let mut rng = StdRng::seed_from_u64(13);
+        let config = RsaConfig {
+            min_distance_bits: 100,
+            ..RsaConfig::default()
+        };
+
+        for bits in [256, 257, 384] {
+            let RsaPrimes { p, q } = generate_rsa_primes_biguint(bits, &config, &mut rng);
+
+            assert!(is_prime_biguint(&p) && is_prime_biguint(&q) && p > q);
+            assert_eq!((&p * &q).bits(), bits);
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn dh_params_serde_01() {
+        let params = DhParams { p: 23, q: 11, g: 4 };
+        let json = serde_json::to_string(&params).unwrap();
+
+        assert_eq!(json, r#"{"p":23,"q":11,"g":4}"#);
+        assert_eq!(
+            serde_json::from_str::<DhParams<u64>>(&json).unwrap(),
+            params
+        );
+
+        for bad in [
+            r#"{"p":23,"q":11,"g":1}"#,
+            r#"{"p":23,"q":11,"g":5}"#,
+            r#"{"p":21,"q":10,"g":4}"#,
+            r#"{"p":23,"q":11}"#,
+        ] {
+            assert!(serde_json::from_str::<DhParams<u64>>(bad).is_err(), "{bad}");
+        }
+
+        #[cfg(feature = "bigint")]
+        {
+            let params = generate_dh_params_biguint(128, &mut StdRng::seed_from_u64(18));
+            let json = serde_json::to_string(&params).unwrap();
+
+            assert_eq!(
+                json,
+                format!(
+                    r#"{{"p":"{}","q":"{}","g":"{}"}}"#,
+                    params.p, params.q, params.g
+                )
+            );
+            assert_eq!(
+                serde_json::from_str::<DhParams<BigUint>>(&json).unwrap(),
+                params
+            );
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn rsa_primes_serde_01() {
+        let primes = generate_rsa_primes(64, &mut StdRng::seed_from_u64(19));
+        let json = serde_json::to_string(&primes).unwrap();
+
+        assert_eq!(
+            serde_json::from_str::<RsaPrimes<u64>>(&json).unwrap(),
+            primes
+        );
+
+        for bad in [
+            r#"{"p":11,"q":13}"#,
+            r#"{"p":15,"q":13}"#,
+            r#"{"p":13,"q":11,"r":7}"#,
+        ] {
+            assert!(
+                serde_json::from_str::<RsaPrimes<u64>>(bad).is_err(),
+                "{bad}"
+            );
+        }
+
+        #[cfg(feature = "bigint")]
+        {
+            let primes = generate_rsa_primes_biguint(
+                256,
+                &RsaConfig::default(),
+                &mut StdRng::seed_from_u64(20),
+            );
+            let json = serde_json::to_string(&primes).unwrap();
+
+            assert_eq!(
+                serde_json::from_str::<RsaPrimes<BigUint>>(&json).unwrap(),
+                primes
+            );
+            assert!(serde_json::from_str::<RsaPrimes<BigUint>>(r#"{"p":"13","q":"x"}"#).is_err());
+        }
+    }
+
+    #[cfg(feature = "bigint")]
+    #[test]
+    fn random_provable_prime_01() {
+        let mut rng = StdRng::seed_from_u64(14);
+
+        for bits in [2, 64, 65, 100, 128, 256, 512] {
+            let (p, certificate) = random_provable_prime(bits, &mut rng);
+
+            assert!(certificate.verify());
+            assert_eq!(certificate.n(), p);
+            assert_eq!(p.bits(), bits);
+        }
+    }
+
+    #[test]
+    fn drbg_01() {
+        // Fixed outputs for fixed seeds, which must never change.
+        let mut rng = Drbg::from_u64(2024);
+
+        assert_eq!(random_prime_bits(64, &mut rng), 12_872_615_377_877_171_381);
+        assert_eq!(random_prime_in(..100, &mut rng), Some(83));
+        assert_eq!(
+            random_safe_prime(32, &mut Drbg::new([7; 32])),
+            3_090_996_179
+        );
+
+        let (mut a, mut b) = (Drbg::from_u64(1), Drbg::from_u64(1));
+
+        for bits in [2, 17, 40, 64] {
+            assert_eq!(
+                random_prime_bits(bits, &mut a),
+                random_prime_bits(bits, &mut b)
+            );
+        }
+
+        assert_ne!(Drbg::from_u64(1).next_u64(), Drbg::from_u64(2).next_u64());
+    }
+
+    #[cfg(feature = "zeroize")]
+    #[test]
+    fn drbg_zeroize_01() {
+        use zeroize::Zeroize;
+
+        let mut rng = Drbg::from_u64(15);
+
+        rng.next_u64();
+        rng.zeroize();
+
+        assert_eq!(rng.next_u64(), Drbg::new([0; 32]).next_u64());
+    }
+
+    #[cfg(all(feature = "bigint", feature = "zeroize"))]
+    #[test]
+    fn rsa_primes_zeroize_01() {
+        use zeroize::{Zeroize, Zeroizing};
+
+        let mut rng = Drbg::from_u64(16);
+        let mut primes = generate_rsa_primes_biguint(256, &RsaConfig::default(), &mut rng);
+
+        primes.zeroize();
+        assert!(primes.p.is_zero() && primes.q.is_zero());
+
+        let mut primes = generate_rsa_primes(64, &mut rng);
+
+        primes.zeroize();
+        assert_eq!(primes, RsaPrimes { p: 0, q: 0 });
+
+        let mut params = generate_dh_params_biguint(96, &mut rng);
+
+        params.zeroize();
+        assert!(params.p.is_zero() && params.g.is_zero());
+
+        let (_, mut certificate) = random_provable_prime(128, &mut rng);
+
+        certificate.zeroize();
+        assert!(certificate.n().is_zero() && !certificate.verify());
+
+        // Zeroizing wipes on drop, and reads through to the primes until then.
+        let primes = Zeroizing::new(generate_rsa_primes_biguint(
+            256,
+            &RsaConfig::default(),
+            &mut rng,
+        ));
+
+        assert_eq!((&primes.p * &primes.q).bits(), 256);
+    }
+
+    #[cfg(all(feature = "bigint", feature = "zeroize"))]
+    #[test]
+    fn wiped_01() {
+        let n = (BigUint::one() << 512u32) - 1u32;
+        let kept = Wiped(n.clone()).into_inner();
+        let mut wiped = n;
+
+        wipe_biguint(&mut wiped);
+
+        assert_eq!(kept.bits(), 512);
+        assert!(wiped.is_zero());
+    }
+
+    #[test]
+    fn miller_rabin_rounds_01() {
+        // The 2^-80 column of the Handbook of Applied Cryptography's Table 4.4.
+        for (bits, rounds) in [
+            (100, 27),
+            (150, 18),
+            (200, 15),
+            (250, 12),
+            (300, 9),
+            (350, 8),
+            (400, 7),
+            (450, 6),
+            (550, 5),
+            (650, 4),
+            (850, 3),
+        ] {
+            assert_eq!(
+                SecurityLevel::BITS_80.miller_rabin_rounds(bits),
+                rounds,
+                "{bits}"
+            );
+        }
+
+        // Rabin's bound for tiny candidates, and more rounds at higher levels.
+        assert_eq!(SecurityLevel::BITS_128.miller_rabin_rounds(8), 64);
+
+        for bits in [64, 128, 512, 1024, 2048, 4096] {
+            let rounds = |level: SecurityLevel| level.miller_rabin_rounds(bits);
+
+            assert!(rounds(SecurityLevel::BITS_80) <= rounds(SecurityLevel::BITS_112));
+            assert!(rounds(SecurityLevel::BITS_112) <= rounds(SecurityLevel::BITS_128));
+        }
+    }
+
+    #[cfg(feature = "bigint")]
+    #[test]
+    fn is_probable_prime_biguint_01() {
+        let mut rng = StdRng::seed_from_u64(16);
+        let level = SecurityLevel::default();
+        let m127 = (BigUint::one() << 127) - 1u32;
+
+        assert!(is_probable_prime_biguint(&m127, level, &mut rng));
+        assert!(!is_probable_prime_biguint(
+            &(&m127 * &m127),
+            level,
+            &mut rng
+        ));
+        assert!(!is_probable_prime_biguint(&(&m127 + 2u32), level, &mut rng));
+        assert!(!is_probable_prime_biguint(
+            &BigUint::from(561u32),
+            level,
+            &mut rng
+        ));
+        assert!(is_probable_prime_biguint(
+            &BigUint::from(1_000_000_007u32),
+            level,
+            &mut rng
+        ));
+    }
+}
@@ -0,0 +1,139 @@
+use std::ops::ControlFlow;
+
+// Odd numbers per segment of the segmented sieve, so that each segment stays in cache.
+const SEGMENT_LEN: usize = 1 << 18;
+
+/// Sieve of Eratosthenes over the odd numbers, returning every prime `<= limit`.
+pub(crate) fn sieve_primes(limit: u64) -> Vec<u64> {
+    let mut primes = vec![];
+
+    if limit < 2 {
+        return primes;
+    }
+
+    primes.push(2);
+
+    // composite[i] represents the odd number 2 * i + 1.
+    let size = ((limit - 1) / 2) as usize + 1;
+    let mut composite = vec![false; size];
+
+    let mut i = 1;
+    while (2 * i + 1) * (2 * i + 1) <= limit as usize {
+        if !composite[i] {
+            let p = 2 * i + 1;
+            let mut j = (p * p) / 2;
+
+            while j < size {
+                composite[j] = true;
+                j += p;
+            }
+        }
+
+        i += 1;
+    }
+
+    primes.extend(
+        (1..size)
+            .filter(|&i| !composite[i])
+            .map(|i| (2 * i + 1) as u64),
+    );
+
+    primes
+}
+
+/// Segmented sieve of Eratosthenes over the odd numbers, calling `f` with each odd prime
+/// `<= limit` in increasing order until it breaks, and returning the value it breaks with. Only
+/// the primes up to `sqrt(limit)` and one segment are held in memory at a time.
+pub(crate) fn for_each_odd_prime<B>(
+    limit: u64,
+    mut f: impl FnMut(u64) -> ControlFlow<B>,
+) -> Option<B> {
+    let base: Vec<u64> = sieve_primes(limit.isqrt()).into_iter().skip(1).collect();
+
+    // The next odd multiple of each base prime to cross out, from its square.
+    let mut next: Vec<u64> = base.iter().map(|&q| q * q).collect();
+    let mut composite = vec![false; SEGMENT_LEN];
+    let mut low = 3;
+
+    while low <= limit {
+        // The segment holds the odd numbers low, low + 2, …, high.
+        let high = limit.min(low + 2 * (SEGMENT_LEN as u64 - 1));
+        let len = ((high - low) / 2) as usize + 1;
+
+        composite[..len].fill(false);
+
+        for (&q, m) in base.iter().zip(&mut next) {
+            while *m <= high {
+                composite[((*m - low) / 2) as usize] = true;
+                *m += 2 * q;
+            }
+        }
+
+        for (i, _) in composite[..len].iter().enumerate().filter(|(_, &c)| !c) {
+            if let ControlFlow::Break(b) = f(low + 2 * i as u64) {
+                return Some(b);
+            }
+        }
+
+        match high.checked_add(2) {
+            Some(next_low) => low = next_low,
+            None => break,
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::primes_upto;
+
+    #[test]
+    fn sieve_primes_01() {
+        for limit in 0..200 {
+            let a: Vec<_> = primes_upto(limit).collect();
+
+            assert_eq!(sieve_primes(limit), a);
+        }
+
+        assert_eq!(sieve_primes(100_000).len(), 9592);
+    }
+
+    #[test]
+    fn for_each_odd_prime_01() {
+        let segment = 2 * SEGMENT_LEN as u64;
+
+        for limit in [
+            0,
+            1,
+            2,
+            3,
+            4,
+            100,
+            segment,
+            segment + 1,
+            segment + 3,
+            3 * segment + 7,
+        ] {
+            let mut primes = vec![2];
+
+            for_each_odd_prime(limit, |p| {
+                primes.push(p);
+                ControlFlow::<()>::Continue(())
+            });
+
+            primes.retain(|&p| p <= limit);
+            assert_eq!(primes, sieve_primes(limit), "{limit}");
+        }
+
+        assert_eq!(
+            for_each_odd_prime(1000, |p| if p > 500 {
+                ControlFlow::Break(p)
+            } else {
+                ControlFlow::Continue(())
+            }),
+            Some(503)
+        );
+    }
+}
@@ -0,0 +1,871 @@
+#[cfg(feature = "bigint")]
+use std::iter::FusedIterator;
+
+#[cfg(feature = "bigint")]
+use num_bigint::BigUint;
+#[cfg(feature = "bigint")]
+use num_traits::{One, ToPrimitive, Zero};
+
+use crate::is_prime;
+#[cfg(feature = "bigint")]
+use crate::modular::jacobi;
+use crate::modular::mod_pow_u128;
+#[cfg(feature = "bigint")]
+use crate::modular::mul_mod_u128;
+use crate::sieve::sieve_primes;
+#[cfg(feature = "bigint")]
+use crate::{is_prime_biguint, primes, Primes};
+
+// The exponents p of the first 47 Mersenne primes 2^p − 1. Every smaller exponent has been
+// checked, so no Mersenne prime is missing below the last.
+#[cfg(feature = "bigint")]
+const MERSENNE_EXPONENTS: [u64; 47] = [
+    2, 3, 5, 7, 13, 17, 19, 31, 61, 89, 107, 127, 521, 607, 1279, 2203, 2281, 3217, 4253, 4423,
+    9689, 9941, 11213, 19937, 21701, 23209, 44497, 86243, 110503, 132049, 216091, 756839, 859433,
+    1257787, 1398269, 2976221, 3021377, 6972593, 13466917, 20996011, 24036583, 25964951, 30402457,
+    32582657, 37156667, 42643801, 43112609,
+];
+
+// The lengths n of the base-10 repunit primes (10^n − 1) / 9 with n <= 1031, every one there
+// being proven prime.
+#[cfg(feature = "bigint")]
+const DECIMAL_REPUNIT_PRIMES: [u64; 5] = [2, 19, 23, 317, 1031];
+
+// The number of small odd primes tried as the base of Proth's theorem.
+#[cfg(feature = "bigint")]
+const PROTH_BASES: usize = 64;
+
+// The Fermat primes F_0 to F_4, the only ones known.
+const FERMAT_PRIMES: [u64; 5] = [3, 5, 17, 257, 65537];
+
+// The prime factors of the fully factored composite Fermat numbers F_5 to F_11, bar the largest,
+// which is what remains after dividing them out.
+#[cfg(feature = "bigint")]
+const FERMAT_FACTORS: [&[&str]; 7] = [
+    &["641"],
+    &["274177"],
+    &["59649589127497217"],
+    &["1238926361552897"],
+    &[
+        "2424833",
+        "7455602825647884208337395736200454918783366342657",
+    ],
+    &[
+        "45592577",
+        "6487031809",
+        "4659775785220018543264560743076778192897",
+    ],
+    &[
+        "319489",
+        "974849",
+        "167988556341760475137",
+        "3560841906445833920513",
+    ],
+];
+
+/// Whether the Mersenne number `2^p − 1` is prime, by the Lucas–Lehmer test: for odd prime `p`
+/// it is exactly when `s_{p−2} ≡ 0`, where `s_0 = 4` and `s_{k+1} = s_k^2 − 2`, modulo
+/// `2^p − 1`. Each step reduces by shifts and adds rather than division. Composite `p` give
+/// composite `2^p − 1`.
+#[cfg(feature = "bigint")]
+pub fn lucas_lehmer(p: u64) -> bool {
+    if !is_prime(p) {
+        return false;
+    }
+
+    if p == 2 {
+        return true;
+    }
+
+    let m = (BigUint::one() << p) - 1u32;
+    let mut s = BigUint::from(4u32);
+
+    for _ in 0..p - 2 {
+        // s^2 − 2 as s^2 + (m − 2), folding the bits above p back in since 2^p ≡ 1.
+        let mut x = &s * &s + &m - 2u32;
+
+        while x.bits() > p {
+            x = (&x & &m) + (x >> p);
+        }
+
+        s = if x == m { BigUint::zero() } else { x };
+    }
+
+    s.is_zero()
+}
+
+/// Whether `2^p − 1` is prime, by lookup below the last exponent known to be complete and the
+/// [`lucas_lehmer`] test above it.
+#[cfg(feature = "bigint")]
+pub fn is_mersenne_prime_exponent(p: u64) -> bool {
+    match MERSENNE_EXPONENTS.last() {
+        Some(&last) if p <= last => MERSENNE_EXPONENTS.binary_search(&p).is_ok(),
+        _ => lucas_lehmer(p),
+    }
+}
+
+/// The exponents `p` of the Mersenne primes `2^p − 1` in increasing order, from
+/// [`mersenne_prime_exponents`].
+#[cfg(feature = "bigint")]
+pub struct MersennePrimeExponents {
+    index: usize,
+    // The next exponent to test once the list runs out.
+    next: u64,
+}
+
+#[cfg(feature = "bigint")]
+impl Iterator for MersennePrimeExponents {
+    type Item = u64;
+
+    fn next(&mut self) -> Option<u64> {
+        if let Some(&p) = MERSENNE_EXPONENTS.get(self.index) {
+            self.index += 1;
+            return Some(p);
+        }
+
+        // Past the list, every larger prime exponent is tested in turn.
+        loop {
+            let p = self.next;
+
+            self.next += 1;
+
+            if is_prime(p) && lucas_lehmer(p) {
+                return Some(p);
+            }
+        }
+    }
+}
+
+#[cfg(feature = "bigint")]
+impl FusedIterator for MersennePrimeExponents {}
+
+/// The exponents `p` with `2^p − 1` prime: 2, 3, 5, 7, 13, …. The first 47 come from the list
+/// of known Mersenne primes, and later ones are found by the [`lucas_lehmer`] test.
+#[cfg(feature = "bigint")]
+pub fn mersenne_prime_exponents() -> MersennePrimeExponents {
+    MersennePrimeExponents {
+        index: 0,
+        next: MERSENNE_EXPONENTS[MERSENNE_EXPONENTS.len() - 1] + 1,
+    }
+}
+
+/// The even perfect numbers `2^(p−1) (2^p − 1)` in increasing order, one for each Mersenne
+/// prime by the Euclid–Euler theorem: 6, 28, 496, 8128, ….
+#[cfg(feature = "bigint")]
+pub fn perfect_numbers() -> impl FusedIterator<Item = BigUint> {
+    mersenne_prime_exponents().map(|p| ((BigUint::one() << p) - 1u32) << (p - 1))
+}
+
+/// The Fermat number `F_k = 2^(2^k) + 1`. Panics if `k >= 64`, whose `F_k` would not fit in
+/// memory anyway.
+#[cfg(feature = "bigint")]
+pub fn fermat_number(k: u32) -> BigUint {
+    assert!(k < 64, "F_{k} is too large");
+
+    (BigUint::one() << (1u64 << k)) + 1u32
+}
+
+/// The known Fermat primes, `F_0` to `F_4`: 3, 5, 17, 257 and 65537. Every `F_k` from `F_5` to
+/// `F_32` is known to be composite.
+pub fn known_fermat_primes() -> &'static [u64] {
+    &FERMAT_PRIMES
+}
+
+/// The prime factors of `F_k` in increasing order, for the `k <= 11` for which it is known
+/// completely, or `None` for larger `k`. For `k >= 2` each factor is `≡ 1 (mod 2^(k+2))`, so
+/// `F_5 = 641 · 6700417` with `641 = 5 · 2^7 + 1`.
+#[cfg(feature = "bigint")]
+pub fn fermat_number_factors(k: u32) -> Option<Vec<BigUint>> {
+    if k < 5 {
+        return Some(vec![fermat_number(k)]);
+    }
+
+    // Look k up before building F_k, which past the table can be far too large to hold.
+    let listed = FERMAT_FACTORS.get(k as usize - 5)?;
+    let f = fermat_number(k);
+    let mut factors: Vec<BigUint> = listed
+        .iter()
+        .map(|s| BigUint::parse_bytes(s.as_bytes(), 10).unwrap())
+        .collect();
+    let cofactor = factors.iter().fold(f, |f, p| f / p);
+
+    factors.push(cofactor);
+    Some(factors)
+}
+
+/// Whether `k 2^n + 1` is prime. For Proth numbers, with odd `k < 2^n`, this is Proth's
+/// theorem: it is prime exactly when `a^((N − 1) / 2) ≡ −1 (mod N)` for any `a` with Jacobi
+/// symbol `(a | N) = −1`, found among the small primes, so the answer is proven. Other forms
+/// fall back to the Baillie–PSW test, as does the rare `N` with no such small `a`.
+#[cfg(feature = "bigint")]
+pub fn is_proth_prime(k: u64, n: u64) -> bool {
+    if k == 0 {
+        return false;
+    }
+
+    let n = n + k.trailing_zeros() as u64;
+    let k = k >> k.trailing_zeros();
+    let big = (BigUint::from(k) << n) + 1u32;
+
+    if let Some(big) = big.to_u64() {
+        return is_prime(big);
+    }
+
+    if n >= 64 || k < 1 << n {
+        // With N ≡ 1 (mod 4), (a | N) = (N mod a | a) by reciprocity.
+        for a in primes().skip(1).take(PROTH_BASES) {
+            match jacobi((&big % a).to_i64().unwrap(), a) {
+                0 => return false,
+                -1 => {
+                    let minus_one = &big - 1u32;
+
+                    return BigUint::from(a).modpow(&(&minus_one >> 1), &big) == minus_one;
+                }
+                _ => {}
+            }
+        }
+    }
+
+    is_prime_biguint(&big)
+}
+
+/// The exponents `n` with `k 2^n + 1` prime, for a fixed `k`, in increasing order, from
+/// [`proth_prime_exponents`].
+#[cfg(feature = "bigint")]
+pub struct ProthPrimeExponents {
+    k: u64,
+    n: u64,
+}
+
+#[cfg(feature = "bigint")]
+impl Iterator for ProthPrimeExponents {
+    type Item = u64;
+
+    fn next(&mut self) -> Option<u64> {
+        loop {
+            let n = self.n;
+
+            self.n += 1;
+
+            if is_proth_prime(self.k, n) {
+                return Some(n);
+            }
+        }
+    }
+}
+
+#[cfg(feature = "bigint")]
+impl FusedIterator for ProthPrimeExponents {}
+
+/// The exponents `n >= 1` with `k 2^n + 1` prime, each tested by [`is_proth_prime`]: 1, 2, 5,
+/// 6, 8, 12, … for `k = 3`. For a Sierpiński number such as 78557 there are none, and the
+/// iterator never yields. Panics if `k` is 0.
+#[cfg(feature = "bigint")]
+pub fn proth_prime_exponents(k: u64) -> ProthPrimeExponents {
+    assert!(k != 0, "k must be nonzero");
+
+    ProthPrimeExponents { k, n: 1 }
+}
+
+/// Whether the Cullen number `n 2^n + 1` is prime. It is a Proth number for every `n >= 1`, so
+/// this is proven by [`is_proth_prime`], and exact from a `u64` test while it fits.
+#[cfg(feature = "bigint")]
+pub fn is_cullen_prime(n: u64) -> bool {
+    is_proth_prime(n, n)
+}
+
+/// Whether the Woodall number `n 2^n − 1` is prime: exact while it fits in a `u64`, and the
+/// Baillie–PSW test above.
+#[cfg(feature = "bigint")]
+pub fn is_woodall_prime(n: u64) -> bool {
+    if n == 0 {
+        return false;
+    }
+
+    if let Some(w) = (n < 64).then(|| n.checked_mul(1 << n)).flatten() {
+        return is_prime(w - 1);
+    }
+
+    is_prime_biguint(&((BigUint::from(n) << n) - 1u32))
+}
+
+/// The `n` with `n 2^n + 1` prime, in increasing order: 1, 141, 4713, 5795, ….
+#[cfg(feature = "bigint")]
+pub fn cullen_primes() -> impl FusedIterator<Item = u64> {
+    (1..).filter(|&n| is_cullen_prime(n))
+}
+
+/// The `n` with `n 2^n − 1` prime, in increasing order: 2, 3, 6, 30, 75, 81, ….
+#[cfg(feature = "bigint")]
+pub fn woodall_primes() -> impl FusedIterator<Item = u64> {
+    (1..).filter(|&n| is_woodall_prime(n))
+}
+
+/// The factorial primes `n! ± 1` as `(n, ±1)`, from [`factorial_primes`].
+#[cfg(feature = "bigint")]
+pub struct FactorialPrimes {
+    n: u64,
+    factorial: BigUint,
+    // Whether n! + 1 is next, rather than n! − 1.
+    plus: bool,
+}
+
+#[cfg(feature = "bigint")]
+impl Iterator for FactorialPrimes {
+    type Item = (u64, i8);
+
+    fn next(&mut self) -> Option<(u64, i8)> {
+        loop {
+            let n = self.n;
+            let (candidate, sign) = if self.plus {
+                (&self.factorial + 1u32, 1)
+            } else {
+                (&self.factorial - 1u32, -1)
+            };
+
+            if self.plus {
+                self.n += 1;
+                self.factorial *= self.n;
+            }
+
+            self.plus = !self.plus;
+
+            if is_prime_biguint(&candidate) {
+                return Some((n, sign));
+            }
+        }
+    }
+}
+
+#[cfg(feature = "bigint")]
+impl FusedIterator for FactorialPrimes {}
+
+/// The `n` with `n! − 1` or `n! + 1` prime, as `(n, −1)` or `(n, 1)` in increasing order of
+/// `n` and then the sign: `(0, 1)`, `(1, 1)`, `(2, 1)`, `(3, −1)`, `(3, 1)`, `(4, −1)`, ….
+/// Each factorial is built from the last, and each candidate tested by Baillie–PSW, which
+/// proves nothing at these sizes but has no known counterexample.
+#[cfg(feature = "bigint")]
+pub fn factorial_primes() -> FactorialPrimes {
+    FactorialPrimes {
+        n: 0,
+        factorial: BigUint::one(),
+        plus: false,
+    }
+}
+
+/// The primorial primes `p# ± 1` as `(p, ±1)`, from [`primorial_primes`].
+#[cfg(feature = "bigint")]
+pub struct PrimorialPrimes {
+    primes: Primes,
+    p: u64,
+    primorial: BigUint,
+    // Whether p# + 1 is next, rather than p# − 1.
+    plus: bool,
+}
+
+#[cfg(feature = "bigint")]
+impl Iterator for PrimorialPrimes {
+    type Item = (u64, i8);
+
+    fn next(&mut self) -> Option<(u64, i8)> {
+        loop {
+            let p = self.p;
+            let (candidate, sign) = if self.plus {
+                (&self.primorial + 1u32, 1)
+            } else {
+                (&self.primorial - 1u32, -1)
+            };
+
+            if self.plus {
+                self.p = self.primes.next().unwrap();
+                self.primorial *= self.p;
+            }
+
+            self.plus = !self.plus;
+
+            if is_prime_biguint(&candidate) {
+                return Some((p, sign));
+            }
+        }
+    }
+}
+
+#[cfg(feature = "bigint")]
+impl FusedIterator for PrimorialPrimes {}
+
+/// The primes `p` with `p# − 1` or `p# + 1` prime, where `p#` is the
+/// [`primorial`](crate::primorial) of `p`, as `(p, −1)` or `(p, 1)` in increasing order of `p`
+/// and then the sign: `(2, 1)`, `(3, −1)`, `(3, 1)`, `(5, −1)`, …. Each primorial is built from
+/// the last, and each candidate tested by Baillie–PSW.
+#[cfg(feature = "bigint")]
+pub fn primorial_primes() -> PrimorialPrimes {
+    let mut primes = Primes::new();
+    let p = primes.next().unwrap();
+
+    PrimorialPrimes {
+        primes,
+        p,
+        primorial: BigUint::from(p),
+        plus: false,
+    }
+}
+
+/// Whether the Wagstaff number `(2^p + 1) / 3` is prime, for odd prime `p`. Exact while it
+/// fits in a `u64`; above, it is a Fermat probable prime test to base 3, as used in searches for
+/// these numbers, since every Wagstaff number is a probable prime to base 2. No composite is
+/// known to pass. Other `p` give composites or, for `p = 2`, not an integer.
+#[cfg(feature = "bigint")]
+pub fn is_wagstaff_prime(p: u64) -> bool {
+    if p < 3 || !is_prime(p) {
+        return false;
+    }
+
+    if p < 64 {
+        return is_prime(((1u64 << p) + 1) / 3);
+    }
+
+    let w = ((BigUint::one() << p) + 1u32) / 3u32;
+
+    BigUint::from(3u32).modpow(&(&w - 1u32), &w).is_one()
+}
+
+/// The repunit `(base^n − 1) / (base − 1)`, whose `n` digits in `base` are all 1. Panics if
+/// `base < 2` or `n >= 2^32`.
+#[cfg(feature = "bigint")]
+pub fn repunit(n: u64, base: u64) -> BigUint {
+    assert!(base >= 2, "base must be at least 2");
+
+    let digits = u32::try_from(n).unwrap_or_else(|_| panic!("{n} digits is too many"));
+
+    (BigUint::from(base).pow(digits) - 1u32) / (base - 1)
+}
+
+/// Whether the repunit of `n` digits in `base` is prime. Composite `n` give composites, since
+/// the repunit of each divisor divides it; base 2 gives the Mersenne numbers, settled by
+/// [`is_mersenne_prime_exponent`], and base-10 lengths up to 1031 come from the known list.
+/// Otherwise the test is exact below 2^64 and the Baillie–PSW probable prime test above.
+/// Panics if `base < 2`, or if the repunit itself is needed and `n >= 2^32`.
+#[cfg(feature = "bigint")]
+pub fn is_repunit_prime(n: u64, base: u64) -> bool {
+    assert!(base >= 2, "base must be at least 2");
+
+    if !is_prime(n) {
+        return false;
+    }
+
+    match base {
+        2 => is_mersenne_prime_exponent(n),
+        10 if n <= DECIMAL_REPUNIT_PRIMES[DECIMAL_REPUNIT_PRIMES.len() - 1] => {
+            DECIMAL_REPUNIT_PRIMES.binary_search(&n).is_ok()
+        }
+        _ => is_prime_biguint(&repunit(n, base)),
+    }
+}
+
+/// The Pierpont primes `2^u 3^v + 1 <= limit` in increasing order: 2, 3, 5, 7, 13, 17, 19,
+/// 37, …. The candidates are generated directly, only `O(log^2 limit)` of them, rather than
+/// found among all integers.
+pub fn pierpont_primes_upto(limit: u64) -> Vec<u64> {
+    let mut primes = vec![];
+    let mut power_of_3 = Some(1u64);
+
+    while let Some(p3) = power_of_3.filter(|&p3| p3 < limit) {
+        let mut smooth = Some(p3);
+
+        while let Some(m) = smooth.filter(|&m| m < limit) {
+            if is_prime(m + 1) {
+                primes.push(m + 1);
+            }
+
+            smooth = m.checked_mul(2);
+        }
+
+        power_of_3 = p3.checked_mul(3);
+    }
+
+    primes.sort_unstable();
+    primes
+}
+
+/// The Wieferich primes `<= limit`, those `p` with `2^(p−1) ≡ 1 (mod p^2)`. Only 1093 and 3511
+/// are known.
+pub fn wieferich_primes_upto(limit: u64) -> Vec<u64> {
+    wieferich_primes_upto_base(2, limit)
+}
+
+/// The Wieferich primes to base `a` that are `<= limit`, those `p` not dividing `a` with
+/// `a^(p−1) ≡ 1 (mod p^2)`, such as 11 and 1006003 for base 3. Each is a single modular power
+/// with 128-bit arithmetic. Panics if `a < 2`.
+pub fn wieferich_primes_upto_base(a: u64, limit: u64) -> Vec<u64> {
+    assert!(a >= 2, "base must be at least 2");
+
+    sieve_primes(limit)
+        .into_iter()
+        .filter(|&p| {
+            let square = p as u128 * p as u128;
+
+            !a.is_multiple_of(p) && mod_pow_u128(a as u128, p as u128 - 1, square) == 1
+        })
+        .collect()
+}
+
+/// The Wilson primes `<= limit`, those `p` with `(p − 1)! ≡ −1 (mod p^2)`: only 5, 13 and 563
+/// are known. The factorials come from an accumulating remainder tree, as in the search of
+/// Costa, Gerbicz and Harvey: the primes are halved, the product of the integers the left half
+/// spans is reduced modulo the product of `p^2` over the right half and handed down, and so on
+/// to single primes. The work goes into a logarithmic number of levels of large products and
+/// remainders, rather than `p − 1` products for every prime.
+#[cfg(feature = "bigint")]
+pub fn wilson_primes_upto(limit: u64) -> Vec<u64> {
+    let primes = sieve_primes(limit);
+    let mut wilson = vec![];
+
+    if !primes.is_empty() {
+        wilson_tree(&primes, 1, &BigUint::one(), &mut wilson);
+    }
+
+    wilson
+}
+
+// Pushes the Wilson primes among primes, given c = (start − 1)! modulo the product of their
+// squares, where start is the prime before the first, or 1.
+#[cfg(feature = "bigint")]
+fn wilson_tree(primes: &[u64], start: u64, c: &BigUint, wilson: &mut Vec<u64>) {
+    if let [p] = *primes {
+        let square = p as u128 * p as u128;
+        let factorial = (start..p).fold(c.to_u128().unwrap(), |f, k| {
+            mul_mod_u128(f, k as u128, square)
+        });
+
+        if factorial == square - 1 {
+            wilson.push(p);
+        }
+
+        return;
+    }
+
+    let (left, right) = primes.split_at(primes.len() / 2);
+    let middle = left[left.len() - 1];
+
+    wilson_tree(left, start, &(c % square_product(left)), wilson);
+
+    let modulus = square_product(right);
+    let c = c % &modulus * (range_product(start, middle) % &modulus) % &modulus;
+
+    wilson_tree(right, middle, &c, wilson);
+}
+
+// The product of the integers in [low, high), split in halves so the factors stay balanced.
+#[cfg(feature = "bigint")]
+fn range_product(low: u64, high: u64) -> BigUint {
+    if high - low <= 16 {
+        return (low..high).fold(BigUint::one(), |product, k| product * k);
+    }
+
+    let middle = low + (high - low) / 2;
+
+    range_product(low, middle) * range_product(middle, high)
+}
+
+// The product of p^2 over primes, split in halves likewise.
+#[cfg(feature = "bigint")]
+fn square_product(primes: &[u64]) -> BigUint {
+    if primes.len() <= 8 {
+        return primes.iter().fold(BigUint::one(), |product, &p| {
+            product * (p as u128 * p as u128)
+        });
+    }
+
+    let (left, right) = primes.split_at(primes.len() / 2);
+
+    square_product(left) * square_product(right)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(feature = "bigint")]
+    use crate::is_prime_u128;
+    #[cfg(feature = "bigint")]
+    use crate::modular::mul_mod;
+    #[cfg(feature = "bigint")]
+    use crate::primorial;
+
+    #[cfg(feature = "bigint")]
+    #[test]
+    fn lucas_lehmer_01() {
+        // The test against the list, and the list against itself through the iterator.
+        let exponents: Vec<u64> = (0..1300).filter(|&p| lucas_lehmer(p)).collect();
+
+        assert_eq!(exponents, MERSENNE_EXPONENTS[..15]);
+        assert!((0..1300).all(|p| is_mersenne_prime_exponent(p) == lucas_lehmer(p)));
+        assert!(lucas_lehmer(4423) && !lucas_lehmer(4421));
+        assert!(mersenne_prime_exponents().take(47).eq(MERSENNE_EXPONENTS));
+
+        // 2^11 − 1 = 23 · 89, though 11 is prime.
+        assert!(!lucas_lehmer(11) && !is_mersenne_prime_exponent(11));
+    }
+
+    #[cfg(feature = "bigint")]
+    #[test]
+    fn perfect_numbers_01() {
+        let perfect: Vec<BigUint> = perfect_numbers().take(8).collect();
+
+        assert_eq!(
+            perfect,
+            [
+                6u64,
+                28,
+                496,
+                8128,
+                33_550_336,
+                8_589_869_056,
+                137_438_691_328,
+                2_305_843_008_139_952_128,
+            ]
+            .map(BigUint::from)
+        );
+
+        // Each is the sum of its proper divisors, 2^(p−1) (2^p − 1) having divisors 2^i and
+        // 2^i (2^p − 1) for i < p.
+        for n in perfect.iter().take(4) {
+            let n = n.to_u64_digits()[0];
+
+            assert_eq!((1..n).filter(|d| n % d == 0).sum::<u64>(), n);
+        }
+    }
+
+    #[cfg(feature = "bigint")]
+    #[test]
+    fn fermat_number_01() {
+        assert_eq!(fermat_number(0), BigUint::from(3u32));
+        assert_eq!(fermat_number(5), BigUint::from(4_294_967_297u64));
+        assert_eq!(fermat_number(20).bits(), (1 << 20) + 1);
+
+        for (k, &p) in known_fermat_primes().iter().enumerate() {
+            assert_eq!(fermat_number(k as u32), BigUint::from(p));
+            assert!(is_prime(p));
+        }
+
+        // The factors multiply back, are prime, and have the form forced on them.
+        for k in 0..12 {
+            let factors = fermat_number_factors(k).unwrap();
+            let step = BigUint::one() << (k + 2);
+
+            assert_eq!(factors.iter().product::<BigUint>(), fermat_number(k));
+            assert!(factors.windows(2).all(|w| w[0] < w[1]));
+
+            for p in &factors {
+                assert!(is_prime_biguint(p), "F_{k}");
+                assert!(k < 2 || (p % &step).is_one(), "F_{k}");
+            }
+        }
+
+        assert_eq!(
+            fermat_number_factors(5).unwrap(),
+            [BigUint::from(641u32), BigUint::from(6_700_417u32)]
+        );
+        assert_eq!(fermat_number_factors(12), None);
+        assert_eq!(fermat_number_factors(40), None);
+        assert_eq!(fermat_number_factors(64), None);
+    }
+
+    #[cfg(feature = "bigint")]
+    #[test]
+    fn is_proth_prime_01() {
+        assert!(proth_prime_exponents(3)
+            .take(19)
+            .eq([1, 2, 5, 6, 8, 12, 18, 30, 36, 41, 66, 189, 201, 209, 276, 353, 408, 438, 534]));
+        assert!(proth_prime_exponents(1).take(5).eq([1, 2, 4, 8, 16]));
+
+        // Against Baillie–PSW, for odd and even k, in and out of Proth form.
+        for k in 1..40 {
+            for n in 0..150 {
+                let big = (BigUint::from(k) << n) + 1u32;
+
+                assert_eq!(
+                    is_proth_prime(k, n),
+                    is_prime_biguint(&big),
+                    "{k} 2^{n} + 1"
+                );
+            }
+        }
+
+        assert!(!is_proth_prime(78_557, 1000));
+        assert!(!is_proth_prime(0, 10));
+    }
+
+    #[cfg(feature = "bigint")]
+    #[test]
+    fn cullen_woodall_01() {
+        assert!(cullen_primes().take(2).eq([1, 141]));
+        assert!(woodall_primes()
+            .take(10)
+            .eq([2, 3, 6, 30, 75, 81, 115, 123, 249, 362]));
+
+        for n in 0..120u64 {
+            let power = BigUint::from(n) << n;
+
+            assert_eq!(
+                is_cullen_prime(n),
+                is_prime_biguint(&(&power + 1u32)),
+                "{n}"
+            );
+            assert_eq!(
+                is_woodall_prime(n),
+                n > 0 && is_prime_biguint(&(power - 1u32)),
+                "{n}"
+            );
+        }
+    }
+
+    #[cfg(feature = "bigint")]
+    #[test]
+    fn factorial_primes_01() {
+        let found: Vec<(u64, i8)> = factorial_primes().take_while(|&(n, _)| n <= 100).collect();
+        let minus: Vec<u64> = found.iter().filter(|f| f.1 == -1).map(|f| f.0).collect();
+        let plus: Vec<u64> = found.iter().filter(|f| f.1 == 1).map(|f| f.0).collect();
+
+        assert_eq!(minus, [3, 4, 6, 7, 12, 14, 30, 32, 33, 38, 94]);
+        assert_eq!(plus, [0, 1, 2, 3, 11, 27, 37, 41, 73, 77]);
+        assert!(found.windows(2).all(|w| w[0] < w[1]));
+    }
+
+    #[cfg(feature = "bigint")]
+    #[test]
+    fn primorial_primes_01() {
+        let found: Vec<(u64, i8)> = primorial_primes().take(15).collect();
+        let minus: Vec<u64> = found.iter().filter(|f| f.1 == -1).map(|f| f.0).collect();
+        let plus: Vec<u64> = found.iter().filter(|f| f.1 == 1).map(|f| f.0).collect();
+
+        assert_eq!(minus, [3, 5, 11, 13, 41, 89, 317, 337]);
+        assert_eq!(plus, [2, 3, 5, 7, 11, 31, 379]);
+
+        // Against the u64 primorials directly.
+        for &(p, sign) in found.iter().take_while(|f| f.0 <= 47) {
+            assert!(is_prime(
+                primorial(p).unwrap().wrapping_add_signed(sign as i64)
+            ));
+        }
+    }
+
+    #[cfg(feature = "bigint")]
+    #[test]
+    fn is_wagstaff_prime_01() {
+        let exponents: Vec<u64> = (0..800).filter(|&p| is_wagstaff_prime(p)).collect();
+
+        assert_eq!(
+            exponents,
+            [3, 5, 7, 11, 13, 17, 19, 23, 31, 43, 61, 79, 101, 127, 167, 191, 199, 313, 347, 701]
+        );
+
+        // Against the deterministic test where it fits in a u128.
+        for p in (64..128).filter(|&p| is_prime(p)) {
+            let w = ((1u128 << p) + 1) / 3;
+
+            assert_eq!(is_wagstaff_prime(p), is_prime_u128(w), "{p}");
+        }
+    }
+
+    #[cfg(feature = "bigint")]
+    #[test]
+    fn repunit_01() {
+        assert_eq!(repunit(0, 10), BigUint::zero());
+        assert_eq!(repunit(5, 10), BigUint::from(11_111u32));
+        assert_eq!(repunit(4, 2), BigUint::from(15u32));
+        assert_eq!(repunit(3, 36), BigUint::from(36u32 * 36 + 36 + 1));
+
+        // The list and the Mersenne exponents against testing the repunits themselves.
+        for n in 0..250 {
+            for base in [2, 10] {
+                assert_eq!(
+                    is_repunit_prime(n, base),
+                    is_prime_biguint(&repunit(n, base)),
+                    "R_{n} in base {base}"
+                );
+            }
+        }
+
+        let base_3: Vec<u64> = (0..200).filter(|&n| is_repunit_prime(n, 3)).collect();
+
+        assert_eq!(base_3, [3, 7, 13, 71, 103]);
+        assert!(is_repunit_prime(1031, 10) && !is_repunit_prime(1021, 10));
+    }
+
+    #[test]
+    fn pierpont_primes_upto_01() {
+        assert_eq!(
+            pierpont_primes_upto(4000),
+            [
+                2, 3, 5, 7, 13, 17, 19, 37, 73, 97, 109, 163, 193, 257, 433, 487, 577, 769, 1153,
+                1297, 1459, 2593, 2917, 3457, 3889
+            ]
+        );
+        assert_eq!(pierpont_primes_upto(2), [2]);
+        assert!(pierpont_primes_upto(1).is_empty());
+
+        // Against factoring p − 1 for every prime.
+        let expected: Vec<u64> = sieve_primes(100_000)
+            .into_iter()
+            .filter(|&p| {
+                let mut m = p - 1;
+
+                for q in [2, 3] {
+                    while m.is_multiple_of(q) {
+                        m /= q;
+                    }
+                }
+
+                m == 1
+            })
+            .collect();
+
+        assert_eq!(pierpont_primes_upto(100_000), expected);
+
+        let all = pierpont_primes_upto(u64::MAX);
+
+        assert!(all.windows(2).all(|w| w[0] < w[1]));
+        assert!(all.iter().all(|&p| is_prime(p)));
+    }
+
+    #[test]
+    fn wieferich_primes_upto_01() {
+        assert_eq!(wieferich_primes_upto(1_000_000), [1093, 3511]);
+        assert!(wieferich_primes_upto(1000).is_empty());
+        assert_eq!(wieferich_primes_upto_base(3, 2_000_000), [11, 1_006_003]);
+        assert_eq!(wieferich_primes_upto_base(5, 50_000), [2, 20_771, 40_487]);
+        assert_eq!(wieferich_primes_upto_base(10, 1000), [3, 487]);
+
+        // Against the definition for small primes, writing a^(p−1) as a u128.
+        for a in 2..12u64 {
+            let expected: Vec<u64> = sieve_primes(30)
+                .into_iter()
+                .filter(|&p| {
+                    !a.is_multiple_of(p)
+                        && (a as u128).pow(p as u32 - 1) % (p as u128 * p as u128) == 1
+                })
+                .collect();
+
+            assert_eq!(wieferich_primes_upto_base(a, 30), expected, "{a}");
+        }
+    }
+
+    #[cfg(feature = "bigint")]
+    #[test]
+    fn wilson_primes_upto_01() {
+        assert_eq!(wilson_primes_upto(20_000), [5, 13, 563]);
+        assert!(wilson_primes_upto(4).is_empty());
+
+        // (p − 1)! mod p^2 by the plain product, for p = 2 as well.
+        let expected: Vec<u64> = sieve_primes(1000)
+            .into_iter()
+            .filter(|&p| {
+                let m = p * p;
+
+                (1..p).fold(1, |f, k| mul_mod(f, k, m)) == m - 1
+            })
+            .collect();
+
+        assert_eq!(wilson_primes_upto(1000), expected);
+    }
+}
@@ -0,0 +1,122 @@
+use crate::Factorization;
+
+/// The smallest prime factor of every integer up to a limit, from a linear sieve, for
+/// factoring any `n` up to the limit in `O(log n)` steps.
+#[derive(Clone, Debug)]
+pub struct SpfSieve {
+    // spf[n] is the smallest prime factor of n, and 0 for n < 2.
+    spf: Vec<u32>,
+}
+
+impl SpfSieve {
+    /// Sieves every integer `<= limit`, which must fit in a `u32`.
+    pub fn new(limit: u64) -> SpfSieve {
+        assert!(limit <= u32::MAX as u64, "limit must fit in a u32");
+
+        let size = limit as usize + 1;
+        let mut spf = vec![0u32; size];
+        let mut primes: Vec<u32> = vec![];
+
+        for i in 2..size {
+            if spf[i] == 0 {
+                spf[i] = i as u32;
+                primes.push(i as u32);
+            }
+
+            // Each composite i p is crossed off once, by its smallest prime factor p.
+            for &p in &primes {
+                let j = i * p as usize;
+
+                if p > spf[i] || j >= size {
+                    break;
+                }
+
+                spf[j] = p;
+            }
+        }
+
+        SpfSieve { spf }
+    }
+
+    pub fn limit(&self) -> u64 {
+        self.spf.len() as u64 - 1
+    }
+
+    fn check(&self, n: u64) {
+        assert!(n != 0, "0 has no factorization");
+        assert!(n <= self.limit(), "{n} is past the sieve limit");
+    }
+
+    /// The smallest prime factor of `n`, or 1 if `n == 1`.
+    pub fn smallest_prime_factor(&self, n: u64) -> u64 {
+        self.check(n);
+
+        if n == 1 {
+            1
+        } else {
+            self.spf[n as usize] as u64
+        }
+    }
+
+    pub fn is_prime(&self, n: u64) -> bool {
+        n >= 2 && self.smallest_prime_factor(n) == n
+    }
+
+    pub fn factorize(&self, n: u64) -> Factorization {
+        self.check(n);
+
+        let mut factorization = Factorization::new();
+        let mut n = n as usize;
+
+        while n > 1 {
+            let p = self.spf[n] as usize;
+            let mut e = 0;
+
+            while n.is_multiple_of(p) {
+                n /= p;
+                e += 1;
+            }
+
+            factorization.insert(p as u64, e);
+        }
+
+        factorization
+    }
+
+    /// The Möbius function μ(n).
+    pub fn mobius(&self, n: u64) -> i64 {
+        self.factorize(n)
+            .iter()
+            .fold(1, |acc, &(_, e)| if e == 1 { -acc } else { 0 })
+    }
+
+    /// Euler's totient φ(n).
+    pub fn totient(&self, n: u64) -> u64 {
+        self.factorize(n).totient().unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{factorization, is_prime, Mobius, MultiplicativeFunction, Totient};
+
+    #[test]
+    fn spf_sieve_01() {
+        let sieve = SpfSieve::new(10_000);
+
+        assert_eq!(sieve.limit(), 10_000);
+        assert_eq!(sieve.smallest_prime_factor(1), 1);
+        assert_eq!(sieve.smallest_prime_factor(9991), 97);
+
+        for n in 1..=10_000 {
+            assert_eq!(sieve.is_prime(n), is_prime(n));
+            assert_eq!(sieve.factorize(n), factorization(n));
+            assert_eq!(sieve.mobius(n), Mobius.evaluate(n));
+            assert_eq!(sieve.totient(n), Totient.evaluate(n));
+        }
+
+        assert!(SpfSieve::new(1).factorize(1).is_empty());
+        assert!(!SpfSieve::new(0).is_prime(0));
+    }
+}